@@ -0,0 +1,269 @@
+//! Derive macros for xmlop-datatypes.
+//!
+//! Use the re-exports from `xmlop_datatypes` (with `derive` feature) instead
+//! of depending on this crate directly.
+#![warn(missing_docs)]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro2::TokenStream;
+use syn::{Data, DeriveInput, Fields, Ident, LitStr};
+
+mod phf;
+
+/// Derives `xmlop_datatypes::names::XmlNames` for fieldless enums.
+///
+/// See the documentation of `xmlop_datatypes::names::XmlNames`.
+#[proc_macro_derive(XmlNames, attributes(xml))]
+pub fn derive_xml_names(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    xml_names(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Namespace and prefix of names.
+#[derive(Default, Clone)]
+struct NamespaceAttrs {
+    /// Namespace name.
+    namespace: Option<LitStr>,
+    /// Namespace prefix used by the QName.
+    prefix: Option<LitStr>,
+}
+
+/// Variant with its XML name.
+struct NamedVariant {
+    /// Variant identifier.
+    ident: Ident,
+    /// Local name.
+    name: LitStr,
+    /// Namespace and prefix.
+    ns: NamespaceAttrs,
+}
+
+impl NamedVariant {
+    /// Returns the QName string.
+    fn qname(&self) -> LitStr {
+        match self.ns.prefix {
+            Some(ref prefix) => LitStr::new(
+                &format!("{}:{}", prefix.value(), self.name.value()),
+                self.name.span(),
+            ),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Parses the `#[xml(..)]` attributes into `ns`, and returns the `name` if any.
+fn parse_attrs(attrs: &[syn::Attribute], ns: &mut NamespaceAttrs) -> syn::Result<Option<LitStr>> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("xml")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("namespace") {
+                let namespace: LitStr = meta.value()?.parse()?;
+                if namespace.value().is_empty() {
+                    // An empty namespace name cannot be bound to a prefix, and
+                    // means no namespace for the default namespace.
+                    return Err(syn::Error::new(
+                        namespace.span(),
+                        "`namespace` should not be empty, use `no_namespace` instead",
+                    ));
+                }
+                ns.namespace = Some(namespace);
+            } else if meta.path.is_ident("prefix") {
+                ns.prefix = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("no_namespace") {
+                *ns = NamespaceAttrs::default();
+            } else {
+                return Err(meta.error("expected `name`, `namespace`, `prefix` or `no_namespace`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(name)
+}
+
+/// Collects the variants with their names.
+fn named_variants(input: &DeriveInput) -> syn::Result<Vec<NamedVariant>> {
+    let data = match input.data {
+        Data::Enum(ref data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`XmlNames` can only be derived for enums",
+            ))
+        },
+    };
+    let mut default_ns = NamespaceAttrs::default();
+    if parse_attrs(&input.attrs, &mut default_ns)?.is_some() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`name` should be specified for each variant",
+        ));
+    }
+
+    let mut variants: Vec<NamedVariant> = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`XmlNames` variants should not have fields",
+            ));
+        }
+        let mut ns = default_ns.clone();
+        let name = parse_attrs(&variant.attrs, &mut ns)?.ok_or_else(|| {
+            syn::Error::new_spanned(variant, "missing `#[xml(name = \"...\")]` attribute")
+        })?;
+        if ns.prefix.is_some() && ns.namespace.is_none() {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`prefix` requires `namespace`",
+            ));
+        }
+        if let Some(other) = variants.iter().find(|v| v.name.value() == name.value()) {
+            return Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "local name {:?} is already used by `{}`",
+                    name.value(),
+                    other.ident
+                ),
+            ));
+        }
+        variants.push(NamedVariant {
+            ident: variant.ident.clone(),
+            name,
+            ns,
+        });
+    }
+    Ok(variants)
+}
+
+/// Generates the `XmlNames` impl.
+fn xml_names(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let variants = named_variants(input)?;
+    let krate = quote!(::xmlop_datatypes);
+    let private = quote!(#krate::__private);
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let idents: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let names: Vec<_> = variants.iter().map(|v| &v.name).collect();
+    let qnames: Vec<_> = variants.iter().map(NamedVariant::qname).collect();
+    let namespaces: Vec<_> = variants
+        .iter()
+        .map(|v| match v.ns.namespace {
+            Some(ref ns) => quote!(#private::Option::Some(#ns)),
+            None => quote!(#private::Option::None),
+        })
+        .collect();
+    let indices: Vec<_> = (0..variants.len()).collect();
+    // Separate constants spanned at the names, including the paths, let the
+    // compile errors point to the names.
+    let checks: Vec<_> = names
+        .iter()
+        .zip(&qnames)
+        .map(|(name, qname)| {
+            quote_spanned! {name.span()=>
+                const _: () = ::xmlop_datatypes::__private::check_literal(
+                    #name,
+                    ::xmlop_datatypes::__private::LiteralKind::Ncname,
+                );
+                const _: () = ::xmlop_datatypes::__private::check_literal(
+                    #qname,
+                    ::xmlop_datatypes::__private::LiteralKind::Qname,
+                );
+            }
+        })
+        .collect();
+
+    let keys: Vec<String> = variants.iter().map(|v| v.name.value()).collect();
+    let table = phf::Table::build(&keys);
+    let seed = table.seed;
+    let displacements = &table.displacements;
+    let num_displacements = displacements.len();
+    let slots = &table.slots;
+    let num_slots = slots.len();
+    let num_variants = variants.len();
+
+    Ok(quote! {
+        #( #checks )*
+
+        #[automatically_derived]
+        // The code for empty enums is unreachable and has one-slot tables.
+        #[allow(unreachable_code, clippy::modulo_one)]
+        impl #impl_generics #krate::names::XmlNames for #ty #ty_generics #where_clause {
+            fn as_ncname(&self) -> &'static #krate::strings::NcnameStr {
+                let s = match *self {
+                    #( #ty::#idents => #names, )*
+                };
+                // This is safe because the names are checked at compile time.
+                unsafe { #krate::strings::NcnameStr::from_str_unchecked(s) }
+            }
+
+            fn as_qname(&self) -> &'static #krate::strings::QnameStr {
+                let s = match *self {
+                    #( #ty::#idents => #qnames, )*
+                };
+                // This is safe because the names are checked at compile time.
+                unsafe { #krate::strings::QnameStr::from_str_unchecked(s) }
+            }
+
+            fn namespace(&self) -> #private::Option<&'static str> {
+                match *self {
+                    #( #ty::#idents => #namespaces, )*
+                }
+            }
+
+            fn from_ncname(name: &#krate::strings::NcnameStr) -> #private::Option<Self> {
+                const NAMES: [&str; #num_variants] = [#( #names ),*];
+                const DISPLACEMENTS: [u32; #num_displacements] = [#( #displacements ),*];
+                const SLOTS: [usize; #num_slots] = [#( #slots ),*];
+
+                let s = name.as_str();
+                let bucket = #private::hash_name(s, #seed) as usize % #num_displacements;
+                let slot = #private::hash_name(s, DISPLACEMENTS[bucket]) as usize % #num_slots;
+                let index = SLOTS[slot];
+                if index >= #num_variants || NAMES[index] != s {
+                    return #private::Option::None;
+                }
+                match index {
+                    #( #indices => #private::Option::Some(#ty::#idents), )*
+                    _ => #private::Option::None,
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject_empty_namespace() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[xml(namespace = "")]
+            enum Name {
+                #[xml(name = "a")]
+                A,
+            }
+        };
+        let err = named_variants(&input).err().expect("Should fail");
+        assert!(err.to_string().contains("should not be empty"));
+
+        let input: DeriveInput = syn::parse_quote! {
+            enum Name {
+                #[xml(name = "a", namespace = "", prefix = "p")]
+                A,
+            }
+        };
+        assert!(named_variants(&input).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Perfect hash table construction.
+//!
+//! The table uses the hash-and-displace scheme: the first hash with the global
+//! seed selects a bucket, and the second hash with the per-bucket displacement
+//! selects a slot.
+
+/// Hashes the name with the seed.
+///
+/// This should be identical to `xmlop_datatypes::__private::hash_name`, and
+/// the tests of both crates check the same hashes.
+pub fn hash_name(s: &str, seed: u32) -> u32 {
+    let mut hash = 0x811c_9dc5 ^ seed;
+    for &b in s.as_bytes() {
+        hash ^= u32::from(b);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash
+}
+
+/// Average number of keys in a bucket.
+const KEYS_PER_BUCKET: usize = 4;
+/// Maximum number of displacements tried for a bucket.
+const MAX_DISPLACEMENT_TRIES: u32 = 1 << 16;
+
+/// Perfect hash table.
+#[derive(Debug, Clone)]
+pub struct Table {
+    /// Seed of the bucket hash.
+    pub seed: u32,
+    /// Displacement for each bucket.
+    pub displacements: Vec<u32>,
+    /// Key index for each slot, or the number of keys for empty slots.
+    pub slots: Vec<usize>,
+}
+
+impl Table {
+    /// Builds the table for the given distinct keys.
+    pub fn build(keys: &[String]) -> Self {
+        let num_buckets = keys.len().div_ceil(KEYS_PER_BUCKET).max(1);
+        let num_slots = keys.len().max(1);
+        (0..)
+            .filter_map(|seed| Self::try_build(keys, seed, num_buckets, num_slots))
+            .next()
+            .expect("Should never fail: some seed should succeed")
+    }
+
+    /// Tries to build the table with the given seed.
+    fn try_build(keys: &[String], seed: u32, num_buckets: usize, num_slots: usize) -> Option<Self> {
+        let mut buckets = vec![Vec::new(); num_buckets];
+        for (index, key) in keys.iter().enumerate() {
+            buckets[hash_name(key, seed) as usize % num_buckets].push(index);
+        }
+        // Place larger buckets first, while many slots are free.
+        let mut order: Vec<usize> = (0..num_buckets).collect();
+        order.sort_by_key(|&bucket| ::std::cmp::Reverse(buckets[bucket].len()));
+
+        let mut displacements = vec![0; num_buckets];
+        let mut slots = vec![keys.len(); num_slots];
+        let mut bucket_slots = Vec::new();
+        for bucket in order {
+            let placed = (0..MAX_DISPLACEMENT_TRIES).find(|&displacement| {
+                bucket_slots.clear();
+                for &index in &buckets[bucket] {
+                    let slot = hash_name(&keys[index], displacement) as usize % num_slots;
+                    if slots[slot] != keys.len() || bucket_slots.contains(&slot) {
+                        return false;
+                    }
+                    bucket_slots.push(slot);
+                }
+                true
+            })?;
+            displacements[bucket] = placed;
+            for (&index, &slot) in buckets[bucket].iter().zip(&bucket_slots) {
+                slots[slot] = index;
+            }
+        }
+        Some(Self {
+            seed,
+            displacements,
+            slots,
+        })
+    }
+
+    /// Returns the slot value for the key.
+    #[cfg(test)]
+    fn lookup(&self, key: &str) -> usize {
+        let bucket = hash_name(key, self.seed) as usize % self.displacements.len();
+        let slot = hash_name(key, self.displacements[bucket]) as usize % self.slots.len();
+        self.slots[slot]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hashes to be produced by both this crate and `xmlop-datatypes`.
+    ///
+    /// Keep in sync with the test in `xmlop_datatypes::names`.
+    const HASHES: [(&str, u32, u32); 5] = [
+        ("", 0, 0x5db4_a501),
+        ("svg", 0, 0xb88f_7b77),
+        ("xlink:href", 1, 0x57bc_137a),
+        ("viewBox", 42, 0x90e9_c2cf),
+        ("\u{E9}l\u{E9}ment", 0xdead_beef, 0x75fe_97fe),
+    ];
+
+    #[test]
+    fn hash_name_matches_datatypes() {
+        for &(s, seed, hash) in &HASHES {
+            assert_eq!(hash_name(s, seed), hash, "{:?} with seed {}", s, seed);
+        }
+    }
+
+    #[test]
+    fn perfect_hash() {
+        for &len in &[0, 1, 2, 7, 100, 1000] {
+            let keys: Vec<String> = (0..len).map(|i| format!("name{}", i)).collect();
+            let table = Table::build(&keys);
+            for (index, key) in keys.iter().enumerate() {
+                assert_eq!(table.lookup(key), index, "{:?}", key);
+            }
+        }
+    }
+}
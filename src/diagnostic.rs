@@ -0,0 +1,328 @@
+//! Human-readable diagnostics for name errors.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use xmlop_datatypes::diagnostic::NameDiagnostic;
+//! # use xmlop_datatypes::position::Span;
+//! # use xmlop_datatypes::strings::{NameKind, NcnameStr};
+//! let source = "<a xmlns:1foo=\"urn:foo\"/>";
+//! let span = Span::new(9, 13);
+//! let err = NcnameStr::new(&source[span.range()]).unwrap_err();
+//! let diag = NameDiagnostic::new(source, span, NameKind::Ncname, err)
+//!     .with_suggestion(true)
+//!     .with_spec_link(true);
+//! assert_eq!(
+//!     diag.to_string(),
+//!     "error: invalid NCName `1foo`\n \
+//!      --> line 1, column 10\n  \
+//!       |\n\
+//!      1 | <a xmlns:1foo=\"urn:foo\"/>\n  \
+//!       |          ^\n  \
+//!       = digits cannot start an NCName\n  \
+//!       = help: did you mean `_1foo`?\n  \
+//!       = see: https://www.w3.org/TR/REC-xml-names/#NT-NCName\n"
+//! );
+//! ```
+
+use std::borrow::ToOwned;
+use std::fmt;
+use std::string::{String, ToString};
+
+use position::{Span, TextPosition};
+use strings::{is_name_char, is_whitespace_char, NameError, NameKind};
+
+/// Returns the article for the production name.
+fn article(production: NameKind) -> &'static str {
+    match production {
+        NameKind::Ncname | NameKind::Nmtoken => "an",
+        _ => "a",
+    }
+}
+
+/// Explains why the name is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::diagnostic::explain;
+/// # use xmlop_datatypes::strings::{NameError, NameKind};
+/// assert_eq!(
+///     explain(NameKind::Ncname, &NameError::InvalidNameChar(3, ':')),
+///     "':' is not allowed in NCName"
+/// );
+/// assert_eq!(
+///     explain(NameKind::Name, &NameError::InvalidNameChar(0, '1')),
+///     "digits cannot start a Name"
+/// );
+/// ```
+pub fn explain(production: NameKind, error: &NameError) -> String {
+    match *error {
+        NameError::Empty => format!("{} cannot be empty", production),
+        NameError::InvalidNameChar(_, ':') if production == NameKind::Ncname => {
+            "':' is not allowed in NCName".to_owned()
+        },
+        NameError::InvalidNameChar(0, ':') if production == NameKind::Qname => {
+            "QName cannot start with ':'".to_owned()
+        },
+        NameError::InvalidNameChar(_, ':') if production == NameKind::Qname => {
+            "QName can have only one ':' followed by a local part".to_owned()
+        },
+        NameError::InvalidNameChar(_, c) if is_whitespace_char(c) => {
+            format!("whitespace is not allowed in {}", production)
+        },
+        NameError::InvalidNameChar(0, c) if c.is_ascii_digit() => {
+            format!("digits cannot start {} {}", article(production), production)
+        },
+        NameError::InvalidNameChar(pos, c) if is_name_char(c) && c != ':' => {
+            let part = if pos == 0 {
+                format!("{} {}", article(production), production)
+            } else {
+                "the local part of QName".to_owned()
+            };
+            format!("{:?} cannot start {}", c, part)
+        },
+        NameError::InvalidNameChar(_, c) => format!(
+            "{:?} (U+{:04X}) is not allowed in {}",
+            c, c as u32, production
+        ),
+        NameError::LimitExceeded(limit) => format!("{} is too long: {}", production, limit),
+        NameError::ReservedName => format!("the {} is reserved", production),
+        NameError::UnpairedSurrogate(_) => {
+            format!("{} has an unpaired UTF-16 surrogate", production)
+        },
+    }
+}
+
+/// Diagnostic for an invalid name in source text.
+///
+/// The `Display` implementation renders the offending line with a caret under
+/// the invalid character.
+#[derive(Debug, Clone)]
+pub struct NameDiagnostic<'a> {
+    /// Source text.
+    source: &'a str,
+    /// Span of the name in the source.
+    span: Span,
+    /// Production which is violated.
+    production: NameKind,
+    /// Error.
+    error: NameError,
+    /// Whether to suggest a corrected name.
+    suggestion: bool,
+    /// Whether to link to the spec.
+    spec_link: bool,
+}
+
+impl<'a> NameDiagnostic<'a> {
+    /// Creates a new `NameDiagnostic`.
+    ///
+    /// Positions in the error are relative to the start of `span`.
+    /// If the position is not in the span, the whole span is marked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is out of bounds of the source.
+    pub fn new(source: &'a str, span: Span, production: NameKind, error: NameError) -> Self {
+        assert!(
+            span.end() <= source.len(),
+            "Span should be in the source: span={:?}, len={}",
+            span,
+            source.len()
+        );
+        Self {
+            source,
+            span,
+            production,
+            error,
+            suggestion: false,
+            spec_link: false,
+        }
+    }
+
+    /// Sets whether to suggest a corrected name.
+    pub fn with_suggestion(mut self, suggestion: bool) -> Self {
+        self.suggestion = suggestion;
+        self
+    }
+
+    /// Sets whether to link to the spec production.
+    pub fn with_spec_link(mut self, spec_link: bool) -> Self {
+        self.spec_link = spec_link;
+        self
+    }
+
+    /// Returns the error.
+    pub fn error(&self) -> &NameError {
+        &self.error
+    }
+
+    /// Returns the production.
+    pub fn production(&self) -> NameKind {
+        self.production
+    }
+
+    /// Returns the invalid name.
+    pub fn name(&self) -> &'a str {
+        &self.source[self.span.range()]
+    }
+
+    /// Returns the span to be marked.
+    ///
+    /// If the error position does not point to a character in the span, the
+    /// whole span is marked.
+    fn marked_span(&self) -> Span {
+        match self.error {
+            NameError::InvalidNameChar(pos, c) => {
+                let start = self.span.start().saturating_add(pos);
+                let end = start.saturating_add(c.len_utf8());
+                if end <= self.span.end()
+                    && self.source.is_char_boundary(start)
+                    && self.source.is_char_boundary(end)
+                {
+                    Span::new(start, end)
+                } else {
+                    self.span
+                }
+            },
+            _ => self.span,
+        }
+    }
+
+    /// Returns the position of the marked span.
+    pub fn position(&self) -> TextPosition {
+        TextPosition::from_offset(self.source, self.marked_span().start())
+    }
+}
+
+impl<'a> fmt::Display for NameDiagnostic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let marked = self.marked_span();
+        let pos = self.position();
+        let line_start = self.source[..marked.start()]
+            .rfind(['\n', '\r'])
+            .map_or(0, |i| i + 1);
+        let line_end = self.source[line_start..]
+            .find(['\n', '\r'])
+            .map_or(self.source.len(), |len| line_start + len);
+        let line = &self.source[line_start..line_end];
+        let gutter = pos.line().to_string().len();
+
+        writeln!(
+            f,
+            "error: invalid {} `{}`",
+            self.production,
+            self.name().escape_debug()
+        )?;
+        writeln!(f, "{:w$}--> {}", "", pos, w = gutter)?;
+        writeln!(f, "{:w$} |", "", w = gutter)?;
+        writeln!(f, "{} | {}", pos.line(), line)?;
+        write!(f, "{:w$} | ", "", w = gutter)?;
+        for c in self.source[line_start..marked.start()].chars() {
+            f.write_str(if c == '\t' { "\t" } else { " " })?;
+        }
+        let marked_end = marked.end().min(line_end);
+        let carets = self.source[marked.start()..marked_end]
+            .chars()
+            .count()
+            .max(1);
+        writeln!(f, "{}", "^".repeat(carets))?;
+        writeln!(
+            f,
+            "{:w$} = {}",
+            "",
+            explain(self.production, &self.error),
+            w = gutter
+        )?;
+        if self.suggestion {
+            if let Some(suggestion) = self.production.suggest(self.name()) {
+                writeln!(
+                    f,
+                    "{:w$} = help: did you mean `{}`?",
+                    "",
+                    suggestion.escape_debug(),
+                    w = gutter
+                )?;
+            }
+        }
+        if self.spec_link {
+            writeln!(
+                f,
+                "{:w$} = see: {}",
+                "",
+                self.production.spec_url(),
+                w = gutter
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strings::QnameStr;
+
+    #[test]
+    fn render_qname_error() {
+        let source = "<root>\n\t<a:b:c/>\n</root>";
+        let span = Span::new(9, 14);
+        let err = QnameStr::new(&source[span.range()]).expect_err("Should fail");
+        let diag = NameDiagnostic::new(source, span, NameKind::Qname, err).with_suggestion(true);
+        assert_eq!(
+            diag.to_string(),
+            "error: invalid QName `a:b:c`\n \
+             --> line 2, column 6\n  \
+             |\n\
+             2 | \t<a:b:c/>\n  \
+             | \t    ^\n  \
+             = QName can have only one ':' followed by a local part\n  \
+             = help: did you mean `a:b_c`?\n"
+        );
+    }
+
+    #[test]
+    fn mark_whole_span_for_inconsistent_position() {
+        let source = "<\u{E9}t\u{E9}/>";
+        let span = Span::new(1, 6);
+        let expected = |column: usize, marker: &str| {
+            format!(
+                "error: invalid Name `\u{E9}t\u{E9}`\n \
+                 --> line 1, column {}\n  \
+                 |\n\
+                 1 | <\u{E9}t\u{E9}/>\n  \
+                 | {}\n  \
+                 = '@' (U+0040) is not allowed in Name\n",
+                column, marker
+            )
+        };
+        for &pos in &[1, 3, 5, 100, usize::MAX] {
+            let err = NameError::InvalidNameChar(pos, '@');
+            let diag = NameDiagnostic::new(source, span, NameKind::Name, err);
+            assert_eq!(diag.to_string(), expected(2, " ^^^"), "pos={}", pos);
+        }
+        let err = NameError::InvalidNameChar(2, '@');
+        let diag = NameDiagnostic::new(source, span, NameKind::Name, err);
+        assert_eq!(diag.to_string(), expected(3, "  ^"));
+    }
+
+    #[test]
+    fn explain_errors() {
+        assert_eq!(
+            explain(NameKind::Name, &NameError::InvalidNameChar(0, '-')),
+            "'-' cannot start a Name"
+        );
+        assert_eq!(
+            explain(NameKind::Qname, &NameError::InvalidNameChar(2, '-')),
+            "'-' cannot start the local part of QName"
+        );
+        assert_eq!(
+            explain(NameKind::Nmtoken, &NameError::InvalidNameChar(1, ' ')),
+            "whitespace is not allowed in Nmtoken"
+        );
+        assert_eq!(
+            explain(NameKind::Name, &NameError::InvalidNameChar(1, '@')),
+            "'@' (U+0040) is not allowed in Name"
+        );
+    }
+}
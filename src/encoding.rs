@@ -0,0 +1,522 @@
+//! Encoding detection.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#sec-guessing>.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+use std::string::String;
+
+use strings::{is_whitespace_char, EncNameStr, EncNameString};
+use syntax::PseudoAttributes;
+
+/// Byte order of UCS-4.
+///
+/// The digits are the order of the bytes, from the most significant (`1`) to
+/// the least significant (`4`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Ucs4Order {
+    /// Big-endian.
+    Order1234,
+    /// Little-endian.
+    Order4321,
+    /// Unusual octet order.
+    Order2143,
+    /// Unusual octet order.
+    Order3412,
+}
+
+impl Ucs4Order {
+    /// Returns the byte positions of the most significant to the least
+    /// significant bytes.
+    fn positions(self) -> [usize; 4] {
+        match self {
+            Ucs4Order::Order1234 => [0, 1, 2, 3],
+            Ucs4Order::Order4321 => [3, 2, 1, 0],
+            Ucs4Order::Order2143 => [1, 0, 3, 2],
+            Ucs4Order::Order3412 => [2, 3, 0, 1],
+        }
+    }
+}
+
+/// Encoding family detected from the first bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DetectedEncoding {
+    /// UTF-8.
+    Utf8,
+    /// UTF-16, big-endian.
+    Utf16Be,
+    /// UTF-16, little-endian.
+    Utf16Le,
+    /// UCS-4 (UTF-32).
+    Ucs4(Ucs4Order),
+    /// Some ASCII-compatible encoding, which is specified by the declaration.
+    AsciiCompatible,
+    /// Some EBCDIC encoding, which is specified by the declaration.
+    Ebcdic,
+}
+
+impl DetectedEncoding {
+    /// Returns the code unit width in bytes for ASCII characters.
+    fn unit_len(self) -> usize {
+        match self {
+            DetectedEncoding::Utf16Be | DetectedEncoding::Utf16Le => 2,
+            DetectedEncoding::Ucs4(_) => 4,
+            _ => 1,
+        }
+    }
+
+    /// Decodes an ASCII character from the code unit.
+    fn decode_ascii(self, unit: &[u8]) -> Option<char> {
+        let (value, rest_zero) = match self {
+            DetectedEncoding::Utf16Be => (unit[1], unit[0] == 0),
+            DetectedEncoding::Utf16Le => (unit[0], unit[1] == 0),
+            DetectedEncoding::Ucs4(order) => {
+                let [b1, b2, b3, b4] = order.positions();
+                (unit[b4], unit[b1] == 0 && unit[b2] == 0 && unit[b3] == 0)
+            },
+            DetectedEncoding::Ebcdic => return decode_ebcdic_invariant(unit[0]),
+            DetectedEncoding::Utf8 | DetectedEncoding::AsciiCompatible => (unit[0], true),
+        };
+        if rest_zero && value.is_ascii() {
+            Some(char::from(value))
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether the declared encoding name is compatible.
+    fn is_compatible_with(self, name: &EncNameStr) -> bool {
+        let name = name.as_str().to_ascii_uppercase();
+        let is_utf16 = matches!(
+            name.as_str(),
+            "UTF-16" | "UTF-16BE" | "UTF-16LE" | "ISO-10646-UCS-2" | "UCS-2"
+        );
+        let is_ucs4 = matches!(
+            name.as_str(),
+            "UTF-32" | "UTF-32BE" | "UTF-32LE" | "ISO-10646-UCS-4" | "UCS-4"
+        );
+        match self {
+            DetectedEncoding::Utf8 => name == "UTF-8",
+            DetectedEncoding::Utf16Be => is_utf16 && name != "UTF-16LE",
+            DetectedEncoding::Utf16Le => is_utf16 && name != "UTF-16BE",
+            DetectedEncoding::Ucs4(order) => {
+                is_ucs4
+                    && match name.as_str() {
+                        "UTF-32BE" => order == Ucs4Order::Order1234,
+                        "UTF-32LE" => order == Ucs4Order::Order4321,
+                        _ => true,
+                    }
+            },
+            DetectedEncoding::AsciiCompatible => !is_utf16 && !is_ucs4,
+            DetectedEncoding::Ebcdic => !is_utf16 && !is_ucs4 && name != "UTF-8",
+        }
+    }
+
+    /// Returns the canonical encoding name if the family is a single encoding.
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            DetectedEncoding::Utf8 => Some("UTF-8"),
+            DetectedEncoding::Utf16Be => Some("UTF-16BE"),
+            DetectedEncoding::Utf16Le => Some("UTF-16LE"),
+            DetectedEncoding::Ucs4(Ucs4Order::Order1234) => Some("UTF-32BE"),
+            DetectedEncoding::Ucs4(Ucs4Order::Order4321) => Some("UTF-32LE"),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes an EBCDIC character which is the same in all EBCDIC code pages and
+/// may appear in an XML declaration.
+fn decode_ebcdic_invariant(b: u8) -> Option<char> {
+    let c = match b {
+        0x05 => '\t',
+        0x0D => '\r',
+        0x15 | 0x25 => '\n',
+        0x40 => ' ',
+        0x4B => '.',
+        0x4C => '<',
+        0x60 => '-',
+        0x6D => '_',
+        0x6E => '>',
+        0x6F => '?',
+        0x7A => ':',
+        0x7D => '\'',
+        0x7E => '=',
+        0x7F => '"',
+        0x81..=0x89 => char::from(b'a' + (b - 0x81)),
+        0x91..=0x99 => char::from(b'j' + (b - 0x91)),
+        0xA2..=0xA9 => char::from(b's' + (b - 0xA2)),
+        0xC1..=0xC9 => char::from(b'A' + (b - 0xC1)),
+        0xD1..=0xD9 => char::from(b'J' + (b - 0xD1)),
+        0xE2..=0xE9 => char::from(b'S' + (b - 0xE2)),
+        0xF0..=0xF9 => char::from(b'0' + (b - 0xF0)),
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// Encoding detection error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EncodingError {
+    /// The byte order mark disagrees with the byte pattern of `<?xml`.
+    BomPatternMismatch,
+    /// The declared encoding is incompatible with the detected encoding.
+    DeclarationMismatch(DetectedEncoding, EncNameString),
+    /// The document is not in UTF-8 or UTF-16 with BOM, but has no encoding
+    /// declaration.
+    MissingDeclaration(DetectedEncoding),
+    /// The XML declaration is malformed or incomplete.
+    MalformedDeclaration,
+    /// The document declares `UTF-16` but has no byte order mark.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#charencoding>.
+    MissingBom(DetectedEncoding),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for EncodingError {}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodingError::BomPatternMismatch => {
+                f.write_str("Byte order mark disagrees with the XML declaration bytes")
+            },
+            EncodingError::DeclarationMismatch(detected, declared) => write!(
+                f,
+                "Declared encoding `{}` is incompatible with the detected encoding {:?}",
+                declared, detected
+            ),
+            EncodingError::MissingDeclaration(detected) => write!(
+                f,
+                "Encoding declaration is required for the detected encoding {:?}",
+                detected
+            ),
+            EncodingError::MalformedDeclaration => {
+                f.write_str("XML declaration is malformed or incomplete")
+            },
+            EncodingError::MissingBom(detected) => write!(
+                f,
+                "Byte order mark is required for `UTF-16` in the detected encoding {:?}",
+                detected
+            ),
+        }
+    }
+}
+
+/// Result of encoding detection.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SniffedEncoding {
+    /// Detected encoding family.
+    detected: DetectedEncoding,
+    /// Length of the byte order mark.
+    bom_len: usize,
+    /// Declared encoding name.
+    declared: Option<EncNameString>,
+}
+
+impl SniffedEncoding {
+    /// Returns the detected encoding family.
+    pub fn detected(&self) -> DetectedEncoding {
+        self.detected
+    }
+
+    /// Returns the length of the byte order mark, or 0 if there is none.
+    pub fn bom_len(&self) -> usize {
+        self.bom_len
+    }
+
+    /// Returns the declared encoding name.
+    pub fn declared(&self) -> Option<&EncNameStr> {
+        self.declared.as_ref().map(|s| s.as_encname_str())
+    }
+
+    /// Returns the encoding name to decode the document with.
+    ///
+    /// This is the declared name if available, and otherwise the name of the
+    /// detected encoding.
+    pub fn encoding_name(&self) -> &str {
+        match (&self.declared, self.detected.name()) {
+            (Some(declared), _) => declared.as_str(),
+            (None, Some(name)) => name,
+            (None, None) => unreachable!("Should never fail: checked at detection"),
+        }
+    }
+}
+
+/// Detects the encoding of the document from its first bytes.
+///
+/// `bytes` should contain the whole XML declaration, if any.
+/// The detection follows Appendix F of the XML specification: the byte order
+/// mark and the byte pattern of `<?xml` determine the encoding family, and
+/// the encoding declaration determines the exact encoding.
+/// A document declared as `UTF-16` should start with the byte order mark.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::encoding::{sniff, DetectedEncoding, EncodingError};
+/// # use xmlop_datatypes::strings::EncNameString;
+/// let sniffed = sniff(b"<?xml version='1.0' encoding='Shift_JIS'?><a/>").unwrap();
+/// assert_eq!(sniffed.detected(), DetectedEncoding::AsciiCompatible);
+/// assert_eq!(sniffed.encoding_name(), "Shift_JIS");
+///
+/// let sniffed = sniff(b"\xFF\xFE<\0a\0/\0>\0").unwrap();
+/// assert_eq!(sniffed.detected(), DetectedEncoding::Utf16Le);
+/// assert_eq!(sniffed.bom_len(), 2);
+///
+/// assert_eq!(
+///     sniff(b"\xEF\xBB\xBF<?xml version='1.0' encoding='UTF-16'?>").map(|_| ()),
+///     Err(EncodingError::DeclarationMismatch(
+///         DetectedEncoding::Utf8,
+///         EncNameString::new("UTF-16".to_owned()).unwrap()
+///     ))
+/// );
+/// ```
+pub fn sniff(bytes: &[u8]) -> Result<SniffedEncoding, EncodingError> {
+    let bom = detect_bom(bytes);
+    let body = &bytes[bom.map_or(0, |(_, len)| len)..];
+    let pattern = detect_pattern(body);
+    let detected = match (bom, pattern) {
+        (Some((bom, _)), Some(pattern)) if !is_pattern_consistent(bom, pattern) => {
+            return Err(EncodingError::BomPatternMismatch)
+        },
+        (Some((bom, _)), _) => bom,
+        (None, Some(pattern)) => pattern,
+        (None, None) => DetectedEncoding::Utf8,
+    };
+    let declared = if pattern.is_some() {
+        read_declared_encoding(body, detected)?
+    } else {
+        None
+    };
+    let detected = match (&declared, detected) {
+        // An XML declaration without encoding declaration means UTF-8.
+        (None, DetectedEncoding::AsciiCompatible) => DetectedEncoding::Utf8,
+        (_, detected) => detected,
+    };
+    match &declared {
+        Some(declared) if !detected.is_compatible_with(declared) => {
+            return Err(EncodingError::DeclarationMismatch(
+                detected,
+                declared.clone(),
+            ))
+        },
+        Some(declared) if bom.is_none() && declared.eq_ignore_ascii_case("UTF-16") => {
+            return Err(EncodingError::MissingBom(detected))
+        },
+        None if detected.name().is_none()
+            || (bom.is_none() && detected != DetectedEncoding::Utf8) =>
+        {
+            return Err(EncodingError::MissingDeclaration(detected))
+        },
+        _ => {},
+    }
+    Ok(SniffedEncoding {
+        detected,
+        bom_len: bom.map_or(0, |(_, len)| len),
+        declared,
+    })
+}
+
+/// Detects the byte order mark and returns the encoding and the BOM length.
+fn detect_bom(bytes: &[u8]) -> Option<(DetectedEncoding, usize)> {
+    use self::DetectedEncoding::*;
+
+    let detected = match bytes {
+        [0x00, 0x00, 0xFE, 0xFF, ..] => (Ucs4(Ucs4Order::Order1234), 4),
+        [0xFF, 0xFE, 0x00, 0x00, ..] => (Ucs4(Ucs4Order::Order4321), 4),
+        [0x00, 0x00, 0xFF, 0xFE, ..] => (Ucs4(Ucs4Order::Order2143), 4),
+        [0xFE, 0xFF, 0x00, 0x00, ..] => (Ucs4(Ucs4Order::Order3412), 4),
+        [0xFE, 0xFF, ..] => (Utf16Be, 2),
+        [0xFF, 0xFE, ..] => (Utf16Le, 2),
+        [0xEF, 0xBB, 0xBF, ..] => (Utf8, 3),
+        _ => return None,
+    };
+    Some(detected)
+}
+
+/// Detects the encoding from the byte pattern of `<?xml`.
+fn detect_pattern(bytes: &[u8]) -> Option<DetectedEncoding> {
+    use self::DetectedEncoding::*;
+
+    let detected = match bytes.get(..4)? {
+        [0x00, 0x00, 0x00, 0x3C] => Ucs4(Ucs4Order::Order1234),
+        [0x3C, 0x00, 0x00, 0x00] => Ucs4(Ucs4Order::Order4321),
+        [0x00, 0x00, 0x3C, 0x00] => Ucs4(Ucs4Order::Order2143),
+        [0x00, 0x3C, 0x00, 0x00] => Ucs4(Ucs4Order::Order3412),
+        [0x00, 0x3C, 0x00, 0x3F] => Utf16Be,
+        [0x3C, 0x00, 0x3F, 0x00] => Utf16Le,
+        [0x3C, 0x3F, 0x78, 0x6D] => AsciiCompatible,
+        [0x4C, 0x6F, 0xA7, 0x94] => Ebcdic,
+        _ => return None,
+    };
+    Some(detected)
+}
+
+/// Checks whether the encoding detected from the byte pattern is consistent
+/// with the byte order mark.
+fn is_pattern_consistent(bom: DetectedEncoding, pattern: DetectedEncoding) -> bool {
+    match pattern {
+        DetectedEncoding::AsciiCompatible => bom == DetectedEncoding::Utf8,
+        pattern => bom == pattern,
+    }
+}
+
+/// Reads the encoding declared in the XML declaration at the beginning.
+fn read_declared_encoding(
+    bytes: &[u8],
+    detected: DetectedEncoding,
+) -> Result<Option<EncNameString>, EncodingError> {
+    let unit_len = detected.unit_len();
+    let mut decl = String::new();
+    for unit in bytes
+        .chunks(unit_len)
+        .take_while(|unit| unit.len() == unit_len)
+    {
+        match detected.decode_ascii(unit) {
+            Some(c) => decl.push(c),
+            None => break,
+        }
+        if decl.ends_with("?>") {
+            break;
+        }
+    }
+    let rest = match decl.strip_prefix("<?xml") {
+        // `<?xml` followed by a name character starts a processing instruction
+        // such as `<?xml-stylesheet?>`, not an XML declaration.
+        Some(rest) if rest.starts_with(|c| c == '?' || is_whitespace_char(c)) => rest,
+        _ => return Ok(None),
+    };
+    let data = match rest.strip_suffix("?>") {
+        Some(data) => data,
+        None => return Err(EncodingError::MalformedDeclaration),
+    };
+    for attr in PseudoAttributes::new(data) {
+        let (name, value) = attr.map_err(|_| EncodingError::MalformedDeclaration)?;
+        if name == "encoding" {
+            return EncNameString::new(value.into_owned())
+                .map(Some)
+                .map_err(|_| EncodingError::MalformedDeclaration);
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn utf16le(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .flat_map(|u| u.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    fn ucs4(s: &str, order: Ucs4Order) -> Vec<u8> {
+        s.chars()
+            .flat_map(|c| {
+                let be = (c as u32).to_be_bytes();
+                let pos = order.positions();
+                let mut bytes = [0; 4];
+                for (i, &p) in pos.iter().enumerate() {
+                    bytes[p] = be[i];
+                }
+                bytes.to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sniff_without_declaration() {
+        let sniffed = sniff(b"<root/>").expect("Should never fail");
+        assert_eq!(sniffed.detected(), DetectedEncoding::Utf8);
+        assert_eq!(sniffed.bom_len(), 0);
+        assert_eq!(sniffed.encoding_name(), "UTF-8");
+
+        let sniffed = sniff(b"<?xml version='1.0'?>").expect("Should never fail");
+        assert_eq!(sniffed.detected(), DetectedEncoding::Utf8);
+        assert_eq!(sniffed.declared(), None);
+
+        // Processing instruction with a target starting with `xml`.
+        let sniffed = sniff(b"<?xml-stylesheet href='a.xsl'?><r/>").expect("Should never fail");
+        assert_eq!(sniffed.detected(), DetectedEncoding::Utf8);
+        assert_eq!(sniffed.declared(), None);
+
+        let sniffed = sniff(b"\xFE\xFF\0<").expect("Should never fail");
+        assert_eq!(sniffed.detected(), DetectedEncoding::Utf16Be);
+        assert_eq!(sniffed.encoding_name(), "UTF-16BE");
+    }
+
+    #[test]
+    fn sniff_declaration() {
+        let mut doc = vec![0xFF, 0xFE];
+        doc.extend(utf16le("<?xml version=\"1.0\" encoding=\"UTF-16\"?><a/>"));
+        let sniffed = sniff(&doc).expect("Should never fail");
+        assert_eq!(sniffed.detected(), DetectedEncoding::Utf16Le);
+        assert_eq!(sniffed.declared().map(EncNameStr::as_str), Some("UTF-16"));
+        assert_eq!(
+            sniff(&doc[2..]),
+            Err(EncodingError::MissingBom(DetectedEncoding::Utf16Le))
+        );
+        let doc = utf16le("<?xml version=\"1.0\" encoding=\"utf-16le\"?><a/>");
+        let sniffed = sniff(&doc).expect("Should never fail");
+        assert_eq!(sniffed.detected(), DetectedEncoding::Utf16Le);
+
+        let doc = ucs4(
+            "<?xml version='1.0' encoding='UCS-4'?>",
+            Ucs4Order::Order2143,
+        );
+        let sniffed = sniff(&doc).expect("Should never fail");
+        assert_eq!(
+            sniffed.detected(),
+            DetectedEncoding::Ucs4(Ucs4Order::Order2143)
+        );
+        assert_eq!(sniffed.encoding_name(), "UCS-4");
+
+        // `<?xml version='1.0' encoding='IBM037'?>` in EBCDIC.
+        let doc = b"\x4C\x6F\xA7\x94\x93\x40\xA5\x85\x99\xA2\x89\x96\x95\x7E\x7D\xF1\x4B\xF0\x7D\
+                    \x40\x85\x95\x83\x96\x84\x89\x95\x87\x7E\x7D\xC9\xC2\xD4\xF0\xF3\xF7\x7D\x6F\x6E";
+        let sniffed = sniff(doc).expect("Should never fail");
+        assert_eq!(sniffed.detected(), DetectedEncoding::Ebcdic);
+        assert_eq!(sniffed.encoding_name(), "IBM037");
+    }
+
+    #[test]
+    fn sniff_conflicts() {
+        assert_eq!(
+            sniff(b"\xFF\xFE<?xml version='1.0'?>"),
+            Err(EncodingError::BomPatternMismatch)
+        );
+        assert_eq!(
+            sniff(&utf16le("<?xml version='1.0'?>")),
+            Err(EncodingError::MissingDeclaration(DetectedEncoding::Utf16Le))
+        );
+        let doc = utf16le("<?xml version='1.0' encoding='UTF-16BE'?>");
+        assert!(matches!(
+            sniff(&doc),
+            Err(EncodingError::DeclarationMismatch(
+                DetectedEncoding::Utf16Le,
+                _
+            ))
+        ));
+        assert!(matches!(
+            sniff(b"<?xml version='1.0' encoding='UTF-32'?>"),
+            Err(EncodingError::DeclarationMismatch(
+                DetectedEncoding::AsciiCompatible,
+                _
+            ))
+        ));
+        assert_eq!(
+            sniff(b"<?xml version='1.0' encoding='UTF-8'"),
+            Err(EncodingError::MalformedDeclaration)
+        );
+        assert_eq!(
+            sniff(b"<?xml version='1.0' encoding='8bit'?>"),
+            Err(EncodingError::MalformedDeclaration)
+        );
+    }
+}
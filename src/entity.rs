@@ -0,0 +1,471 @@
+//! Entity expansion.
+
+use std::borrow::ToOwned;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+#[cfg(feature = "std")]
+use std::hash::BuildHasher;
+use std::string::String;
+use std::vec::Vec;
+
+use escape::{parse_reference, predefined_entity, UnescapeError};
+use strings::{NameStr, NameString};
+use syntax::Reference;
+
+/// Resolver of general entities.
+pub trait EntityResolver {
+    /// Returns the replacement text of the entity with the given name.
+    ///
+    /// The replacement text may contain references, which are expanded recursively.
+    fn resolve(&self, name: &NameStr) -> Option<&str>;
+}
+
+impl<R: EntityResolver + ?Sized> EntityResolver for &R {
+    fn resolve(&self, name: &NameStr) -> Option<&str> {
+        (**self).resolve(name)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: BuildHasher> EntityResolver for HashMap<NameString, String, S> {
+    fn resolve(&self, name: &NameStr) -> Option<&str> {
+        self.get(name).map(String::as_str)
+    }
+}
+
+/// Resolver which knows no entities other than the predefined ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PredefinedEntities;
+
+impl EntityResolver for PredefinedEntities {
+    fn resolve(&self, _name: &NameStr) -> Option<&str> {
+        None
+    }
+}
+
+/// Limits of entity expansion.
+///
+/// The default limits are suitable for untrusted documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExpansionLimits {
+    /// Maximum nesting depth of entity references.
+    pub max_depth: usize,
+    /// Maximum size of the expanded text in bytes.
+    pub max_expanded_size: usize,
+    /// Maximum ratio of the expanded text size to the input text size.
+    pub max_amplification: usize,
+    /// Expanded text size in bytes from which the amplification ratio is checked.
+    ///
+    /// Small expansions are allowed to have large ratio.
+    pub amplification_threshold: usize,
+    /// Maximum number of entity references resolved by the resolver.
+    ///
+    /// This catches entities expanding to empty or tiny text, which the
+    /// output size limits cannot see.
+    pub max_expansions: usize,
+    /// Maximum total size of the replacement texts processed, in bytes.
+    pub max_replacement_size: usize,
+}
+
+impl ExpansionLimits {
+    /// Returns limits which never fail.
+    ///
+    /// Note that recursive entities are still detected.
+    pub fn unlimited() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_expanded_size: usize::MAX,
+            max_amplification: usize::MAX,
+            amplification_threshold: usize::MAX,
+            max_expansions: usize::MAX,
+            max_replacement_size: usize::MAX,
+        }
+    }
+}
+
+impl Default for ExpansionLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 16,
+            max_expanded_size: 8 * 1024 * 1024,
+            max_amplification: 100,
+            amplification_threshold: 64 * 1024,
+            max_expansions: 100_000,
+            max_replacement_size: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// Entity expansion error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ExpansionError {
+    /// Invalid reference.
+    ///
+    /// The position is relative to the text containing the reference, which
+    /// is the replacement text of the given entity or the input text (`None`).
+    InvalidReference(Option<NameString>, UnescapeError),
+    /// Entity is neither predefined nor resolvable.
+    UnknownEntity(NameString),
+    /// Entity references itself directly or indirectly.
+    RecursiveEntity(NameString),
+    /// Nesting depth of entity references exceeds the limit.
+    DepthLimitExceeded(usize),
+    /// Expanded text size exceeds the limit.
+    SizeLimitExceeded(usize),
+    /// Ratio of the expanded text size to the input text size exceeds the limit.
+    AmplificationLimitExceeded(usize),
+    /// Number of resolved entity references exceeds the limit.
+    ExpansionCountLimitExceeded(usize),
+    /// Total size of the processed replacement texts exceeds the limit.
+    ReplacementSizeLimitExceeded(usize),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ExpansionError {}
+
+impl fmt::Display for ExpansionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpansionError::InvalidReference(Some(entity), e) => {
+                write!(f, "{} in the replacement text of entity `{}`", e, entity)
+            },
+            ExpansionError::InvalidReference(None, e) => e.fmt(f),
+            ExpansionError::UnknownEntity(name) => write!(f, "Unknown entity `{}`", name),
+            ExpansionError::RecursiveEntity(name) => {
+                write!(f, "Entity `{}` references itself", name)
+            },
+            ExpansionError::DepthLimitExceeded(limit) => write!(
+                f,
+                "Nesting depth of entity references exceeds the limit {}",
+                limit
+            ),
+            ExpansionError::SizeLimitExceeded(limit) => {
+                write!(f, "Expanded text size exceeds the limit {} bytes", limit)
+            },
+            ExpansionError::AmplificationLimitExceeded(limit) => write!(
+                f,
+                "Entity expansion amplifies the input more than the limit {} times",
+                limit
+            ),
+            ExpansionError::ExpansionCountLimitExceeded(limit) => {
+                write!(f, "Number of entity references exceeds the limit {}", limit)
+            },
+            ExpansionError::ReplacementSizeLimitExceeded(limit) => write!(
+                f,
+                "Total size of replacement texts exceeds the limit {} bytes",
+                limit
+            ),
+        }
+    }
+}
+
+/// Entity expander.
+///
+/// Expands character references, predefined entity references and entity
+/// references resolved by the resolver.
+/// Replacement texts are treated as character data with references.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "std")]
+/// # mod example {
+/// # use std::collections::HashMap;
+/// # use xmlop_datatypes::entity::{EntityExpander, ExpansionError};
+/// # use xmlop_datatypes::strings::NameString;
+/// # pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut entities = HashMap::new();
+/// entities.insert(NameString::new("lol".to_owned())?, "lol".to_owned());
+/// entities.insert(NameString::new("lol2".to_owned())?, "&lol;&lol;".to_owned());
+/// entities.insert(NameString::new("self".to_owned())?, "&self;".to_owned());
+///
+/// let expander = EntityExpander::new(&entities);
+/// assert_eq!(expander.expand("&lol2;&amp;&#33;")?, "lollol&!");
+/// assert_eq!(
+///     expander.expand("&self;"),
+///     Err(ExpansionError::RecursiveEntity(NameString::new("self".to_owned())?))
+/// );
+/// # Ok(())
+/// # }
+/// # }
+/// # #[cfg(feature = "std")]
+/// # example::run().expect("Should never fail");
+/// ```
+#[derive(Debug, Clone)]
+pub struct EntityExpander<R> {
+    /// Resolver.
+    resolver: R,
+    /// Limits.
+    limits: ExpansionLimits,
+}
+
+impl<R: EntityResolver> EntityExpander<R> {
+    /// Creates a new `EntityExpander` with the default limits.
+    pub fn new(resolver: R) -> Self {
+        Self::with_limits(resolver, ExpansionLimits::default())
+    }
+
+    /// Creates a new `EntityExpander` with the given limits.
+    pub fn with_limits(resolver: R, limits: ExpansionLimits) -> Self {
+        Self { resolver, limits }
+    }
+
+    /// Returns the limits.
+    pub fn limits(&self) -> &ExpansionLimits {
+        &self.limits
+    }
+
+    /// Expands references in the given text.
+    pub fn expand(&self, text: &str) -> Result<String, ExpansionError> {
+        let mut expanded = String::with_capacity(text.len());
+        self.expand_into(text, &mut expanded)?;
+        Ok(expanded)
+    }
+
+    /// Expands references in the given text and appends the result to `out`.
+    ///
+    /// On error, `out` may contain partially expanded text.
+    pub fn expand_into(&self, text: &str, out: &mut String) -> Result<(), ExpansionError> {
+        let mut expansion = Expansion {
+            resolver: &self.resolver,
+            limits: &self.limits,
+            base_len: out.len(),
+            out,
+            input_len: text.len(),
+            stack: Vec::new(),
+            expansions: 0,
+            replacement_size: 0,
+        };
+        expansion.expand(text, None)
+    }
+}
+
+/// State of an expansion.
+struct Expansion<'a, R: 'a> {
+    /// Resolver.
+    resolver: &'a R,
+    /// Limits.
+    limits: &'a ExpansionLimits,
+    /// Length of the output before the expansion.
+    base_len: usize,
+    /// Output.
+    out: &'a mut String,
+    /// Length of the input text.
+    input_len: usize,
+    /// Entities being expanded.
+    stack: Vec<&'a NameStr>,
+    /// Number of entity references resolved so far.
+    expansions: usize,
+    /// Total size of the replacement texts resolved so far.
+    replacement_size: usize,
+}
+
+impl<'a, R: EntityResolver> Expansion<'a, R> {
+    /// Expands the text, which is the replacement text of `entity` or the input.
+    fn expand(&mut self, text: &'a str, entity: Option<&NameStr>) -> Result<(), ExpansionError> {
+        let mut pos = 0;
+        while pos < text.len() {
+            let rest = &text[pos..];
+            if !rest.starts_with('&') {
+                let len = rest.find('&').unwrap_or(rest.len());
+                self.push_str(&rest[..len])?;
+                pos += len;
+                continue;
+            }
+            let (reference, len) = parse_reference(rest).map_err(|kind| {
+                ExpansionError::InvalidReference(
+                    entity.map(ToOwned::to_owned),
+                    UnescapeError::new(kind, pos),
+                )
+            })?;
+            match reference {
+                Reference::Char(c) => {
+                    let mut buf = [0; 4];
+                    self.push_str(c.value().encode_utf8(&mut buf))?;
+                },
+                Reference::Entity(r) => self.expand_entity(r.name())?,
+            }
+            pos += len;
+        }
+        Ok(())
+    }
+
+    /// Expands the entity.
+    fn expand_entity(&mut self, name: &'a NameStr) -> Result<(), ExpansionError> {
+        if let Some(text) = predefined_entity(name) {
+            return self.push_str(text);
+        }
+        let text = self
+            .resolver
+            .resolve(name)
+            .ok_or_else(|| ExpansionError::UnknownEntity(name.to_owned()))?;
+        self.expansions += 1;
+        if self.expansions > self.limits.max_expansions {
+            return Err(ExpansionError::ExpansionCountLimitExceeded(
+                self.limits.max_expansions,
+            ));
+        }
+        self.replacement_size = self.replacement_size.saturating_add(text.len());
+        if self.replacement_size > self.limits.max_replacement_size {
+            return Err(ExpansionError::ReplacementSizeLimitExceeded(
+                self.limits.max_replacement_size,
+            ));
+        }
+        if self.stack.contains(&name) {
+            return Err(ExpansionError::RecursiveEntity(name.to_owned()));
+        }
+        if self.stack.len() >= self.limits.max_depth {
+            return Err(ExpansionError::DepthLimitExceeded(self.limits.max_depth));
+        }
+        self.stack.push(name);
+        self.expand(text, Some(name))?;
+        self.stack.pop();
+        Ok(())
+    }
+
+    /// Appends the string to the output, checking the limits.
+    fn push_str(&mut self, s: &str) -> Result<(), ExpansionError> {
+        let len = self.out.len() - self.base_len + s.len();
+        if len > self.limits.max_expanded_size {
+            return Err(ExpansionError::SizeLimitExceeded(
+                self.limits.max_expanded_size,
+            ));
+        }
+        if len > self.limits.amplification_threshold
+            && len / self.input_len.max(1) >= self.limits.max_amplification
+        {
+            return Err(ExpansionError::AmplificationLimitExceeded(
+                self.limits.max_amplification,
+            ));
+        }
+        self.out.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use escape::UnescapeErrorKind;
+
+    use super::*;
+
+    fn name(s: &str) -> NameString {
+        NameString::new(s.to_owned()).expect("Should never fail")
+    }
+
+    fn billion_laughs(levels: usize, lol0: &str) -> HashMap<NameString, String> {
+        let mut entities = HashMap::new();
+        entities.insert(name("lol0"), lol0.to_owned());
+        for i in 1..levels {
+            let prev = format!("&lol{};", i - 1);
+            entities.insert(name(&format!("lol{}", i)), prev.repeat(10));
+        }
+        entities
+    }
+
+    #[test]
+    fn expand_nested() {
+        let entities = billion_laughs(3, "lol");
+        let expander = EntityExpander::new(&entities);
+        assert_eq!(
+            expander.expand("<&lol2;>").map(|s| s.len()),
+            Ok(2 + 3 * 100)
+        );
+        assert_eq!(
+            EntityExpander::new(PredefinedEntities).expand("&lt;&#x41;&gt;"),
+            Ok("<A>".to_owned())
+        );
+    }
+
+    #[test]
+    fn detect_billion_laughs() {
+        let entities = billion_laughs(10, "lol");
+        let expander = EntityExpander::new(&entities);
+        assert_eq!(
+            expander.expand("&lol9;"),
+            Err(ExpansionError::AmplificationLimitExceeded(100))
+        );
+
+        let limits = ExpansionLimits {
+            max_amplification: usize::MAX,
+            max_expansions: usize::MAX,
+            max_replacement_size: usize::MAX,
+            ..ExpansionLimits::default()
+        };
+        let expander = EntityExpander::with_limits(&entities, limits);
+        assert_eq!(
+            expander.expand("&lol9;"),
+            Err(ExpansionError::SizeLimitExceeded(8 * 1024 * 1024))
+        );
+
+        let limits = ExpansionLimits {
+            max_depth: 3,
+            ..ExpansionLimits::unlimited()
+        };
+        let expander = EntityExpander::with_limits(&entities, limits);
+        assert_eq!(
+            expander.expand("&lol9;"),
+            Err(ExpansionError::DepthLimitExceeded(3))
+        );
+    }
+
+    #[test]
+    fn detect_empty_billion_laughs() {
+        let entities = billion_laughs(16, "");
+        let expander = EntityExpander::new(&entities);
+        assert_eq!(
+            expander.expand("&lol15;"),
+            Err(ExpansionError::ExpansionCountLimitExceeded(100_000))
+        );
+
+        let limits = ExpansionLimits {
+            max_replacement_size: 1000,
+            ..ExpansionLimits::unlimited()
+        };
+        let expander = EntityExpander::with_limits(&entities, limits);
+        assert_eq!(
+            expander.expand("&lol15;"),
+            Err(ExpansionError::ReplacementSizeLimitExceeded(1000))
+        );
+
+        let limits = ExpansionLimits {
+            max_expansions: 111,
+            ..ExpansionLimits::default()
+        };
+        let expander = EntityExpander::with_limits(&entities, limits);
+        assert_eq!(expander.expand("&lol2;"), Ok(String::new()));
+        assert_eq!(
+            expander.expand("&lol2;&lol0;"),
+            Err(ExpansionError::ExpansionCountLimitExceeded(111))
+        );
+    }
+
+    #[test]
+    fn expansion_errors() {
+        let mut entities = HashMap::new();
+        entities.insert(name("a"), "&b;".to_owned());
+        entities.insert(name("b"), "x&a;".to_owned());
+        entities.insert(name("bad"), "ok&#0;".to_owned());
+        let expander = EntityExpander::with_limits(&entities, ExpansionLimits::unlimited());
+        assert_eq!(
+            expander.expand("&a;"),
+            Err(ExpansionError::RecursiveEntity(name("a")))
+        );
+        assert_eq!(
+            expander.expand("&c;"),
+            Err(ExpansionError::UnknownEntity(name("c")))
+        );
+        match expander.expand("&bad;") {
+            Err(ExpansionError::InvalidReference(Some(ref entity), ref e)) => {
+                assert_eq!(*entity, name("bad"));
+                assert_eq!(e.position(), 2);
+                assert!(matches!(e.kind(), UnescapeErrorKind::InvalidCharRef(_)));
+            },
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! End-of-line handling and character validation of raw input.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#sec-line-ends> and
+//! <https://www.w3.org/TR/2006/REC-xml11-20060816/#sec-line-ends>.
+
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+use std::str;
+use std::string::String;
+
+use version::XmlVersion;
+
+/// Kind of an end-of-line handling error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EolErrorKind {
+    /// Input is not valid UTF-8.
+    InvalidUtf8,
+    /// A character is not `Char` of the version.
+    InvalidChar(char),
+    /// A character is `RestrictedChar`, which cannot appear literally.
+    RestrictedChar(char),
+}
+
+/// End-of-line handling error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EolError {
+    /// Error kind.
+    kind: EolErrorKind,
+    /// Byte position of the error in the input.
+    position: usize,
+}
+
+impl EolError {
+    /// Creates a new `EolError`.
+    fn new(kind: EolErrorKind, position: usize) -> Self {
+        Self { kind, position }
+    }
+
+    /// Returns the error kind.
+    pub fn kind(&self) -> EolErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte position of the error in the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for EolError {}
+
+impl fmt::Display for EolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            EolErrorKind::InvalidUtf8 => write!(
+                f,
+                "Invalid UTF-8 sequence at byte position {}",
+                self.position
+            ),
+            EolErrorKind::InvalidChar(c) => write!(
+                f,
+                "Invalid character at byte position {}: {:?}",
+                self.position, c
+            ),
+            EolErrorKind::RestrictedChar(c) => write!(
+                f,
+                "Restricted character should be a character reference (byte position {}): {:?}",
+                self.position, c
+            ),
+        }
+    }
+}
+
+/// State of end-of-line handling, shared by the adaptors.
+#[derive(Debug, Clone, Copy)]
+struct LineEnds {
+    /// XML version.
+    version: XmlVersion,
+    /// Whether the previous character is CR.
+    after_cr: bool,
+}
+
+impl LineEnds {
+    /// Creates a new `LineEnds`.
+    fn new(version: XmlVersion) -> Self {
+        Self {
+            version,
+            after_cr: false,
+        }
+    }
+
+    /// Validates and normalizes the character at the given byte position.
+    ///
+    /// Returns `None` if the character is absorbed into the previous line end.
+    fn next(&mut self, c: char, pos: usize) -> Result<Option<char>, EolError> {
+        if !self.version.is_char(c) {
+            return Err(EolError::new(EolErrorKind::InvalidChar(c), pos));
+        }
+        if self.version.is_restricted_char(c) {
+            return Err(EolError::new(EolErrorKind::RestrictedChar(c), pos));
+        }
+        let after_cr = self.after_cr;
+        self.after_cr = c == '\r';
+        if after_cr && (c == '\n' || (c == '\u{85}' && self.version == XmlVersion::V1_1)) {
+            return Ok(None);
+        }
+        if self.version.is_line_end_char(c) {
+            return Ok(Some('\n'));
+        }
+        Ok(Some(c))
+    }
+}
+
+/// Iterator of validated and normalized characters of a string.
+///
+/// The iterator stops after an error.
+#[derive(Debug, Clone)]
+pub struct NormalizedChars<'a> {
+    /// Characters.
+    chars: str::CharIndices<'a>,
+    /// End-of-line handling state.
+    line_ends: LineEnds,
+    /// Whether an error occurred.
+    failed: bool,
+}
+
+impl<'a> NormalizedChars<'a> {
+    /// Creates a new `NormalizedChars`.
+    pub fn new(s: &'a str, version: XmlVersion) -> Self {
+        Self {
+            chars: s.char_indices(),
+            line_ends: LineEnds::new(version),
+            failed: false,
+        }
+    }
+}
+
+impl<'a> Iterator for NormalizedChars<'a> {
+    type Item = Result<char, EolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        for (pos, c) in &mut self.chars {
+            match self.line_ends.next(c, pos) {
+                Ok(Some(c)) => return Some(Ok(c)),
+                Ok(None) => {},
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                },
+            }
+        }
+        None
+    }
+}
+
+/// Validates and normalizes line ends of the string.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::eol::normalize;
+/// # use xmlop_datatypes::version::XmlVersion;
+/// assert_eq!(normalize("a\r\nb\rc", XmlVersion::V1_0).unwrap(), "a\nb\nc");
+/// assert_eq!(normalize("a\r\u{85}b", XmlVersion::V1_0).unwrap(), "a\n\u{85}b");
+/// assert_eq!(normalize("a\r\u{85}b\u{2028}", XmlVersion::V1_1).unwrap(), "a\nb\n");
+/// assert!(normalize("a\u{1}", XmlVersion::V1_1).is_err());
+/// ```
+pub fn normalize(s: &str, version: XmlVersion) -> Result<Cow<'_, str>, EolError> {
+    let mut line_ends = LineEnds::new(version);
+    let mut chars = s.char_indices();
+    for (pos, c) in &mut chars {
+        if version.is_line_end_char(c) {
+            let mut normalized = String::with_capacity(s.len());
+            normalized.push_str(&s[..pos]);
+            if let Some(c) = line_ends.next(c, pos)? {
+                normalized.push(c);
+            }
+            for (pos, c) in chars {
+                if let Some(c) = line_ends.next(c, pos)? {
+                    normalized.push(c);
+                }
+            }
+            return Ok(Cow::Owned(normalized));
+        }
+        line_ends.next(c, pos)?;
+    }
+    Ok(Cow::Borrowed(s))
+}
+
+/// Streaming end-of-line normalizer of UTF-8 bytes.
+///
+/// Input can be split at any byte, even in the middle of a line end or a
+/// UTF-8 sequence.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::eol::{EolError, EolNormalizer};
+/// # use xmlop_datatypes::version::XmlVersion;
+/// # fn run() -> Result<(), EolError> {
+/// let mut normalizer = EolNormalizer::new(XmlVersion::V1_1);
+/// let mut out = String::new();
+/// normalizer.feed(b"a\r", &mut out)?;
+/// normalizer.feed(b"\xC2", &mut out)?;
+/// normalizer.feed(b"\x85b\r", &mut out)?;
+/// normalizer.finish()?;
+/// assert_eq!(out, "a\nb\n");
+/// # Ok(())
+/// # }
+/// # run().expect("Should never fail");
+/// ```
+#[derive(Debug, Clone)]
+pub struct EolNormalizer {
+    /// End-of-line handling state.
+    line_ends: LineEnds,
+    /// Incomplete UTF-8 sequence at the end of the previous input.
+    partial: [u8; 4],
+    /// Length of the incomplete UTF-8 sequence.
+    partial_len: usize,
+    /// Byte position of the next input.
+    position: usize,
+}
+
+impl EolNormalizer {
+    /// Creates a new `EolNormalizer`.
+    pub fn new(version: XmlVersion) -> Self {
+        Self {
+            line_ends: LineEnds::new(version),
+            partial: [0; 4],
+            partial_len: 0,
+            position: 0,
+        }
+    }
+
+    /// Returns the byte position of the next input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Normalizes the bytes and appends the result to `out`.
+    pub fn feed(&mut self, mut bytes: &[u8], out: &mut String) -> Result<(), EolError> {
+        if self.partial_len > 0 {
+            let start = self.position - self.partial_len;
+            let needed = utf8_len(self.partial[0]) - self.partial_len;
+            let len = needed.min(bytes.len());
+            self.partial[self.partial_len..(self.partial_len + len)].copy_from_slice(&bytes[..len]);
+            self.partial_len += len;
+            self.position += len;
+            bytes = &bytes[len..];
+            if len < needed {
+                return Ok(());
+            }
+            let partial = self.partial;
+            let s = str::from_utf8(&partial[..self.partial_len])
+                .map_err(|_| EolError::new(EolErrorKind::InvalidUtf8, start))?;
+            self.partial_len = 0;
+            self.push_str(s, start, out)?;
+        }
+        let (valid, rest) = match str::from_utf8(bytes) {
+            Ok(s) => (s, &[][..]),
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                if e.error_len().is_some() {
+                    return Err(EolError::new(
+                        EolErrorKind::InvalidUtf8,
+                        self.position + valid.len(),
+                    ));
+                }
+                let valid = str::from_utf8(valid)
+                    .unwrap_or_else(|e| unreachable!("Should never fail: validated prefix: {}", e));
+                (valid, rest)
+            },
+        };
+        let start = self.position;
+        self.position += valid.len();
+        self.push_str(valid, start, out)?;
+        self.partial[..rest.len()].copy_from_slice(rest);
+        self.partial_len = rest.len();
+        self.position += rest.len();
+        Ok(())
+    }
+
+    /// Finishes the input.
+    ///
+    /// Fails if the input ends in the middle of a UTF-8 sequence.
+    pub fn finish(self) -> Result<(), EolError> {
+        if self.partial_len > 0 {
+            return Err(EolError::new(
+                EolErrorKind::InvalidUtf8,
+                self.position - self.partial_len,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Normalizes the string starting at the given byte position.
+    fn push_str(&mut self, s: &str, start: usize, out: &mut String) -> Result<(), EolError> {
+        for (pos, c) in s.char_indices() {
+            if let Some(c) = self.line_ends.next(c, start + pos)? {
+                out.push(c);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the length of the UTF-8 sequence from the leading byte.
+fn utf8_len(b: u8) -> usize {
+    match b {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn normalize_str() {
+        assert!(matches!(
+            normalize("a\nb", XmlVersion::V1_0),
+            Ok(Cow::Borrowed("a\nb"))
+        ));
+        assert_eq!(
+            normalize("\r\r\n\n", XmlVersion::V1_0).map(Cow::into_owned),
+            Ok("\n\n\n".to_owned())
+        );
+        assert_eq!(
+            normalize("a\r\u{1}", XmlVersion::V1_0),
+            Err(EolError::new(EolErrorKind::InvalidChar('\u{1}'), 2))
+        );
+        assert_eq!(
+            normalize("\u{7F}", XmlVersion::V1_1),
+            Err(EolError::new(EolErrorKind::RestrictedChar('\u{7F}'), 0))
+        );
+    }
+
+    #[test]
+    fn normalized_chars() {
+        let chars = NormalizedChars::new("a\r\u{85}\u{2028}", XmlVersion::V1_1)
+            .collect::<Result<String, _>>();
+        assert_eq!(chars, Ok("a\n\n".to_owned()));
+        let mut chars = NormalizedChars::new("a\u{FFFE}b", XmlVersion::V1_0);
+        assert_eq!(chars.next(), Some(Ok('a')));
+        assert_eq!(
+            chars.next(),
+            Some(Err(EolError::new(EolErrorKind::InvalidChar('\u{FFFE}'), 1)))
+        );
+        assert_eq!(chars.next(), None);
+    }
+
+    #[test]
+    fn normalize_stream() {
+        let input = "x\r\n\u{3042}\r\u{2028}y\r".as_bytes();
+        for split in 0..input.len() {
+            let mut normalizer = EolNormalizer::new(XmlVersion::V1_1);
+            let mut out = String::new();
+            normalizer
+                .feed(&input[..split], &mut out)
+                .and_then(|_| normalizer.feed(&input[split..], &mut out))
+                .expect("Should never fail");
+            normalizer.finish().expect("Should never fail");
+            assert_eq!(out, "x\n\u{3042}\n\ny\n");
+        }
+
+        let mut normalizer = EolNormalizer::new(XmlVersion::V1_0);
+        let mut out = String::new();
+        assert_eq!(
+            normalizer.feed(b"ab\xFFc", &mut out),
+            Err(EolError::new(EolErrorKind::InvalidUtf8, 2))
+        );
+        let mut normalizer = EolNormalizer::new(XmlVersion::V1_0);
+        normalizer
+            .feed(b"ab\xE3\x81", &mut out)
+            .expect("Should never fail");
+        assert_eq!(
+            normalizer.finish(),
+            Err(EolError::new(EolErrorKind::InvalidUtf8, 2))
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! Escaping and unescaping of text and attribute values.
+
+use std::borrow::Cow;
+use std::borrow::ToOwned;
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+use std::string::String;
+
+use strings::{is_xml_char, NameStr, NameString};
+use syntax::{CharRefError, Quote, Reference, ReferenceParseError};
+
+/// Escape error: the string has a character which is not an XML `Char`.
+///
+/// Such characters cannot be written even as character references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EscapeError {
+    /// Byte position of the character.
+    position: usize,
+    /// The invalid character.
+    invalid: char,
+}
+
+impl EscapeError {
+    /// Returns the byte position of the invalid character.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the invalid character.
+    pub fn invalid_char(&self) -> char {
+        self.invalid
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for EscapeError {}
+
+impl fmt::Display for EscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Character at byte position {} is not an XML `Char`: {:?}",
+            self.position, self.invalid
+        )
+    }
+}
+
+/// Checks that the string consists of XML `Char`s.
+fn check_chars(s: &str) -> Result<(), EscapeError> {
+    match s.char_indices().find(|&(_, c)| !is_xml_char(c)) {
+        Some((position, invalid)) => Err(EscapeError { position, invalid }),
+        None => Ok(()),
+    }
+}
+
+/// Returns the escaped form of the given character in character data.
+fn escape_text_char(c: char) -> Option<&'static str> {
+    match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '\r' => Some("&#xD;"),
+        _ => None,
+    }
+}
+
+/// Returns the escaped form of the given character in an attribute value.
+fn escape_attribute_char(c: char, quote: Quote) -> Option<&'static str> {
+    match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '"' if quote == Quote::Double => Some("&quot;"),
+        '\'' if quote == Quote::Single => Some("&apos;"),
+        '\t' => Some("&#x9;"),
+        '\n' => Some("&#xA;"),
+        '\r' => Some("&#xD;"),
+        _ => None,
+    }
+}
+
+/// Escapes the string using the given escape function.
+fn escape_with<F>(s: &str, escape: F) -> Result<Cow<'_, str>, EscapeError>
+where
+    F: Fn(char) -> Option<&'static str>,
+{
+    check_chars(s)?;
+    let first = match s.char_indices().find(|&(_, c)| escape(c).is_some()) {
+        Some((pos, _)) => pos,
+        None => return Ok(Cow::Borrowed(s)),
+    };
+    let mut escaped = String::with_capacity(s.len() + 8);
+    escaped.push_str(&s[..first]);
+    for c in s[first..].chars() {
+        match escape(c) {
+            Some(e) => escaped.push_str(e),
+            None => escaped.push(c),
+        }
+    }
+    Ok(Cow::Owned(escaped))
+}
+
+/// Writes the string escaped using the given escape function.
+///
+/// Fails if the string has a non-`Char` character.
+fn write_escaped_with<W, F>(w: &mut W, s: &str, escape: F) -> fmt::Result
+where
+    W: fmt::Write,
+    F: Fn(char) -> Option<&'static str>,
+{
+    check_chars(s).map_err(|_| fmt::Error)?;
+    let mut rest = s;
+    while let Some((pos, c, e)) = rest
+        .char_indices()
+        .find_map(|(pos, c)| escape(c).map(|e| (pos, c, e)))
+    {
+        w.write_str(&rest[..pos])?;
+        w.write_str(e)?;
+        rest = &rest[(pos + c.len_utf8())..];
+    }
+    w.write_str(rest)
+}
+
+/// Escapes the string to be used as character data.
+///
+/// `&`, `<` and `>` are replaced with entity references, and carriage
+/// returns are replaced with a character reference to survive end-of-line
+/// normalization.
+///
+/// # Failures
+///
+/// Fails if the string has a character which is not an XML `Char`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::escape::escape_text;
+/// assert_eq!(escape_text("a < b && c").unwrap(), "a &lt; b &amp;&amp; c");
+/// assert_eq!(escape_text("]]>").unwrap(), "]]&gt;");
+/// assert_eq!(escape_text("a\u{1}").unwrap_err().position(), 1);
+/// ```
+pub fn escape_text(s: &str) -> Result<Cow<'_, str>, EscapeError> {
+    escape_with(s, escape_text_char)
+}
+
+/// Escapes the string to be used as an attribute value quoted by the given quote.
+///
+/// `&`, `<` and the quote are replaced with entity references, and tabs,
+/// newlines and carriage returns are replaced with character references to
+/// survive attribute-value normalization.
+///
+/// # Failures
+///
+/// Fails if the string has a character which is not an XML `Char`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::escape::escape_attribute;
+/// # use xmlop_datatypes::syntax::Quote;
+/// let escape = |s| escape_attribute(s, Quote::Double).unwrap();
+/// assert_eq!(escape("'a' \"b\""), "'a' &quot;b&quot;");
+/// assert_eq!(escape("a\nb"), "a&#xA;b");
+/// assert_eq!(
+///     escape_attribute("'a' \"b\"", Quote::Single).unwrap(),
+///     "&apos;a&apos; \"b\""
+/// );
+/// assert!(escape_attribute("\u{FFFE}", Quote::Single).is_err());
+/// ```
+pub fn escape_attribute(s: &str, quote: Quote) -> Result<Cow<'_, str>, EscapeError> {
+    escape_with(s, |c| escape_attribute_char(c, quote))
+}
+
+/// `fmt::Write` adaptor escaping written strings as character data.
+///
+/// See [`escape_text`] for details.
+/// Writing a non-`Char` character fails with `fmt::Error`.
+#[derive(Debug, Clone)]
+pub struct TextEscaper<W> {
+    /// Underlying writer.
+    inner: W,
+}
+
+impl<W: fmt::Write> TextEscaper<W> {
+    /// Creates a new `TextEscaper`.
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for TextEscaper<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_escaped_with(&mut self.inner, s, escape_text_char)
+    }
+}
+
+/// `fmt::Write` adaptor escaping written strings as an attribute value.
+///
+/// See [`escape_attribute`] for details.
+/// Writing a non-`Char` character fails with `fmt::Error`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::fmt::Write;
+/// # use xmlop_datatypes::escape::AttributeEscaper;
+/// # use xmlop_datatypes::syntax::Quote;
+/// let mut escaper = AttributeEscaper::new(String::new(), Quote::Double);
+/// write!(escaper, "{} & {}", "\"a\"", 42).expect("Should never fail");
+/// assert_eq!(escaper.into_inner(), "&quot;a&quot; &amp; 42");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AttributeEscaper<W> {
+    /// Underlying writer.
+    inner: W,
+    /// Quote of the attribute value.
+    quote: Quote,
+}
+
+impl<W: fmt::Write> AttributeEscaper<W> {
+    /// Creates a new `AttributeEscaper`.
+    pub fn new(inner: W, quote: Quote) -> Self {
+        Self { inner, quote }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for AttributeEscaper<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let quote = self.quote;
+        write_escaped_with(&mut self.inner, s, |c| escape_attribute_char(c, quote))
+    }
+}
+
+/// Kind of an unescape error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UnescapeErrorKind {
+    /// `&` without terminating `;`.
+    UnterminatedReference,
+    /// Reference is syntactically invalid.
+    InvalidReference,
+    /// Character reference refers to a non-`Char` code point.
+    InvalidCharRef(CharRefError),
+    /// Entity is not predefined.
+    UnknownEntity(NameString),
+}
+
+/// Unescape error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnescapeError {
+    /// Error kind.
+    kind: UnescapeErrorKind,
+    /// Byte position of the reference.
+    position: usize,
+}
+
+impl UnescapeError {
+    /// Creates a new `UnescapeError`.
+    pub(crate) fn new(kind: UnescapeErrorKind, position: usize) -> Self {
+        Self { kind, position }
+    }
+
+    /// Returns the error kind.
+    pub fn kind(&self) -> &UnescapeErrorKind {
+        &self.kind
+    }
+
+    /// Returns the byte position of the reference.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for UnescapeError {}
+
+impl fmt::Display for UnescapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            UnescapeErrorKind::UnterminatedReference => write!(
+                f,
+                "Unterminated reference at byte position {}",
+                self.position
+            ),
+            UnescapeErrorKind::InvalidReference => {
+                write!(f, "Invalid reference at byte position {}", self.position)
+            },
+            UnescapeErrorKind::InvalidCharRef(e) => {
+                write!(f, "{} at byte position {}", e, self.position)
+            },
+            UnescapeErrorKind::UnknownEntity(name) => write!(
+                f,
+                "Unknown entity `{}` at byte position {}",
+                name, self.position
+            ),
+        }
+    }
+}
+
+/// Returns the replacement text of the given predefined entity.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#sec-predefined-ent>.
+pub fn predefined_entity(name: &NameStr) -> Option<&'static str> {
+    match name.as_str() {
+        "lt" => Some("<"),
+        "gt" => Some(">"),
+        "amp" => Some("&"),
+        "apos" => Some("'"),
+        "quot" => Some("\""),
+        _ => None,
+    }
+}
+
+/// Parses the reference at the beginning of the given string.
+///
+/// The string should start with `&`.
+/// Returns the reference and its length in bytes.
+pub(crate) fn parse_reference(s: &str) -> Result<(Reference<'_>, usize), UnescapeErrorKind> {
+    Reference::parse_prefix(s).map_err(|e| match e {
+        ReferenceParseError::Unterminated => UnescapeErrorKind::UnterminatedReference,
+        ReferenceParseError::Invalid => UnescapeErrorKind::InvalidReference,
+        ReferenceParseError::InvalidChar(e) => UnescapeErrorKind::InvalidCharRef(e),
+    })
+}
+
+/// Expands predefined entity references and character references.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::escape::{unescape, UnescapeErrorKind};
+/// assert_eq!(unescape("a &lt; b").expect("Should never fail"), "a < b");
+/// assert_eq!(unescape("&#x3C;&#60;").expect("Should never fail"), "<<");
+///
+/// let err = unescape("ok &#0;").expect_err("Should fail");
+/// assert_eq!(err.position(), 3);
+/// let err = unescape("&unknown;").expect_err("Should fail");
+/// assert!(matches!(err.kind(), UnescapeErrorKind::UnknownEntity(_)));
+/// ```
+pub fn unescape(s: &str) -> Result<Cow<'_, str>, UnescapeError> {
+    let first = match s.find('&') {
+        Some(pos) => pos,
+        None => return Ok(Cow::Borrowed(s)),
+    };
+    let mut unescaped = String::with_capacity(s.len());
+    unescaped.push_str(&s[..first]);
+    let mut pos = first;
+    while pos < s.len() {
+        let rest = &s[pos..];
+        if !rest.starts_with('&') {
+            let len = rest.find('&').unwrap_or(rest.len());
+            unescaped.push_str(&rest[..len]);
+            pos += len;
+            continue;
+        }
+        let (reference, len) =
+            parse_reference(rest).map_err(|kind| UnescapeError::new(kind, pos))?;
+        match reference {
+            Reference::Char(c) => unescaped.push(c.value()),
+            Reference::Entity(r) => match predefined_entity(r.name()) {
+                Some(text) => unescaped.push_str(text),
+                None => {
+                    return Err(UnescapeError::new(
+                        UnescapeErrorKind::UnknownEntity(r.name().to_owned()),
+                        pos,
+                    ))
+                },
+            },
+        }
+        pos += len;
+    }
+    Ok(Cow::Owned(unescaped))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    #[test]
+    fn escape_round_trip() {
+        let s = "<tag attr=\"'v'\">&amp;\r\n\t</tag>";
+        let escaped = escape_text(s).expect("Should never fail");
+        assert_eq!(unescape(&escaped).expect("Should never fail"), s);
+        for &quote in &[Quote::Single, Quote::Double] {
+            let escaped = escape_attribute(s, quote).expect("Should never fail");
+            assert!(!escaped.contains(quote.as_char()));
+            assert_eq!(unescape(&escaped).expect("Should never fail"), s);
+        }
+    }
+
+    #[test]
+    fn borrow_if_unchanged() {
+        assert!(matches!(escape_text("plain"), Ok(Cow::Borrowed(_))));
+        assert!(matches!(
+            escape_attribute("\"", Quote::Single),
+            Ok(Cow::Borrowed(_))
+        ));
+        assert!(matches!(unescape("plain"), Ok(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn streaming_escape() {
+        let mut escaper = TextEscaper::new(String::new());
+        escaper.write_str("a<").expect("Should never fail");
+        escaper.write_str("&b").expect("Should never fail");
+        assert!(escaper.write_str("\u{0}").is_err());
+        assert_eq!(escaper.into_inner(), "a&lt;&amp;b");
+    }
+
+    #[test]
+    fn escape_invalid_chars() {
+        let err = escape_text("a\u{1}b").expect_err("Should fail");
+        assert_eq!((err.position(), err.invalid_char()), (1, '\u{1}'));
+        let err = escape_attribute("&\u{FFFF}", Quote::Double).expect_err("Should fail");
+        assert_eq!((err.position(), err.invalid_char()), (1, '\u{FFFF}'));
+    }
+
+    #[test]
+    fn unescape_errors() {
+        let err = |s: &str| unescape(s).expect_err("Should fail");
+        assert_eq!(
+            err("a &amp"),
+            UnescapeError::new(UnescapeErrorKind::UnterminatedReference, 2)
+        );
+        assert_eq!(
+            err("&amp b; &lt;"),
+            UnescapeError::new(UnescapeErrorKind::UnterminatedReference, 0)
+        );
+        assert_eq!(
+            err("&;"),
+            UnescapeError::new(UnescapeErrorKind::InvalidReference, 0)
+        );
+        assert_eq!(
+            err("&#x;"),
+            UnescapeError::new(UnescapeErrorKind::InvalidReference, 0)
+        );
+        assert_eq!(
+            err("&#12a;"),
+            UnescapeError::new(UnescapeErrorKind::InvalidReference, 0)
+        );
+        assert_eq!(
+            err("&1a;"),
+            UnescapeError::new(UnescapeErrorKind::InvalidReference, 0)
+        );
+        assert_eq!(
+            err("&#xD800;"),
+            UnescapeError::new(
+                UnescapeErrorKind::InvalidCharRef(CharRefError::InvalidChar(0xD800)),
+                0
+            )
+        );
+        assert_eq!(
+            err("&#99999999999;").kind(),
+            &UnescapeErrorKind::InvalidCharRef(CharRefError::InvalidChar(u32::MAX))
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! XML events.
+
+use position::Span;
+use strings::{NameStr, PiTargetStr, QnameStr};
+use syntax::{QuotedLiteral, XmlDecl};
+
+/// External ID of a document type declaration.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-ExternalID>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExternalId<'a> {
+    /// `SYSTEM` external ID.
+    System {
+        /// System literal.
+        system: QuotedLiteral<'a>,
+    },
+    /// `PUBLIC` external ID.
+    Public {
+        /// Public ID literal.
+        public: QuotedLiteral<'a>,
+        /// System literal.
+        system: QuotedLiteral<'a>,
+    },
+}
+
+/// XML event.
+///
+/// Names are borrowed from the source text and already validated.
+/// Text-like contents are raw: references are not expanded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event<'a> {
+    /// XML declaration (`<?xml version="1.0"?>`).
+    XmlDecl {
+        /// Declaration.
+        decl: XmlDecl<'a>,
+        /// Span of the whole declaration.
+        span: Span,
+    },
+    /// Document type declaration (`<!DOCTYPE name [...]>`).
+    Doctype {
+        /// Name of the root element.
+        name: &'a NameStr,
+        /// External ID.
+        external_id: Option<ExternalId<'a>>,
+        /// Internal subset without brackets.
+        internal_subset: Option<&'a str>,
+        /// Span of the whole declaration.
+        span: Span,
+    },
+    /// Processing instruction (`<?target data?>`).
+    ProcessingInstruction {
+        /// Target.
+        target: &'a PiTargetStr,
+        /// Data.
+        data: Option<&'a str>,
+        /// Span of the whole processing instruction.
+        span: Span,
+    },
+    /// Comment (`<!--text-->`).
+    Comment {
+        /// Text without delimiters.
+        text: &'a str,
+        /// Span of the whole comment.
+        span: Span,
+    },
+    /// Beginning of a start tag or an empty element tag (`<name`).
+    StartTagOpen {
+        /// Element name.
+        name: &'a QnameStr,
+        /// Span of `<name`.
+        span: Span,
+    },
+    /// Attribute in a start tag or an empty element tag (`name="value"`).
+    Attribute {
+        /// Attribute name.
+        name: &'a QnameStr,
+        /// Raw attribute value.
+        value: QuotedLiteral<'a>,
+        /// Span of the whole attribute.
+        span: Span,
+    },
+    /// End of a start tag (`>`) or an empty element tag (`/>`).
+    StartTagClose {
+        /// Whether the tag is an empty element tag.
+        empty: bool,
+        /// Span of `>` or `/>`.
+        span: Span,
+    },
+    /// End tag (`</name>`).
+    EndTag {
+        /// Element name.
+        name: &'a QnameStr,
+        /// Span of the whole end tag.
+        span: Span,
+    },
+    /// Character data.
+    Text {
+        /// Raw text.
+        text: &'a str,
+        /// Span of the text.
+        span: Span,
+    },
+    /// CDATA section (`<![CDATA[text]]>`).
+    Cdata {
+        /// Text without delimiters.
+        text: &'a str,
+        /// Span of the whole CDATA section.
+        span: Span,
+    },
+}
+
+impl<'a> Event<'a> {
+    /// Returns the span of the event in the source text.
+    pub fn span(&self) -> Span {
+        match *self {
+            Event::XmlDecl { span, .. }
+            | Event::Doctype { span, .. }
+            | Event::ProcessingInstruction { span, .. }
+            | Event::Comment { span, .. }
+            | Event::StartTagOpen { span, .. }
+            | Event::Attribute { span, .. }
+            | Event::StartTagClose { span, .. }
+            | Event::EndTag { span, .. }
+            | Event::Text { span, .. }
+            | Event::Cdata { span, .. } => span,
+        }
+    }
+}
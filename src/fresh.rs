@@ -0,0 +1,271 @@
+//! Fresh name generation.
+//!
+//! This is useful to invent namespace prefixes which do not clash with the
+//! in-scope bindings, or to mint unique `ID` values from arbitrary labels.
+
+use std::borrow::ToOwned;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::string::{String, ToString};
+
+use strings::{is_ncname_char, is_ncname_start_char, NcnameStr, NcnameString};
+
+/// Checks whether the string starts with an escape sequence (`_xHHHH_` or
+/// `_xHHHHHHHH_`).
+fn starts_with_escape(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    if !bytes.starts_with(b"_x") {
+        return false;
+    }
+    let digits = bytes[2..]
+        .iter()
+        .take_while(|b| b.is_ascii_hexdigit())
+        .count();
+    (digits == 4 || digits == 8) && bytes.get(2 + digits) == Some(&b'_')
+}
+
+/// Converts the given string into `NCName` by escaping invalid characters.
+///
+/// Invalid characters are escaped as `_xHHHH_` (or `_xHHHHHHHH_` for
+/// characters outside of the BMP), where `HHHH` is the uppercase hexadecimal
+/// code point.
+/// Underscores which would be read as the start of an escape are also
+/// escaped.
+/// The empty string is converted into `_`, and a lone `_` is escaped as
+/// `_x005F_`, so different strings are converted into different names.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::fresh::sanitize_ncname;
+/// assert_eq!(sanitize_ncname("section-1"), "section-1");
+/// assert_eq!(sanitize_ncname("1st section"), "_x0031_st_x0020_section");
+/// assert_eq!(sanitize_ncname("a:b"), "a_x003A_b");
+/// assert_eq!(sanitize_ncname("_x0020_"), "_x005F_x0020_");
+/// assert_eq!(sanitize_ncname(""), "_");
+/// assert_eq!(sanitize_ncname("_"), "_x005F_");
+/// ```
+pub fn sanitize_ncname(s: &str) -> NcnameString {
+    if s.is_empty() {
+        return NcnameString::new("_".to_owned()).expect("Should never fail: `_` is NCName");
+    }
+    let mut escaped = String::with_capacity(s.len());
+    for (pos, c) in s.char_indices() {
+        let valid = if pos == 0 {
+            is_ncname_start_char(c)
+        } else {
+            is_ncname_char(c)
+        };
+        let ambiguous = c == '_' && (s.len() == 1 || starts_with_escape(&s[pos..]));
+        if valid && !ambiguous {
+            escaped.push(c);
+        } else if (c as u32) <= 0xFFFF {
+            write!(escaped, "_x{:04X}_", c as u32).expect("Should never fail");
+        } else {
+            write!(escaped, "_x{:08X}_", c as u32).expect("Should never fail");
+        }
+    }
+    NcnameString::new(escaped).unwrap_or_else(|e| {
+        panic!(
+            "Should never fail: sanitized string should be `NCName`: {}",
+            e
+        )
+    })
+}
+
+/// Converts the given string into a namespace prefix candidate.
+///
+/// Names starting with `xml` in any case are reserved by Namespaces in XML,
+/// so `_` is prepended to them.
+fn sanitize_prefix(s: &str) -> NcnameString {
+    let base = sanitize_ncname(s);
+    let reserved = base
+        .as_bytes()
+        .get(..3)
+        .is_some_and(|head| head.eq_ignore_ascii_case(b"xml"));
+    if !reserved {
+        return base;
+    }
+    let mut prefixed = NcnameString::new("_".to_owned()).expect("Should never fail: `_` is NCName");
+    prefixed.concat(&base);
+    prefixed
+}
+
+/// Generator of fresh `NCName`s.
+///
+/// A name is fresh if it is not taken and not generated by the generator
+/// before.
+/// The taken names are given as a set (by [`reserve`][`Self::reserve`]) or
+/// as a predicate.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::fresh::NameGenerator;
+/// # use xmlop_datatypes::strings::NcnameString;
+/// let mut ids = NameGenerator::new();
+/// ids.reserve(vec![NcnameString::new("intro".to_owned()).unwrap()]);
+/// assert_eq!(ids.generate("intro"), "intro1");
+/// assert_eq!(ids.generate("intro"), "intro2");
+/// assert_eq!(ids.generate("Getting started"), "Getting_x0020_started");
+/// ```
+///
+/// With a predicate, prefixes can avoid the in-scope bindings:
+///
+/// ```rust
+/// # use xmlop_datatypes::fresh::NameGenerator;
+/// # use xmlop_datatypes::namespace::NamespaceContext;
+/// # use xmlop_datatypes::strings::NcnameString;
+/// let mut ctx = NamespaceContext::new();
+/// ctx.push_scope();
+/// let ns0 = NcnameString::new("ns0".to_owned()).unwrap();
+/// ctx.declare(Some(ns0), "http://example.com/".to_owned());
+///
+/// let mut prefixes = NameGenerator::with_predicate(|p| ctx.resolve_prefix(p).is_some());
+/// assert_eq!(prefixes.generate_numbered("ns"), "ns1");
+/// assert_eq!(prefixes.generate_numbered("ns"), "ns2");
+/// ```
+#[derive(Debug, Clone)]
+pub struct NameGenerator<F = fn(&NcnameStr) -> bool> {
+    /// Predicate to check whether the name is taken.
+    is_taken: F,
+    /// Reserved and generated names.
+    used: BTreeSet<NcnameString>,
+}
+
+impl NameGenerator {
+    /// Creates a new `NameGenerator` without taken names.
+    pub fn new() -> Self {
+        Self::with_predicate(|_| false)
+    }
+}
+
+impl Default for NameGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FnMut(&NcnameStr) -> bool> NameGenerator<F> {
+    /// Creates a new `NameGenerator` with the predicate to check whether the
+    /// name is taken.
+    pub fn with_predicate(is_taken: F) -> Self {
+        Self {
+            is_taken,
+            used: BTreeSet::new(),
+        }
+    }
+
+    /// Adds the given names to the taken names.
+    pub fn reserve<I: IntoIterator<Item = NcnameString>>(&mut self, names: I) {
+        self.used.extend(names);
+    }
+
+    /// Checks whether the name is fresh.
+    pub fn is_fresh(&mut self, name: &NcnameStr) -> bool {
+        !self.used.contains(name) && !(self.is_taken)(name)
+    }
+
+    /// Marks the name as used and returns it.
+    fn issue(&mut self, name: NcnameString) -> NcnameString {
+        self.used.insert(name.clone());
+        name
+    }
+
+    /// Returns the sanitized name with the smallest numeric suffix starting
+    /// from `start`.
+    fn generate_from(&mut self, base: NcnameString, start: u64) -> NcnameString {
+        for n in start.. {
+            let mut name = base.clone();
+            name.push_str(&n.to_string())
+                .expect("Should never fail: digits are NCName characters");
+            if self.is_fresh(&name) {
+                return self.issue(name);
+            }
+        }
+        unreachable!("Should never fail: too many names are taken")
+    }
+
+    /// Generates a fresh name from the base.
+    ///
+    /// The base is sanitized by [`sanitize_ncname`], and returned if it is
+    /// fresh.
+    /// Otherwise, the smallest numeric suffix from 1 is appended.
+    pub fn generate(&mut self, base: &str) -> NcnameString {
+        let base = sanitize_ncname(base);
+        if self.is_fresh(&base) {
+            return self.issue(base);
+        }
+        self.generate_from(base, 1)
+    }
+
+    /// Generates a fresh namespace prefix from the base.
+    ///
+    /// This is same as [`generate`][`Self::generate`], except that the result
+    /// never starts with `xml` in any case, which is reserved for prefixes.
+    /// If the sanitized base starts with it, `_` is prepended.
+    pub fn generate_prefix(&mut self, base: &str) -> NcnameString {
+        let base = sanitize_prefix(base);
+        if self.is_fresh(&base) {
+            return self.issue(base);
+        }
+        self.generate_from(base, 1)
+    }
+
+    /// Generates a fresh namespace prefix from the base, always with a numeric
+    /// suffix.
+    ///
+    /// The base is sanitized by [`sanitize_ncname`], and the smallest numeric
+    /// suffix from 0 is appended.
+    /// This is useful for namespace prefixes such as `ns0`, `ns1`, ....
+    /// As [`generate_prefix`][`Self::generate_prefix`], the result never
+    /// starts with `xml` in any case.
+    pub fn generate_numbered(&mut self, base: &str) -> NcnameString {
+        self.generate_from(sanitize_prefix(base), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize() {
+        let cases = [
+            ("abc", "abc"),
+            ("a b", "a_x0020_b"),
+            ("-a", "_x002D_a"),
+            ("a-", "a-"),
+            ("", "_"),
+            ("_", "_x005F_"),
+            ("__", "__"),
+            ("_x12_", "_x12_"),
+            ("_xABCD_", "_x005F_xABCD_"),
+            ("a\u{F0000}", "a_x000F0000_"),
+        ];
+        for &(s, expected) in &cases {
+            assert_eq!(sanitize_ncname(s), expected, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn generate() {
+        let mut gen = NameGenerator::with_predicate(|name| name.as_str() == "p1");
+        assert_eq!(gen.generate("p"), "p");
+        assert_eq!(gen.generate("p"), "p2");
+        assert_eq!(gen.generate_numbered("p"), "p0");
+        assert_eq!(gen.generate_numbered("p"), "p3");
+        assert!(!gen.is_fresh(NcnameStr::new("p3").expect("Should never fail")));
+    }
+
+    #[test]
+    fn generate_non_reserved_prefix() {
+        let mut gen = NameGenerator::new();
+        assert_eq!(gen.generate_numbered("xml"), "_xml0");
+        assert_eq!(gen.generate_numbered("XmLns"), "_XmLns0");
+        assert_eq!(gen.generate_prefix("xmlfoo"), "_xmlfoo");
+        assert_eq!(gen.generate_prefix("xmlfoo"), "_xmlfoo1");
+        assert_eq!(gen.generate_prefix("xm"), "xm");
+        assert_eq!(gen.generate("xml"), "xml");
+    }
+}
@@ -1,11 +1,110 @@
 //! Basic datatypes for xmlop.
+//!
+//! # Features
+//!
+//! * `std` (default): implements `std::error::Error` for the error types and
+//!   enables the modules depending on `std`. Implies `alloc`.
+//! * `alloc`: enables the owned string types, [`strings::Qname`] and the
+//!   modules which allocate.
+//! * `derive`: enables `#[derive(XmlNames)]`, see [`names::XmlNames`].
+//!   Implies `alloc`.
+//!
+//! Without them the crate is `no_std`, and provides the borrowed string types,
+//! the character predicates and the validators.
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+#[macro_use]
+extern crate alloc;
+
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "derive")]
+extern crate xmlop_datatypes_derive;
 #[cfg(feature = "nom-4")]
 #[macro_use]
 extern crate nom;
-extern crate opaque_typedef;
+#[cfg(feature = "proptest")]
+#[cfg_attr(test, macro_use)]
+extern crate proptest;
+#[cfg(feature = "quickcheck")]
+extern crate quickcheck;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+// Lets the derived code refer to this crate in the tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as xmlop_datatypes;
+
+/// Re-exports of `core` and `alloc` as `std` for `no_std` builds.
+///
+/// This lets `std` paths, including the ones in the string type definitions,
+/// resolve without `std`.
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::*;
+
+    #[cfg(feature = "alloc")]
+    pub use alloc::{borrow, boxed, collections, fmt, rc, string, sync, vec};
+}
+
 #[macro_use]
-extern crate opaque_typedef_macros;
+mod macros;
+
+mod literal;
 
+/// Items used by the public macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use literal::{check_literal, LiteralKind};
+    #[cfg(feature = "alloc")]
+    pub use names::hash_name;
+    pub use std::borrow::Borrow;
+    #[cfg(feature = "alloc")]
+    pub use std::borrow::{Cow, ToOwned};
+    #[cfg(feature = "alloc")]
+    pub use std::boxed::Box;
+    pub use std::cmp::{Ordering, PartialEq, PartialOrd};
+    pub use std::convert::{AsRef, From, TryFrom};
+    pub use std::fmt;
+    pub use std::ops::Deref;
+    pub use std::option::Option;
+    #[cfg(feature = "alloc")]
+    pub use std::rc::Rc;
+    pub use std::result::Result::{self, Err, Ok};
+    #[cfg(feature = "alloc")]
+    pub use std::string::String;
+    #[cfg(feature = "alloc")]
+    pub use std::sync::Arc;
+}
+
+#[cfg(feature = "alloc")]
+pub mod diagnostic;
+#[cfg(feature = "alloc")]
+pub mod encoding;
+#[cfg(feature = "alloc")]
+pub mod entity;
+#[cfg(feature = "alloc")]
+pub mod eol;
+#[cfg(feature = "alloc")]
+pub mod escape;
+#[cfg(feature = "alloc")]
+pub mod event;
+#[cfg(feature = "alloc")]
+pub mod fresh;
+#[cfg(feature = "alloc")]
+pub mod names;
+#[cfg(feature = "alloc")]
+pub mod namespace;
+pub mod position;
 pub mod strings;
+pub mod syntax;
+#[cfg(feature = "nom-4")]
+pub mod tokenizer;
+pub mod version;
+#[cfg(feature = "alloc")]
+pub mod wf;
+#[cfg(feature = "std")]
+pub mod writer;
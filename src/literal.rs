@@ -0,0 +1,244 @@
+//! Compile-time validation of name literals.
+//!
+//! This is used by the name literal macros such as [`ncname!`].
+
+use strings::{is_name_char, is_name_start_char, is_ncname_char, is_ncname_start_char};
+
+/// Kind of a name literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralKind {
+    /// `Name`.
+    Name,
+    /// `NCName`.
+    Ncname,
+    /// `QName`.
+    Qname,
+}
+
+impl LiteralKind {
+    /// Returns the name of the production.
+    const fn name(self) -> &'static str {
+        match self {
+            LiteralKind::Name => "Name",
+            LiteralKind::Ncname => "NCName",
+            LiteralKind::Qname => "QName",
+        }
+    }
+}
+
+/// Decodes the character at the given byte position of the valid UTF-8.
+///
+/// Returns the character and its length.
+const fn decode_char(bytes: &[u8], pos: usize) -> (char, usize) {
+    let b0 = bytes[pos] as u32;
+    let (len, init) = if b0 < 0x80 {
+        (1, b0)
+    } else if b0 < 0xE0 {
+        (2, b0 & 0x1F)
+    } else if b0 < 0xF0 {
+        (3, b0 & 0x0F)
+    } else {
+        (4, b0 & 0x07)
+    };
+    let mut code = init;
+    let mut i = 1;
+    while i < len {
+        code = (code << 6) | (bytes[pos + i] as u32 & 0x3F);
+        i += 1;
+    }
+    match char::from_u32(code) {
+        Some(c) => (c, len),
+        None => panic!("Should never fail: the string is valid UTF-8"),
+    }
+}
+
+/// Returns the byte position of the first invalid character, or the string
+/// length if the string is valid.
+const fn invalid_position(s: &str, kind: LiteralKind) -> usize {
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    // Whether the next character starts a NCName part.
+    let mut part_start = true;
+    let mut colon = None;
+    while pos < bytes.len() {
+        let (c, len) = decode_char(bytes, pos);
+        let valid = match kind {
+            LiteralKind::Name if pos == 0 => is_name_start_char(c),
+            LiteralKind::Name => is_name_char(c),
+            LiteralKind::Ncname if pos == 0 => is_ncname_start_char(c),
+            LiteralKind::Ncname => is_ncname_char(c),
+            LiteralKind::Qname if c == ':' => !part_start && colon.is_none(),
+            LiteralKind::Qname if part_start => is_ncname_start_char(c),
+            LiteralKind::Qname => is_ncname_char(c),
+        };
+        if !valid {
+            return pos;
+        }
+        part_start = c == ':';
+        if part_start {
+            colon = Some(pos);
+        }
+        pos += len;
+    }
+    match (kind, colon) {
+        // Trailing colon of QName.
+        (LiteralKind::Qname, Some(colon)) if part_start => colon,
+        _ => pos,
+    }
+}
+
+/// Error message buffer.
+struct Message {
+    /// Buffer.
+    buf: [u8; 128],
+    /// Length of the message.
+    len: usize,
+}
+
+impl Message {
+    /// Creates an empty message.
+    const fn new() -> Self {
+        Self {
+            buf: [0; 128],
+            len: 0,
+        }
+    }
+
+    /// Appends the string, truncating at a character boundary if it overflows.
+    const fn push_str(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            let (_, len) = decode_char(bytes, i);
+            if self.len + len > self.buf.len() {
+                break;
+            }
+            let mut j = 0;
+            while j < len {
+                self.buf[self.len] = bytes[i + j];
+                self.len += 1;
+                j += 1;
+            }
+            i += len;
+        }
+        self
+    }
+
+    /// Appends the decimal number.
+    const fn push_usize(self, mut n: usize) -> Self {
+        let mut digits = [0u8; 20];
+        let mut start = digits.len();
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        match ::std::str::from_utf8(digits.split_at(start).1) {
+            Ok(s) => self.push_str(s),
+            Err(_) => panic!("Should never fail: digits are ASCII"),
+        }
+    }
+
+    /// Returns the message.
+    const fn as_str(&self) -> &str {
+        match ::std::str::from_utf8(self.buf.split_at(self.len).0) {
+            Ok(s) => s,
+            Err(_) => panic!("Should never fail: the message is truncated at a boundary"),
+        }
+    }
+}
+
+/// Checks the literal, and panics with a message naming the invalid character.
+///
+/// Evaluated in a constant, the panic is a compile error.
+pub const fn check_literal(s: &str, kind: LiteralKind) {
+    if s.is_empty() {
+        let msg = Message::new()
+            .push_str("empty string is not a valid ")
+            .push_str(kind.name());
+        panic!("{}", msg.as_str());
+    }
+    let pos = invalid_position(s, kind);
+    if pos < s.len() {
+        let (_, rest) = s.split_at(pos);
+        let (_, len) = decode_char(rest.as_bytes(), 0);
+        let (c, _) = rest.split_at(len);
+        let msg = Message::new()
+            .push_str("invalid ")
+            .push_str(kind.name())
+            .push_str(" literal: character `")
+            .push_str(c)
+            .push_str("` at byte ")
+            .push_usize(pos)
+            .push_str(" is not allowed");
+        panic!("{}", msg.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strings::{NameError, NameStr, NcnameStr, QnameStr};
+
+    #[test]
+    fn same_as_validators() {
+        let cases = [
+            "a",
+            "a:b",
+            ":a",
+            "a:",
+            "a:b:c",
+            "-a",
+            "a-1",
+            "\u{E9}t\u{E9}",
+            "a b",
+            "1",
+            "a:1",
+            "\u{10000}",
+        ];
+        for s in &cases {
+            let expected = |res: Result<(), NameError>| match res {
+                Ok(()) => s.len(),
+                Err(NameError::InvalidNameChar(pos, _)) => pos,
+                Err(e) => panic!("Unexpected error: {}", e),
+            };
+            assert_eq!(
+                invalid_position(s, LiteralKind::Name),
+                expected(NameStr::new(s).map(|_| ())),
+                "{:?}",
+                s
+            );
+            assert_eq!(
+                invalid_position(s, LiteralKind::Ncname),
+                expected(NcnameStr::new(s).map(|_| ())),
+                "{:?}",
+                s
+            );
+            assert_eq!(
+                invalid_position(s, LiteralKind::Qname),
+                expected(QnameStr::new(s).map(|_| ())),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn message() {
+        let msg = Message::new().push_str("at byte ").push_usize(120);
+        assert_eq!(msg.as_str(), "at byte 120");
+    }
+
+    #[test]
+    fn literal_macros() {
+        let name: &'static NameStr = name!("xml:lang");
+        assert_eq!(name, "xml:lang");
+        let ncname: &'static NcnameStr = ncname!("svg");
+        assert_eq!(ncname, "svg");
+        let qname: &'static QnameStr = qname!("xlink:href");
+        assert_eq!(qname.prefix().map(|p| p.as_str()), Some("xlink"));
+    }
+}
@@ -0,0 +1,666 @@
+//! Public macros.
+
+/// Defines a validated borrowed string type and optionally its owned version.
+///
+/// The validator is a function which takes `&str` and returns `Result<_, E>`,
+/// where `E` is the given error type.
+/// The borrowed type is a `#[repr(transparent)]` wrapper of `str`, and the
+/// owned type is a wrapper of `String`.
+///
+/// The generated types have the following items:
+///
+/// * Borrowed type:
+///     + `new(&str) -> Result<&Self, E>` and unsafe `from_str_unchecked(&str)`.
+///     + `as_str()`, `Deref<Target = str>`, `AsRef<str>`, `Display`,
+///       `TryFrom<&str>`, `From<&Self>` for `&str`, and comparisons with
+///       itself, `str` and `&str`.
+///     + With the `alloc` feature of this crate, `From<&Self>` for `String`,
+///       `Box<Self>`, `Rc<Self>` and `Arc<Self>`, and comparisons with
+///       `String` and `Cow<str>`.
+/// * Owned type:
+///     + `new(String) -> Result<Self, E>` and unsafe `new_unchecked(String)`.
+///     + `as_str()`, `into_string()`, `Deref` to the borrowed type, `Borrow`,
+///       `ToOwned` for the borrowed type, `AsRef` to the borrowed type,
+///       `String` and `str`, `Display`, `TryFrom<String>`, `From` the
+///       borrowed type, `From<Self>` for `String`, and comparisons with
+///       itself, the borrowed type, `str`, `&str` and `String`.
+///     + Comparisons of the borrowed type with `Cow` of itself.
+///
+/// The owned type requires the `alloc` feature of this crate.
+/// The string types of this crate are defined with the same set of items.
+///
+/// # Examples
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate xmlop_datatypes;
+///
+/// /// Error of language tags.
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// pub struct LanguageTagError;
+///
+/// fn validate_language_tag(s: &str) -> Result<(), LanguageTagError> {
+///     let valid = s.split('-').all(|sub| {
+///         (1..=8).contains(&sub.len()) && sub.bytes().all(|b| b.is_ascii_alphanumeric())
+///     });
+///     if valid {
+///         Ok(())
+///     } else {
+///         Err(LanguageTagError)
+///     }
+/// }
+///
+/// # #[cfg(feature = "alloc")]
+/// define_xml_string! {
+///     validator = validate_language_tag;
+///     error = LanguageTagError;
+///     /// Borrowed language tag.
+///     pub struct LanguageTagStr(str);
+///     /// Owned language tag.
+///     pub struct LanguageTagString(String);
+/// }
+///
+/// # fn main() {
+/// # #[cfg(feature = "alloc")]
+/// # {
+/// let tag = LanguageTagStr::new("en-US").unwrap();
+/// assert_eq!(tag, "en-US");
+/// assert!(LanguageTagStr::new("en_US").is_err());
+///
+/// let owned: LanguageTagString = tag.to_owned();
+/// assert_eq!(owned, *tag);
+/// assert_eq!(owned.into_string(), "en-US");
+/// # }
+/// # }
+/// ```
+///
+/// The macro can also be invoked by path, without `#[macro_use]`.
+///
+/// ```rust
+/// extern crate xmlop_datatypes;
+///
+/// fn validate_digits(s: &str) -> Result<(), ()> {
+///     if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+///         Ok(())
+///     } else {
+///         Err(())
+///     }
+/// }
+///
+/// xmlop_datatypes::define_xml_string! {
+///     validator = validate_digits;
+///     error = ();
+///     /// Borrowed digits.
+///     pub struct DigitsStr(str);
+/// }
+///
+/// # fn main() {
+/// let digits = DigitsStr::new("42").unwrap();
+/// assert_eq!(digits, "42");
+/// assert!(DigitsStr::new("4a").is_err());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! define_xml_string {
+    (
+        validator = $validator:path;
+        error = $error:ty;
+        $(#[$borrowed_meta:meta])*
+        $vis:vis struct $borrowed:ident(str);
+    ) => {
+        $crate::define_xml_string!(@borrowed, $validator, $error, $borrowed, $vis, $(#[$borrowed_meta])*);
+    };
+    (
+        validator = $validator:path;
+        error = $error:ty;
+        $(#[$borrowed_meta:meta])*
+        $vis:vis struct $borrowed:ident(str);
+        $(#[$owned_meta:meta])*
+        $owned_vis:vis struct $owned:ident(String);
+    ) => {
+        $crate::define_xml_string!(@borrowed, $validator, $error, $borrowed, $vis, $(#[$borrowed_meta])*);
+        $crate::define_xml_string!(
+            @owned, $validator, $error, $borrowed, $owned, $owned_vis, $(#[$owned_meta])*
+        );
+    };
+    (@borrowed, $validator:path, $error:ty, $borrowed:ident, $vis:vis, $(#[$meta:meta])*) => {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        $vis struct $borrowed(str);
+
+        impl $borrowed {
+            /// Creates a new string slice after validation.
+            pub fn new(s: &str) -> $crate::__private::Result<&Self, $error> {
+                match $validator(s) {
+                    $crate::__private::Ok(_) => $crate::__private::Ok(unsafe {
+                        // This is safe because the string is validated.
+                        Self::from_str_unchecked(s)
+                    }),
+                    $crate::__private::Err(e) => $crate::__private::Err(e),
+                }
+            }
+
+            /// Creates a new string slice without validation.
+            ///
+            /// # Safety
+            ///
+            /// The string should be valid for the validator.
+            /// If this constraint is violated, undefined behavior results.
+            pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+                // This is safe because the type is `#[repr(transparent)]`.
+                &*(s as *const str as *const Self)
+            }
+
+            /// Returns the string slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl<'a> $crate::__private::TryFrom<&'a str> for &'a $borrowed {
+            type Error = $error;
+
+            fn try_from(s: &'a str) -> $crate::__private::Result<Self, $error> {
+                $borrowed::new(s)
+            }
+        }
+
+        $crate::define_xml_string!(@borrowed_impls, $borrowed);
+    };
+    (
+        @owned, $validator:path, $error:ty, $borrowed:ident, $owned:ident, $vis:vis,
+        $(#[$meta:meta])*
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis struct $owned($crate::__private::String);
+
+        impl $owned {
+            /// Creates a new owned string after validation.
+            pub fn new(s: $crate::__private::String) -> $crate::__private::Result<Self, $error> {
+                match $validator(&s) {
+                    $crate::__private::Ok(_) => $crate::__private::Ok($owned(s)),
+                    $crate::__private::Err(e) => $crate::__private::Err(e),
+                }
+            }
+
+            /// Creates a new owned string without validation.
+            ///
+            /// # Safety
+            ///
+            /// See the `from_str_unchecked` function of the borrowed type.
+            pub unsafe fn new_unchecked(s: $crate::__private::String) -> Self {
+                $owned(s)
+            }
+
+            /// Returns the string slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Returns the inner string.
+            pub fn into_string(self) -> $crate::__private::String {
+                self.0
+            }
+        }
+
+        impl $crate::__private::TryFrom<$crate::__private::String> for $owned {
+            type Error = $error;
+
+            fn try_from(s: $crate::__private::String) -> $crate::__private::Result<Self, $error> {
+                $owned::new(s)
+            }
+        }
+
+        $crate::define_xml_string!(@owned_impls, $borrowed, $owned);
+    };
+    // Trait impls of the borrowed type `struct $borrowed(str)`.
+    (@borrowed_impls, $borrowed:ident) => {
+        impl $crate::__private::Deref for $borrowed {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl $crate::__private::AsRef<str> for $borrowed {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl $crate::__private::AsRef<$borrowed> for $borrowed {
+            fn as_ref(&self) -> &$borrowed {
+                self
+            }
+        }
+
+        impl $crate::__private::fmt::Display for $borrowed {
+            fn fmt(
+                &self,
+                f: &mut $crate::__private::fmt::Formatter,
+            ) -> $crate::__private::fmt::Result {
+                $crate::__private::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl<'a> $crate::__private::From<&'a $borrowed> for &'a str {
+            fn from(s: &'a $borrowed) -> Self {
+                &s.0
+            }
+        }
+
+        $crate::define_xml_string!(@cmp, $borrowed, &'a $borrowed, $borrowed);
+        $crate::define_xml_string!(@cmp, str, $borrowed, str);
+        $crate::define_xml_string!(@cmp, str, $borrowed, &'a str);
+        $crate::define_xml_string!(@cmp, str, &'a $borrowed, str);
+
+        $crate::__define_xml_string_if_alloc! {
+            $crate::define_xml_string!(@borrowed_alloc_impls, $borrowed);
+        }
+    };
+    // Trait impls of the borrowed type which require the `alloc` feature.
+    (@borrowed_alloc_impls, $borrowed:ident) => {
+        impl<'a> $crate::__private::From<&'a $borrowed> for $crate::__private::String {
+            fn from(s: &'a $borrowed) -> Self {
+                $crate::__private::From::from(&s.0)
+            }
+        }
+
+        impl<'a> $crate::__private::From<&'a $borrowed> for $crate::__private::Box<$borrowed> {
+            fn from(s: &'a $borrowed) -> Self {
+                let raw = $crate::__private::Box::into_raw(
+                    $crate::__private::Box::<str>::from(&s.0),
+                );
+                unsafe {
+                    // This is safe because the type is `#[repr(transparent)]`.
+                    $crate::__private::Box::from_raw(raw as *mut $borrowed)
+                }
+            }
+        }
+
+        impl<'a> $crate::__private::From<&'a $borrowed> for $crate::__private::Rc<$borrowed> {
+            fn from(s: &'a $borrowed) -> Self {
+                let raw = $crate::__private::Rc::into_raw(
+                    $crate::__private::Rc::<str>::from(&s.0),
+                );
+                unsafe {
+                    // This is safe because the type is `#[repr(transparent)]`.
+                    $crate::__private::Rc::from_raw(raw as *const $borrowed)
+                }
+            }
+        }
+
+        impl<'a> $crate::__private::From<&'a $borrowed> for $crate::__private::Arc<$borrowed> {
+            fn from(s: &'a $borrowed) -> Self {
+                let raw = $crate::__private::Arc::into_raw(
+                    $crate::__private::Arc::<str>::from(&s.0),
+                );
+                unsafe {
+                    // This is safe because the type is `#[repr(transparent)]`.
+                    $crate::__private::Arc::from_raw(raw as *const $borrowed)
+                }
+            }
+        }
+
+        $crate::define_xml_string!(@cmp, str, $borrowed, $crate::__private::String);
+        $crate::define_xml_string!(@cmp, str, &'a $borrowed, $crate::__private::String);
+        $crate::define_xml_string!(@cmp, str, $borrowed, &'a $crate::__private::String);
+        $crate::define_xml_string!(@cmp, str, $borrowed, $crate::__private::Cow<'a, str>);
+        $crate::define_xml_string!(
+            @cmp, str, &'b $borrowed, $crate::__private::Cow<'a, str>, 'b
+        );
+    };
+    // Trait impls of the owned type `struct $owned(String)`.
+    (@owned_impls, $borrowed:ident, $owned:ident) => {
+        impl $crate::__private::ToOwned for $borrowed {
+            type Owned = $owned;
+
+            fn to_owned(&self) -> $owned {
+                // This is valid because `self.0` is validated at `self` creation.
+                $owned($crate::__private::ToOwned::to_owned(&self.0))
+            }
+        }
+
+        impl $crate::__private::Deref for $owned {
+            type Target = $borrowed;
+
+            fn deref(&self) -> &$borrowed {
+                let s: &str = &self.0;
+                unsafe {
+                    // This is safe because the type is `#[repr(transparent)]`
+                    // and `self.0` is validated at `self` creation.
+                    &*(s as *const str as *const $borrowed)
+                }
+            }
+        }
+
+        impl $crate::__private::Borrow<$borrowed> for $owned {
+            fn borrow(&self) -> &$borrowed {
+                self
+            }
+        }
+
+        impl $crate::__private::AsRef<$borrowed> for $owned {
+            fn as_ref(&self) -> &$borrowed {
+                self
+            }
+        }
+
+        impl $crate::__private::AsRef<$crate::__private::String> for $owned {
+            fn as_ref(&self) -> &$crate::__private::String {
+                &self.0
+            }
+        }
+
+        impl $crate::__private::AsRef<str> for $owned {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl<'a> $crate::__private::From<&'a $borrowed> for $owned {
+            fn from(s: &'a $borrowed) -> Self {
+                $crate::__private::ToOwned::to_owned(s)
+            }
+        }
+
+        impl $crate::__private::From<$owned> for $crate::__private::String {
+            fn from(s: $owned) -> Self {
+                s.0
+            }
+        }
+
+        impl $crate::__private::fmt::Display for $owned {
+            fn fmt(
+                &self,
+                f: &mut $crate::__private::fmt::Formatter,
+            ) -> $crate::__private::fmt::Result {
+                $crate::__private::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        $crate::define_xml_string!(@cmp, $borrowed, &'a $owned, $owned);
+        $crate::define_xml_string!(@cmp, $borrowed, $owned, $borrowed);
+        $crate::define_xml_string!(@cmp, $borrowed, $owned, &'a $borrowed);
+        $crate::define_xml_string!(@cmp, $borrowed, &'a $owned, $borrowed);
+        $crate::define_xml_string!(@cmp, str, $owned, str);
+        $crate::define_xml_string!(@cmp, str, $owned, &'a str);
+        $crate::define_xml_string!(@cmp, str, &'a $owned, str);
+        $crate::define_xml_string!(@cmp, str, $owned, $crate::__private::String);
+        $crate::define_xml_string!(@cmp, str, &'a $owned, $crate::__private::String);
+        $crate::define_xml_string!(@cmp, str, $owned, &'a $crate::__private::String);
+        $crate::define_xml_string!(
+            @cmp, $borrowed, $borrowed, $crate::__private::Cow<'a, $borrowed>
+        );
+        $crate::define_xml_string!(
+            @cmp, $borrowed, &'b $borrowed, $crate::__private::Cow<'a, $borrowed>, 'b
+        );
+    };
+    (@cmp, $inner:ty, $lhs:ty, $rhs:ty $(, $lt:lifetime)*) => {
+        impl<'a $(, $lt)*> $crate::__private::PartialEq<$rhs> for $lhs {
+            fn eq(&self, rhs: &$rhs) -> bool {
+                $crate::__private::AsRef::<$inner>::as_ref(self)
+                    == $crate::__private::AsRef::<$inner>::as_ref(rhs)
+            }
+        }
+
+        impl<'a $(, $lt)*> $crate::__private::PartialEq<$lhs> for $rhs {
+            fn eq(&self, rhs: &$lhs) -> bool {
+                $crate::__private::AsRef::<$inner>::as_ref(self)
+                    == $crate::__private::AsRef::<$inner>::as_ref(rhs)
+            }
+        }
+
+        impl<'a $(, $lt)*> $crate::__private::PartialOrd<$rhs> for $lhs {
+            fn partial_cmp(
+                &self,
+                rhs: &$rhs,
+            ) -> $crate::__private::Option<$crate::__private::Ordering> {
+                $crate::__private::AsRef::<$inner>::as_ref(self)
+                    .partial_cmp($crate::__private::AsRef::<$inner>::as_ref(rhs))
+            }
+        }
+
+        impl<'a $(, $lt)*> $crate::__private::PartialOrd<$lhs> for $rhs {
+            fn partial_cmp(
+                &self,
+                rhs: &$lhs,
+            ) -> $crate::__private::Option<$crate::__private::Ordering> {
+                $crate::__private::AsRef::<$inner>::as_ref(self)
+                    .partial_cmp($crate::__private::AsRef::<$inner>::as_ref(rhs))
+            }
+        }
+    };
+}
+
+/// Expands the items only if the `alloc` feature of this crate is enabled.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_xml_string_if_alloc {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+/// Expands the items only if the `alloc` feature of this crate is enabled.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_xml_string_if_alloc {
+    ($($item:item)*) => {};
+}
+
+/// Creates a `&'static NameStr` from a string literal checked at compile time.
+///
+/// The literal is validated by the same rules as [`is_name_start_char`] and
+/// [`is_name_char`], and an invalid literal is a compile error naming the
+/// invalid character.
+///
+/// [`is_name_start_char`]: strings/fn.is_name_start_char.html
+/// [`is_name_char`]: strings/fn.is_name_char.html
+///
+/// # Examples
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate xmlop_datatypes;
+///
+/// use xmlop_datatypes::strings::NameStr;
+///
+/// # fn main() {
+/// let lang: &'static NameStr = name!("xml:lang");
+/// assert_eq!(lang, "xml:lang");
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// #[macro_use]
+/// extern crate xmlop_datatypes;
+///
+/// # fn main() {
+/// let _ = name!("1st");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! name {
+    ($s:expr) => {{
+        const S: &str = $s;
+        const _: () = $crate::__private::check_literal(S, $crate::__private::LiteralKind::Name);
+        // This is safe because `S` is checked at compile time above.
+        unsafe { $crate::strings::NameStr::from_str_unchecked(S) }
+    }};
+}
+
+/// Creates a `&'static NcnameStr` from a string literal checked at compile time.
+///
+/// The literal is validated by the same rules as [`is_ncname_start_char`] and
+/// [`is_ncname_char`], and an invalid literal is a compile error naming the
+/// invalid character.
+///
+/// [`is_ncname_start_char`]: strings/fn.is_ncname_start_char.html
+/// [`is_ncname_char`]: strings/fn.is_ncname_char.html
+///
+/// # Examples
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate xmlop_datatypes;
+///
+/// use xmlop_datatypes::strings::NcnameStr;
+///
+/// # fn main() {
+/// let svg: &'static NcnameStr = ncname!("svg");
+/// assert_eq!(svg, "svg");
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// #[macro_use]
+/// extern crate xmlop_datatypes;
+///
+/// # fn main() {
+/// let _ = ncname!("xml:lang");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ncname {
+    ($s:expr) => {{
+        const S: &str = $s;
+        const _: () = $crate::__private::check_literal(S, $crate::__private::LiteralKind::Ncname);
+        // This is safe because `S` is checked at compile time above.
+        unsafe { $crate::strings::NcnameStr::from_str_unchecked(S) }
+    }};
+}
+
+/// Creates a `&'static QnameStr` from a string literal checked at compile time.
+///
+/// The literal should be an NCName, or two NCNames joined by a colon.
+/// An invalid literal is a compile error naming the invalid character.
+///
+/// # Examples
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate xmlop_datatypes;
+///
+/// use xmlop_datatypes::strings::QnameStr;
+///
+/// # fn main() {
+/// let href: &'static QnameStr = qname!("xlink:href");
+/// assert_eq!(href, "xlink:href");
+/// assert_eq!(href.prefix().map(|p| p.as_str()), Some("xlink"));
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// #[macro_use]
+/// extern crate xmlop_datatypes;
+///
+/// # fn main() {
+/// let _ = qname!("a:b:c");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! qname {
+    ($s:expr) => {{
+        const S: &str = $s;
+        const _: () = $crate::__private::check_literal(S, $crate::__private::LiteralKind::Qname);
+        // This is safe because `S` is checked at compile time above.
+        unsafe { $crate::strings::QnameStr::from_str_unchecked(S) }
+    }};
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::{Borrow, Cow, ToOwned};
+    use std::boxed::Box;
+    use std::convert::TryFrom;
+    use std::rc::Rc;
+    use std::string::{String, ToString};
+    use std::sync::Arc;
+
+    /// Validates the given string as a lowercase ASCII word.
+    fn validate_word(s: &str) -> Result<(), usize> {
+        match s.bytes().position(|b| !b.is_ascii_lowercase()) {
+            Some(pos) => Err(pos),
+            None if s.is_empty() => Err(0),
+            None => Ok(()),
+        }
+    }
+
+    define_xml_string! {
+        validator = validate_word;
+        error = usize;
+        /// Borrowed word.
+        pub struct WordStr(str);
+        /// Owned word.
+        pub struct WordString(String);
+    }
+
+    #[test]
+    fn custom_string() {
+        let word = WordStr::new("abc").expect("Should never fail");
+        assert_eq!(word, "abc");
+        assert_eq!("abc", word);
+        assert!(word < "abd");
+        assert_eq!(WordStr::new("aBc"), Err(1));
+        assert_eq!(<&WordStr>::try_from(""), Err(0));
+
+        let owned = word.to_owned();
+        assert_eq!(owned, *word);
+        assert_eq!(owned, word);
+        assert_eq!(owned.len(), 3);
+        assert_eq!(WordString::try_from("a1".to_owned()), Err(1));
+        assert_eq!(String::from(owned), "abc");
+    }
+
+    #[test]
+    fn custom_string_impls() {
+        let word = WordStr::new("abc").expect("Should never fail");
+        let owned = word.to_owned();
+        let string = "abc".to_owned();
+
+        // Conversions.
+        assert_eq!(<&str>::from(word), "abc");
+        assert_eq!(String::from(word), "abc");
+        assert_eq!(&*Box::<WordStr>::from(word), word);
+        assert_eq!(&*Rc::<WordStr>::from(word), word);
+        assert_eq!(&*Arc::<WordStr>::from(word), word);
+        assert_eq!(WordString::from(word), owned);
+        assert_eq!(AsRef::<str>::as_ref(word), "abc");
+        assert_eq!(AsRef::<str>::as_ref(&owned), "abc");
+        assert_eq!(AsRef::<String>::as_ref(&owned), &string);
+        assert_eq!(AsRef::<WordStr>::as_ref(&owned), word);
+        assert_eq!(Borrow::<WordStr>::borrow(&owned), word);
+        assert_eq!(owned.to_string(), "abc");
+
+        // Comparisons with the borrowed type.
+        assert_eq!(word, *word);
+        assert_eq!(&owned, word);
+        assert_eq!(owned, word);
+        assert_eq!(&owned, &owned.clone());
+        assert!(word < WordStr::new("abd").expect("Should never fail"));
+
+        // Comparisons with string types.
+        assert_eq!(word, *"abc");
+        assert_eq!(word, string);
+        assert_eq!(&string, word);
+        assert_eq!(*word, string);
+        assert_eq!(owned, *"abc");
+        assert_eq!(&owned, "abc");
+        assert_eq!(owned, string);
+        assert_eq!(&string, owned);
+        assert!(owned < string.clone() + "d");
+
+        // Comparisons with `Cow`.
+        assert_eq!(*word, Cow::Borrowed("abc"));
+        assert_eq!(Cow::<str>::Owned(string.clone()), word);
+        assert_eq!(*word, Cow::Borrowed(word));
+        assert_eq!(Cow::<WordStr>::Owned(owned.clone()), word);
+    }
+}
@@ -0,0 +1,186 @@
+//! Known XML names.
+
+use namespace::ExpandedName;
+use strings::{NcnameStr, QnameStr};
+
+#[cfg(feature = "derive")]
+pub use xmlop_datatypes_derive::XmlNames;
+
+/// Vocabulary of known XML names, such as element names of XHTML or SVG.
+///
+/// Each value has a distinct local name, optionally with a namespace and a
+/// prefix.
+///
+/// With `derive` feature, this can be derived for fieldless enums.
+/// Each variant should have `#[xml(name = "...")]` attribute, and the enum or
+/// the variants can have `namespace = "..."` and `prefix = "..."`.
+/// The namespace name should not be empty.
+/// `no_namespace` on a variant clears the namespace and the prefix of the enum.
+/// The derived `from_ncname` uses a perfect hash built at compile time, and the
+/// names are checked at compile time.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # mod example {
+/// use xmlop_datatypes::names::XmlNames;
+/// use xmlop_datatypes::strings::NcnameStr;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, XmlNames)]
+/// #[xml(namespace = "http://www.w3.org/2000/svg")]
+/// enum SvgName {
+///     #[xml(name = "svg")]
+///     Svg,
+///     #[xml(name = "circle")]
+///     Circle,
+///     #[xml(name = "href", namespace = "http://www.w3.org/1999/xlink", prefix = "xlink")]
+///     Href,
+///     #[xml(name = "viewBox", no_namespace)]
+///     ViewBox,
+/// }
+///
+/// pub fn run() {
+///     assert_eq!(SvgName::Circle.as_ncname(), "circle");
+///     assert_eq!(SvgName::Href.as_qname(), "xlink:href");
+///     assert_eq!(SvgName::Href.namespace(), Some("http://www.w3.org/1999/xlink"));
+///     assert_eq!(SvgName::ViewBox.namespace(), None);
+///     assert_eq!(
+///         SvgName::Svg.expanded_name().to_string(),
+///         "{http://www.w3.org/2000/svg}svg"
+///     );
+///
+///     let circle = NcnameStr::new("circle").unwrap();
+///     assert_eq!(SvgName::from_ncname(circle), Some(SvgName::Circle));
+///     let rect = NcnameStr::new("rect").unwrap();
+///     assert_eq!(SvgName::from_ncname(rect), None);
+/// }
+/// # }
+/// # #[cfg(feature = "derive")]
+/// # example::run();
+/// ```
+pub trait XmlNames: Sized {
+    /// Returns the local name.
+    fn as_ncname(&self) -> &'static NcnameStr;
+
+    /// Returns the QName, the local name with the prefix if any.
+    fn as_qname(&self) -> &'static QnameStr;
+
+    /// Returns the namespace name.
+    fn namespace(&self) -> Option<&'static str>;
+
+    /// Returns the value with the given local name.
+    fn from_ncname(name: &NcnameStr) -> Option<Self>;
+
+    /// Returns the expanded name.
+    fn expanded_name(&self) -> ExpandedName<'static> {
+        ExpandedName::new(self.namespace(), self.as_ncname())
+    }
+
+    /// Returns the value with the given expanded name.
+    fn from_expanded_name(name: &ExpandedName) -> Option<Self> {
+        Self::from_ncname(name.local()).filter(|v| v.namespace() == name.namespace())
+    }
+}
+
+/// Hashes the name with the seed, for the perfect hash of derived `XmlNames`.
+///
+/// This should be identical to the one in `xmlop-datatypes-derive`, and the
+/// tests of both crates check the same hashes.
+#[doc(hidden)]
+pub fn hash_name(s: &str, seed: u32) -> u32 {
+    let mut hash = 0x811c_9dc5 ^ seed;
+    for &b in s.as_bytes() {
+        hash ^= u32::from(b);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::*;
+    use namespace::XML_NAMESPACE;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, XmlNames)]
+    #[xml(namespace = "http://www.w3.org/1999/xhtml")]
+    enum HtmlName {
+        #[xml(name = "html")]
+        Html,
+        #[xml(name = "head")]
+        Head,
+        #[xml(name = "body")]
+        Body,
+        #[xml(name = "p")]
+        P,
+        #[xml(name = "a")]
+        A,
+        #[xml(
+            name = "lang",
+            namespace = "http://www.w3.org/XML/1998/namespace",
+            prefix = "xml"
+        )]
+        XmlLang,
+        #[xml(name = "class", no_namespace)]
+        Class,
+    }
+
+    #[derive(Debug, PartialEq, Eq, XmlNames)]
+    enum Empty {}
+
+    const ALL: [HtmlName; 7] = [
+        HtmlName::Html,
+        HtmlName::Head,
+        HtmlName::Body,
+        HtmlName::P,
+        HtmlName::A,
+        HtmlName::XmlLang,
+        HtmlName::Class,
+    ];
+
+    fn ncname(s: &str) -> &NcnameStr {
+        NcnameStr::new(s).expect("Should never fail")
+    }
+
+    /// Hashes to be produced by both this crate and `xmlop-datatypes-derive`.
+    ///
+    /// Keep in sync with the test in `xmlop-datatypes-derive`.
+    const HASHES: [(&str, u32, u32); 5] = [
+        ("", 0, 0x5db4_a501),
+        ("svg", 0, 0xb88f_7b77),
+        ("xlink:href", 1, 0x57bc_137a),
+        ("viewBox", 42, 0x90e9_c2cf),
+        ("\u{E9}l\u{E9}ment", 0xdead_beef, 0x75fe_97fe),
+    ];
+
+    #[test]
+    fn hash_name_matches_derive() {
+        for &(s, seed, hash) in &HASHES {
+            assert_eq!(hash_name(s, seed), hash, "{:?} with seed {}", s, seed);
+        }
+    }
+
+    #[test]
+    fn derived_names() {
+        for &name in &ALL {
+            assert_eq!(HtmlName::from_ncname(name.as_ncname()), Some(name));
+            assert_eq!(
+                HtmlName::from_expanded_name(&name.expanded_name()),
+                Some(name)
+            );
+        }
+        assert_eq!(HtmlName::XmlLang.as_ncname(), "lang");
+        assert_eq!(HtmlName::XmlLang.as_qname(), "xml:lang");
+        assert_eq!(HtmlName::XmlLang.namespace(), Some(XML_NAMESPACE));
+        assert_eq!(HtmlName::Class.namespace(), None);
+        for s in &["div", "ht", "htmlx", "langs", "xml"] {
+            assert_eq!(HtmlName::from_ncname(ncname(s)), None);
+        }
+        let foreign = ExpandedName::new(None, ncname("html"));
+        assert_eq!(HtmlName::from_expanded_name(&foreign), None);
+        assert_eq!(Empty::from_ncname(ncname("a")), None);
+    }
+}
@@ -0,0 +1,248 @@
+//! Namespaces.
+
+use std::borrow::ToOwned;
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+
+use strings::{NcnameStr, NcnameString, Qname};
+
+pub use self::normalize::{
+    normalize_namespaces, Declaration, NamespaceElement, NamespaceNode, NormalizeError,
+};
+
+mod normalize;
+
+/// Namespace name bound to the `xml` prefix.
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+/// Namespace name bound to the `xmlns` prefix.
+pub const XMLNS_NAMESPACE: &str = "http://www.w3.org/2000/xmlns/";
+
+/// Returns the attribute name of the namespace declaration.
+///
+/// `None` prefix means the default namespace (`xmlns`).
+pub(crate) fn declaration_name(prefix: Option<&NcnameStr>) -> Qname {
+    let xmlns = NcnameString::new("xmlns".to_owned()).expect("Should never fail: valid NCName");
+    match prefix {
+        Some(prefix) => Qname::from_prefix_and_local(xmlns, prefix.to_owned()),
+        None => Qname::from_local(xmlns),
+    }
+}
+
+/// Expanded name, pair of an optional namespace name and a local name.
+///
+/// See <https://www.w3.org/TR/REC-xml-names/#dt-expname>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExpandedName<'a> {
+    /// Namespace name.
+    namespace: Option<&'a str>,
+    /// Local name.
+    local: &'a NcnameStr,
+}
+
+impl<'a> ExpandedName<'a> {
+    /// Creates a new `ExpandedName`.
+    pub fn new(namespace: Option<&'a str>, local: &'a NcnameStr) -> Self {
+        Self { namespace, local }
+    }
+
+    /// Returns the namespace name.
+    pub fn namespace(&self) -> Option<&'a str> {
+        self.namespace
+    }
+
+    /// Returns the local name.
+    pub fn local(&self) -> &'a NcnameStr {
+        self.local
+    }
+}
+
+/// Formats the expanded name in Clark notation (`{namespace}local`).
+impl<'a> fmt::Display for ExpandedName<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(namespace) = self.namespace {
+            write!(f, "{{{}}}", namespace)?;
+        }
+        self.local.fmt(f)
+    }
+}
+
+/// Stack of in-scope namespace bindings.
+///
+/// The `xml` prefix is always bound to [`XML_NAMESPACE`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::namespace::NamespaceContext;
+/// # use xmlop_datatypes::strings::{NcnameStr, NameError};
+/// # fn run() -> Result<(), NameError> {
+/// let foo = NcnameStr::new("foo")?;
+/// let mut ctx = NamespaceContext::new();
+/// ctx.push_scope();
+/// ctx.declare(Some(foo.to_owned()), "http://example.com/foo".to_owned());
+/// assert_eq!(ctx.resolve_prefix(foo), Some("http://example.com/foo"));
+/// ctx.pop_scope();
+/// assert_eq!(ctx.resolve_prefix(foo), None);
+/// # Ok(())
+/// # }
+/// # run().expect("Should never fail");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NamespaceContext {
+    /// Bindings, from outermost to innermost.
+    ///
+    /// Empty namespace name for the default namespace means undeclaring it.
+    bindings: Vec<(Option<NcnameString>, String)>,
+    /// Start indices of bindings for each scope.
+    scopes: Vec<usize>,
+}
+
+impl NamespaceContext {
+    /// Creates a new empty `NamespaceContext`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a new scope.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(self.bindings.len());
+    }
+
+    /// Closes the innermost scope and drops its bindings.
+    ///
+    /// Returns `false` if there are no scopes to close.
+    pub fn pop_scope(&mut self) -> bool {
+        match self.scopes.pop() {
+            Some(start) => {
+                self.bindings.truncate(start);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Returns the number of open scopes.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Adds a binding to the innermost scope.
+    ///
+    /// `None` prefix means the default namespace.
+    /// Note that this does not check namespace constraints.
+    pub fn declare(&mut self, prefix: Option<NcnameString>, namespace: String) {
+        self.bindings.push((prefix, namespace));
+    }
+
+    /// Returns the bindings declared in the innermost scope.
+    pub fn current_scope(&self) -> impl Iterator<Item = (Option<&NcnameStr>, &str)> {
+        let start = self.scopes.last().cloned().unwrap_or(0);
+        self.bindings[start..]
+            .iter()
+            .map(|(prefix, ns)| (prefix.as_ref().map(AsRef::as_ref), ns.as_str()))
+    }
+
+    /// Returns the namespace name bound to the given prefix.
+    pub fn resolve_prefix(&self, prefix: &NcnameStr) -> Option<&str> {
+        if prefix == "xml" {
+            return Some(XML_NAMESPACE);
+        }
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(p, _)| p.as_ref().map(AsRef::as_ref) == Some(prefix))
+            .map(|(_, ns)| ns.as_str())
+    }
+
+    /// Returns the default namespace name.
+    pub fn default_namespace(&self) -> Option<&str> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(p, _)| p.is_none())
+            .map(|(_, ns)| ns.as_str())
+            .filter(|ns| !ns.is_empty())
+    }
+
+    /// Returns an in-scope prefix bound to the given namespace name.
+    ///
+    /// Prefixes shadowed by inner bindings are ignored.
+    pub fn prefix_for(&self, namespace: &str) -> Option<&NcnameStr> {
+        if namespace == XML_NAMESPACE {
+            return Some(NcnameStr::new("xml").expect("Should never fail: valid NCName"));
+        }
+        self.bindings
+            .iter()
+            .rev()
+            .filter_map(|(p, ns)| p.as_ref().map(|p| (p.as_ref(), ns)))
+            .find(|&(p, ns)| ns == namespace && self.resolve_prefix(p) == Some(namespace))
+            .map(|(p, _)| p)
+    }
+
+    /// Expands the given element name.
+    ///
+    /// Unprefixed element names are in the default namespace.
+    /// Returns `None` if the prefix is not declared.
+    pub fn expand_element_name<'a>(&'a self, name: &'a Qname) -> Option<ExpandedName<'a>> {
+        let namespace = match name.prefix() {
+            Some(prefix) => Some(self.resolve_prefix(prefix)?),
+            None => self.default_namespace(),
+        };
+        Some(ExpandedName::new(namespace, name.local()))
+    }
+
+    /// Expands the given attribute name.
+    ///
+    /// Unprefixed attribute names are in no namespace.
+    /// Returns `None` if the prefix is not declared.
+    pub fn expand_attribute_name<'a>(&'a self, name: &'a Qname) -> Option<ExpandedName<'a>> {
+        let namespace = match name.prefix() {
+            Some(prefix) => Some(self.resolve_prefix(prefix)?),
+            None => None,
+        };
+        Some(ExpandedName::new(namespace, name.local()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+
+    fn ncname(s: &str) -> &NcnameStr {
+        NcnameStr::new(s).expect("Should never fail")
+    }
+
+    #[test]
+    fn nested_scopes() {
+        let mut ctx = NamespaceContext::new();
+        ctx.push_scope();
+        ctx.declare(None, "urn:outer".to_owned());
+        ctx.declare(Some(ncname("p").to_owned()), "urn:p1".to_owned());
+        ctx.push_scope();
+        ctx.declare(None, "".to_owned());
+        ctx.declare(Some(ncname("p").to_owned()), "urn:p2".to_owned());
+
+        assert_eq!(ctx.default_namespace(), None);
+        assert_eq!(ctx.resolve_prefix(ncname("p")), Some("urn:p2"));
+        assert_eq!(ctx.prefix_for("urn:p1"), None);
+        assert_eq!(ctx.prefix_for("urn:p2"), Some(ncname("p")));
+        assert_eq!(ctx.current_scope().count(), 2);
+
+        assert!(ctx.pop_scope());
+        assert_eq!(ctx.default_namespace(), Some("urn:outer"));
+        assert_eq!(ctx.resolve_prefix(ncname("p")), Some("urn:p1"));
+        assert_eq!(ctx.resolve_prefix(ncname("xml")), Some(XML_NAMESPACE));
+
+        let elem = Qname::from_local(ncname("e").to_owned());
+        let expanded = ctx.expand_element_name(&elem).expect("Should never fail");
+        assert_eq!(expanded.to_string(), "{urn:outer}e");
+        let expanded = ctx.expand_attribute_name(&elem).expect("Should never fail");
+        assert_eq!(expanded.to_string(), "e");
+
+        assert!(ctx.pop_scope());
+        assert!(!ctx.pop_scope());
+    }
+}
@@ -0,0 +1,592 @@
+//! Namespace normalization.
+//!
+//! See <https://www.w3.org/TR/2004/REC-DOM-Level-3-Core-20040407/namespaces-algorithms.html#normalizeDocumentAlgo>.
+
+use std::borrow::ToOwned;
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+use std::mem;
+use std::string::String;
+use std::vec::Vec;
+
+use fresh::NameGenerator;
+use namespace::{declaration_name, NamespaceContext, XMLNS_NAMESPACE, XML_NAMESPACE};
+use strings::{NcnameStr, NcnameString, Qname};
+
+/// Namespace declaration, pair of an optional prefix and a namespace name.
+///
+/// `None` prefix means the default namespace, and empty namespace name for the
+/// default namespace means undeclaring it.
+pub type Declaration = (Option<NcnameString>, String);
+
+/// Node with a qualified name, i.e. element or attribute.
+pub trait NamespaceNode {
+    /// Returns the qualified name.
+    fn name(&self) -> &Qname;
+
+    /// Returns the namespace name.
+    fn namespace(&self) -> Option<&str>;
+
+    /// Replaces the prefix of the name.
+    fn set_prefix(&mut self, prefix: Option<NcnameString>);
+}
+
+/// Element to be normalized.
+pub trait NamespaceElement: NamespaceNode + Sized {
+    /// Attribute type.
+    type Attribute: NamespaceNode;
+
+    /// Returns the attributes, except for the namespace declarations.
+    fn attributes_mut(&mut self) -> &mut [Self::Attribute];
+
+    /// Returns the namespace declarations.
+    fn declarations_mut(&mut self) -> &mut Vec<Declaration>;
+
+    /// Calls `f` with each child element in document order.
+    fn for_each_child_mut(&mut self, f: &mut dyn FnMut(&mut Self));
+}
+
+/// Name or declaration which cannot be represented in XML.
+///
+/// Each variant documents the violated constraint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NormalizeError {
+    /// [NSC: Reserved Prefixes and Namespace Names] `xml` or `xmlns` prefix or
+    /// namespace name is misused by the element, the attribute or the
+    /// declaration (as `xmlns:prefix` or `xmlns`).
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#xmlReserved>.
+    ReservedNamespace(Qname),
+    /// [NSC: No Prefix Undeclaring] a prefixed namespace declaration has an
+    /// empty value.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#nsc-NoPrefixUndecl>.
+    PrefixUndeclaring(NcnameString),
+    /// [WFC: Unique Att Spec] the same prefix is declared twice in an element.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#uniqattspec>.
+    DuplicateDeclaration(Qname),
+    /// The element or the attribute has a prefix but no namespace.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#nsc-NSDeclared>.
+    PrefixWithoutNamespace(Qname),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for NormalizeError {}
+
+impl fmt::Display for NormalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NormalizeError::ReservedNamespace(name) => write!(
+                f,
+                "[NSC: Reserved Prefixes and Namespace Names] reserved prefix or namespace is misused by `{}`",
+                name
+            ),
+            NormalizeError::PrefixUndeclaring(prefix) => write!(
+                f,
+                "[NSC: No Prefix Undeclaring] prefix `{}` is bound to empty namespace name",
+                prefix
+            ),
+            NormalizeError::DuplicateDeclaration(name) => write!(
+                f,
+                "[WFC: Unique Att Spec] duplicate namespace declaration `{}`",
+                name
+            ),
+            NormalizeError::PrefixWithoutNamespace(name) => write!(
+                f,
+                "`{}` has a prefix but no namespace",
+                name
+            ),
+        }
+    }
+}
+
+/// Returns the namespace name bound to the prefix, or the default namespace.
+fn resolve<'a>(ctx: &'a NamespaceContext, prefix: Option<&NcnameStr>) -> Option<&'a str> {
+    match prefix {
+        Some(prefix) => ctx.resolve_prefix(prefix),
+        None => ctx.default_namespace(),
+    }
+}
+
+/// Namespace normalizer.
+struct Normalizer<'a> {
+    /// In-scope namespaces.
+    ctx: &'a mut NamespaceContext,
+    /// Errors.
+    errors: Vec<NormalizeError>,
+}
+
+impl<'a> Normalizer<'a> {
+    /// Validates the local declarations, and drops the invalid and redundant
+    /// ones.
+    fn pick_up_declarations(&mut self, decls: Vec<Declaration>) -> Vec<Declaration> {
+        let mut picked: Vec<Declaration> = Vec::with_capacity(decls.len());
+        // Prefixes declared in the element, including dropped ones.
+        let mut seen: Vec<Option<NcnameString>> = Vec::with_capacity(decls.len());
+        for (prefix, namespace) in decls {
+            let name = || declaration_name(prefix.as_ref().map(AsRef::as_ref));
+            let error = match prefix {
+                Some(ref p) if *p == "xml" && namespace == XML_NAMESPACE => None,
+                Some(ref p) if *p == "xml" || *p == "xmlns" => {
+                    Some(NormalizeError::ReservedNamespace(name()))
+                },
+                _ if namespace == XML_NAMESPACE || namespace == XMLNS_NAMESPACE => {
+                    Some(NormalizeError::ReservedNamespace(name()))
+                },
+                Some(ref p) if namespace.is_empty() => {
+                    Some(NormalizeError::PrefixUndeclaring(p.clone()))
+                },
+                _ if seen.contains(&prefix) => Some(NormalizeError::DuplicateDeclaration(name())),
+                _ => None,
+            };
+            seen.push(prefix.clone());
+            if let Some(error) = error {
+                self.errors.push(error);
+                continue;
+            }
+            let inherited = resolve(self.ctx, prefix.as_ref().map(AsRef::as_ref));
+            let redundant = match inherited {
+                Some(inherited) => inherited == namespace,
+                None => namespace.is_empty(),
+            };
+            if !redundant {
+                picked.push((prefix, namespace));
+            }
+        }
+        picked
+    }
+
+    /// Replaces the bindings of the current scope with the declarations.
+    fn rebind(&mut self, decls: &[Declaration]) {
+        self.ctx.pop_scope();
+        self.ctx.push_scope();
+        for (prefix, namespace) in decls {
+            self.ctx.declare(prefix.clone(), namespace.clone());
+        }
+    }
+
+    /// Declares the namespace locally, replacing the conflicting declaration.
+    fn declare(
+        &mut self,
+        decls: &mut Vec<Declaration>,
+        prefix: Option<NcnameString>,
+        namespace: String,
+    ) {
+        match decls.iter_mut().find(|(p, _)| *p == prefix) {
+            Some(decl) => decl.1 = namespace,
+            None => decls.push((prefix, namespace)),
+        }
+        self.rebind(decls);
+    }
+
+    /// Fixes up the namespace of the element.
+    fn fix_element<E: NamespaceElement>(&mut self, elem: &mut E, decls: &mut Vec<Declaration>) {
+        let prefix = elem.name().prefix().map(ToOwned::to_owned);
+        let namespace = match elem.namespace() {
+            Some(namespace) => namespace.to_owned(),
+            None => {
+                if prefix.is_some() {
+                    self.errors
+                        .push(NormalizeError::PrefixWithoutNamespace(elem.name().clone()));
+                } else if self.ctx.default_namespace().is_some() {
+                    self.declare(decls, None, String::new());
+                }
+                return;
+            },
+        };
+        let prefix_str = prefix.as_ref().map(NcnameString::as_str);
+        if namespace == XML_NAMESPACE {
+            if prefix_str != Some("xml") {
+                elem.set_prefix(Some(
+                    NcnameString::new("xml".to_owned()).expect("Should never fail: valid NCName"),
+                ));
+            }
+            return;
+        }
+        if namespace == XMLNS_NAMESPACE || prefix_str == Some("xml") || prefix_str == Some("xmlns")
+        {
+            self.errors
+                .push(NormalizeError::ReservedNamespace(elem.name().clone()));
+            return;
+        }
+        if resolve(self.ctx, prefix.as_ref().map(AsRef::as_ref)) != Some(namespace.as_str()) {
+            self.declare(decls, prefix, namespace);
+        }
+    }
+
+    /// Fixes up the namespace of the attribute.
+    fn fix_attribute<A: NamespaceNode>(&mut self, attr: &mut A, decls: &mut Vec<Declaration>) {
+        let namespace = match attr.namespace() {
+            Some(namespace) => namespace.to_owned(),
+            None => {
+                if attr.name().prefix().is_some() {
+                    self.errors
+                        .push(NormalizeError::PrefixWithoutNamespace(attr.name().clone()));
+                }
+                return;
+            },
+        };
+        if namespace == XMLNS_NAMESPACE {
+            self.errors
+                .push(NormalizeError::ReservedNamespace(attr.name().clone()));
+            return;
+        }
+        let prefix = attr.name().prefix().map(ToOwned::to_owned);
+        if let Some(ref prefix) = prefix {
+            if self.ctx.resolve_prefix(prefix) == Some(namespace.as_str()) {
+                return;
+            }
+        }
+        // Default namespace does not apply to attributes.
+        if let Some(bound) = self.ctx.prefix_for(&namespace) {
+            attr.set_prefix(Some(bound.to_owned()));
+            return;
+        }
+        match prefix {
+            Some(prefix)
+                if self.ctx.resolve_prefix(&prefix).is_none()
+                    && prefix.as_str() != "xml"
+                    && prefix.as_str() != "xmlns" =>
+            {
+                self.declare(decls, Some(prefix), namespace);
+            },
+            _ => {
+                let prefix =
+                    NameGenerator::with_predicate(|p| self.ctx.resolve_prefix(p).is_some())
+                        .generate_numbered("NS");
+                attr.set_prefix(Some(prefix.clone()));
+                self.declare(decls, Some(prefix), namespace);
+            },
+        }
+    }
+
+    /// Normalizes the namespaces of the element and its descendants.
+    fn normalize<E: NamespaceElement>(&mut self, elem: &mut E) {
+        let decls = mem::take(elem.declarations_mut());
+        let mut decls = self.pick_up_declarations(decls);
+        self.ctx.push_scope();
+        self.rebind(&decls);
+
+        self.fix_element(elem, &mut decls);
+        for attr in elem.attributes_mut() {
+            self.fix_attribute(attr, &mut decls);
+        }
+        *elem.declarations_mut() = decls;
+
+        elem.for_each_child_mut(&mut |child| self.normalize(child));
+        self.ctx.pop_scope();
+    }
+}
+
+/// Normalizes the namespaces of the element and its descendants.
+///
+/// This implements the namespace normalization algorithm of DOM Level 3, and
+/// additionally drops the redundant declarations:
+///
+/// * Declarations which misuse the reserved prefixes or namespace names, or
+///   undeclare prefixes, are dropped and reported.
+/// * Declarations which bind the same namespace as the inherited binding are
+///   dropped.
+/// * Missing declarations for the elements and attributes are added.
+///   A conflicting local declaration for the prefix of the element is
+///   changed.
+/// * Attributes with a conflicting or no prefix are renamed to use an
+///   in-scope prefix, or a new prefix `NS0`, `NS1`, ....
+///
+/// `ctx` has the bindings inherited from the ancestors of `elem`, and it is
+/// unchanged on return.
+///
+/// # Failures
+///
+/// Fails with the names and the declarations which cannot be represented.
+/// The other nodes are normalized even on failure.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::namespace::{
+/// #     normalize_namespaces, Declaration, NamespaceContext, NamespaceElement, NamespaceNode,
+/// # };
+/// # use xmlop_datatypes::strings::{NcnameString, Qname, QnameStr};
+/// struct Attr {
+///     name: Qname,
+///     namespace: Option<String>,
+/// }
+///
+/// impl NamespaceNode for Attr {
+///     fn name(&self) -> &Qname {
+///         &self.name
+///     }
+///     fn namespace(&self) -> Option<&str> {
+///         self.namespace.as_deref()
+///     }
+///     fn set_prefix(&mut self, prefix: Option<NcnameString>) {
+///         self.name = Qname::new(prefix, self.name.local().to_owned());
+///     }
+/// }
+///
+/// struct Elem {
+///     node: Attr,
+///     attrs: Vec<Attr>,
+///     decls: Vec<Declaration>,
+///     children: Vec<Elem>,
+/// }
+///
+/// impl NamespaceNode for Elem {
+///     fn name(&self) -> &Qname {
+///         self.node.name()
+///     }
+///     fn namespace(&self) -> Option<&str> {
+///         self.node.namespace()
+///     }
+///     fn set_prefix(&mut self, prefix: Option<NcnameString>) {
+///         self.node.set_prefix(prefix)
+///     }
+/// }
+///
+/// impl NamespaceElement for Elem {
+///     type Attribute = Attr;
+///     fn attributes_mut(&mut self) -> &mut [Attr] {
+///         &mut self.attrs
+///     }
+///     fn declarations_mut(&mut self) -> &mut Vec<Declaration> {
+///         &mut self.decls
+///     }
+///     fn for_each_child_mut(&mut self, f: &mut dyn FnMut(&mut Self)) {
+///         self.children.iter_mut().for_each(f)
+///     }
+/// }
+///
+/// let node = |name: &str, namespace: Option<&str>| Attr {
+///     name: QnameStr::new(name).unwrap().to_qname(),
+///     namespace: namespace.map(ToOwned::to_owned),
+/// };
+/// let mut svg = Elem {
+///     node: node("svg", Some("http://www.w3.org/2000/svg")),
+///     attrs: vec![node("href", Some("http://www.w3.org/1999/xlink"))],
+///     decls: vec![],
+///     children: vec![],
+/// };
+/// normalize_namespaces(&mut svg, &mut NamespaceContext::new()).unwrap();
+/// assert_eq!(svg.attrs[0].name.to_string(), "NS0:href");
+/// assert_eq!(
+///     svg.decls,
+///     vec![
+///         (None, "http://www.w3.org/2000/svg".to_owned()),
+///         (
+///             Some(NcnameString::new("NS0".to_owned()).unwrap()),
+///             "http://www.w3.org/1999/xlink".to_owned()
+///         ),
+///     ]
+/// );
+/// ```
+pub fn normalize_namespaces<E: NamespaceElement>(
+    elem: &mut E,
+    ctx: &mut NamespaceContext,
+) -> Result<(), Vec<NormalizeError>> {
+    let mut normalizer = Normalizer {
+        ctx,
+        errors: Vec::new(),
+    };
+    normalizer.normalize(elem);
+    if normalizer.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(normalizer.errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+    use strings::QnameStr;
+
+    #[derive(Debug)]
+    struct Node {
+        name: Qname,
+        namespace: Option<String>,
+    }
+
+    impl NamespaceNode for Node {
+        fn name(&self) -> &Qname {
+            &self.name
+        }
+
+        fn namespace(&self) -> Option<&str> {
+            self.namespace.as_deref()
+        }
+
+        fn set_prefix(&mut self, prefix: Option<NcnameString>) {
+            self.name = Qname::new(prefix, self.name.local().to_owned());
+        }
+    }
+
+    #[derive(Debug)]
+    struct Elem {
+        node: Node,
+        attrs: Vec<Node>,
+        decls: Vec<Declaration>,
+        children: Vec<Elem>,
+    }
+
+    impl NamespaceNode for Elem {
+        fn name(&self) -> &Qname {
+            self.node.name()
+        }
+
+        fn namespace(&self) -> Option<&str> {
+            self.node.namespace()
+        }
+
+        fn set_prefix(&mut self, prefix: Option<NcnameString>) {
+            self.node.set_prefix(prefix)
+        }
+    }
+
+    impl NamespaceElement for Elem {
+        type Attribute = Node;
+
+        fn attributes_mut(&mut self) -> &mut [Node] {
+            &mut self.attrs
+        }
+
+        fn declarations_mut(&mut self) -> &mut Vec<Declaration> {
+            &mut self.decls
+        }
+
+        fn for_each_child_mut(&mut self, f: &mut dyn FnMut(&mut Self)) {
+            self.children.iter_mut().for_each(f)
+        }
+    }
+
+    fn node(name: &str, namespace: Option<&str>) -> Node {
+        Node {
+            name: QnameStr::new(name).expect("Should never fail").to_qname(),
+            namespace: namespace.map(ToOwned::to_owned),
+        }
+    }
+
+    fn elem(name: &str, namespace: Option<&str>) -> Elem {
+        Elem {
+            node: node(name, namespace),
+            attrs: Vec::new(),
+            decls: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn decl(prefix: Option<&str>, namespace: &str) -> Declaration {
+        (
+            prefix.map(|p| NcnameString::new(p.to_owned()).expect("Should never fail")),
+            namespace.to_owned(),
+        )
+    }
+
+    fn normalize(elem: &mut Elem) -> Result<(), Vec<NormalizeError>> {
+        let mut ctx = NamespaceContext::new();
+        let res = normalize_namespaces(elem, &mut ctx);
+        assert_eq!(ctx, NamespaceContext::new());
+        res
+    }
+
+    #[test]
+    fn add_missing_and_drop_redundant() {
+        let mut root = elem("a:root", Some("urn:a"));
+        root.decls.push(decl(Some("a"), "urn:a"));
+        let mut child = elem("a:child", Some("urn:a"));
+        child.decls.push(decl(Some("a"), "urn:a"));
+        child.attrs.push(node("b:attr", Some("urn:b")));
+        child.attrs.push(node("plain", None));
+        let mut grandchild = elem("leaf", None);
+        grandchild.decls.push(decl(None, ""));
+        child.children.push(grandchild);
+        root.children.push(child);
+
+        assert_eq!(normalize(&mut root), Ok(()));
+        assert_eq!(root.decls, vec![decl(Some("a"), "urn:a")]);
+        let child = &root.children[0];
+        assert_eq!(child.decls, vec![decl(Some("b"), "urn:b")]);
+        assert_eq!(child.attrs[0].name.to_string(), "b:attr");
+        assert!(child.children[0].decls.is_empty());
+    }
+
+    #[test]
+    fn fix_conflicts() {
+        let mut root = elem("p:root", Some("urn:new"));
+        root.decls.push(decl(Some("p"), "urn:old"));
+        root.decls.push(decl(None, "urn:default"));
+        root.attrs.push(node("p:old", Some("urn:old")));
+        root.attrs.push(node("default", Some("urn:default")));
+        root.attrs.push(node("p:new", Some("urn:new")));
+        let mut child = elem("child", None);
+        child.attrs.push(node("xml:lang", Some(XML_NAMESPACE)));
+        child.attrs.push(node("lang", Some(XML_NAMESPACE)));
+        root.children.push(child);
+
+        assert_eq!(normalize(&mut root), Ok(()));
+        assert_eq!(
+            root.decls,
+            vec![
+                decl(Some("p"), "urn:new"),
+                decl(None, "urn:default"),
+                decl(Some("NS0"), "urn:old"),
+                decl(Some("NS1"), "urn:default"),
+            ]
+        );
+        let names: Vec<_> = root.attrs.iter().map(|a| a.name.to_string()).collect();
+        assert_eq!(names, ["NS0:old", "NS1:default", "p:new"]);
+        let child = &root.children[0];
+        assert_eq!(child.decls, vec![decl(None, "")]);
+        let names: Vec<_> = child.attrs.iter().map(|a| a.name.to_string()).collect();
+        assert_eq!(names, ["xml:lang", "xml:lang"]);
+    }
+
+    #[test]
+    fn report_unrepresentable() {
+        let mut root = elem("p:root", None);
+        root.decls.push(decl(Some("xmlns"), "urn:x"));
+        root.decls.push(decl(Some("q"), ""));
+        root.decls.push(decl(Some("r"), "urn:r"));
+        root.decls.push(decl(Some("r"), "urn:r2"));
+        root.attrs.push(node("xmlns:s", Some(XMLNS_NAMESPACE)));
+        root.children.push(elem("xml:child", Some("urn:c")));
+
+        let qname = |s: &str| QnameStr::new(s).expect("Should never fail").to_qname();
+        assert_eq!(
+            normalize(&mut root),
+            Err(vec![
+                NormalizeError::ReservedNamespace(qname("xmlns:xmlns")),
+                NormalizeError::PrefixUndeclaring(
+                    NcnameString::new("q".to_owned()).expect("Should never fail")
+                ),
+                NormalizeError::DuplicateDeclaration(qname("xmlns:r")),
+                NormalizeError::PrefixWithoutNamespace(qname("p:root")),
+                NormalizeError::ReservedNamespace(qname("xmlns:s")),
+                NormalizeError::ReservedNamespace(qname("xml:child")),
+            ])
+        );
+        assert_eq!(root.decls, vec![decl(Some("r"), "urn:r")]);
+    }
+
+    #[test]
+    fn report_duplicate_of_redundant_declaration() {
+        let mut root = elem("a:root", Some("urn:a"));
+        root.decls.push(decl(Some("a"), "urn:a"));
+        let mut child = elem("a:child", Some("urn:a"));
+        child.decls.push(decl(Some("a"), "urn:a"));
+        child.decls.push(decl(Some("a"), "urn:b"));
+        root.children.push(child);
+
+        let qname = |s: &str| QnameStr::new(s).expect("Should never fail").to_qname();
+        assert_eq!(
+            normalize(&mut root),
+            Err(vec![NormalizeError::DuplicateDeclaration(qname("xmlns:a"))])
+        );
+        assert!(root.children[0].decls.is_empty());
+    }
+}
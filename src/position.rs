@@ -0,0 +1,357 @@
+//! Positions in source text.
+
+use std::fmt;
+use std::ops::{Deref, Range};
+#[cfg(feature = "alloc")]
+use std::vec::Vec;
+
+#[cfg(feature = "nom-4")]
+use nom::types::CompleteStr;
+#[cfg(feature = "nom-4")]
+use nom::IResult;
+
+/// Byte range in source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    /// Start byte offset (inclusive).
+    start: usize,
+    /// End byte offset (exclusive).
+    end: usize,
+}
+
+impl Span {
+    /// Creates a new `Span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start > end`.
+    pub fn new(start: usize, end: usize) -> Self {
+        assert!(
+            start <= end,
+            "Span start should not be greater than end: start={}, end={}",
+            start,
+            end
+        );
+        Self { start, end }
+    }
+
+    /// Returns the start byte offset (inclusive).
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the end byte offset (exclusive).
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the length in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns whether the span is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the byte range.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.range()
+    }
+}
+
+/// Position in source text.
+///
+/// Lines and columns are 1-based, and columns are counted in characters.
+/// `"\r\n"`, `"\r"` and `"\n"` are line breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextPosition {
+    /// Byte offset.
+    offset: usize,
+    /// Line number (1-based).
+    line: usize,
+    /// Column number in characters (1-based).
+    column: usize,
+}
+
+impl TextPosition {
+    /// Computes the position of the given byte offset in the source text.
+    ///
+    /// This scans the source from the beginning. Use [`LineIndex`] to compute
+    /// many positions in the same source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is out of bounds or not on a character boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::position::TextPosition;
+    /// let source = "<root>\n  <foo:bar/>\n</root>";
+    /// let pos = TextPosition::from_offset(source, 10);
+    /// assert_eq!((pos.line(), pos.column()), (2, 4));
+    /// assert_eq!(pos.to_string(), "line 2, column 4");
+    /// ```
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let (line, line_start) = line_starts(source)
+            .take_while(|&start| start <= offset)
+            .fold((1, 0), |(line, _), start| (line + 1, start));
+        Self::with_line_start(source, offset, line, line_start)
+    }
+
+    /// Creates a position from the start of its line.
+    fn with_line_start(source: &str, offset: usize, line: usize, line_start: usize) -> Self {
+        let column = source[line_start..offset].chars().count() + 1;
+        Self {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    /// Returns the byte offset.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the line number (1-based).
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number in characters (1-based).
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for TextPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Returns the byte offsets where the lines after the first one start.
+fn line_starts(s: &str) -> impl Iterator<Item = usize> + '_ {
+    let bytes = s.as_bytes();
+    bytes
+        .iter()
+        .enumerate()
+        .filter(move |&(i, &b)| b == b'\n' || (b == b'\r' && bytes.get(i + 1) != Some(&b'\n')))
+        .map(|(i, _)| i + 1)
+}
+
+/// Index of line starts for computing positions in the same source.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    /// Source text.
+    source: &'a str,
+    /// Byte offsets of line starts.
+    line_starts: Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> LineIndex<'a> {
+    /// Creates a new `LineIndex`.
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = Some(0).into_iter().chain(line_starts(source)).collect();
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// Returns the source text.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Computes the position of the given byte offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is out of bounds or not on a character boundary.
+    pub fn position(&self, offset: usize) -> TextPosition {
+        assert!(
+            offset <= self.source.len(),
+            "Offset should not exceed the source length: offset={}, len={}",
+            offset,
+            self.source.len()
+        );
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        };
+        TextPosition::with_line_start(self.source, offset, line, self.line_starts[line - 1])
+    }
+}
+
+/// Value with its span in source text.
+///
+/// The line and column are computed lazily from the source text when needed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::position::{Span, Spanned};
+/// # use xmlop_datatypes::strings::QnameStr;
+/// let source = "<root>\n  <foo:bar/>\n</root>";
+/// let name = Spanned::new(QnameStr::new("foo:bar").unwrap(), Span::new(10, 17));
+/// assert_eq!(name.prefix().map(|p| p.as_str()), Some("foo"));
+/// assert_eq!(
+///     format!("{}: undeclared prefix `foo`", name.position(source)),
+///     "line 2, column 4: undeclared prefix `foo`"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    /// Value.
+    value: T,
+    /// Span of the value.
+    span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// Creates a new `Spanned`.
+    pub fn new(value: T, span: Span) -> Self {
+        Self { value, span }
+    }
+
+    /// Returns the value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the span.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the value, discarding the span.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Returns the value and the span.
+    pub fn into_parts(self) -> (T, Span) {
+        (self.value, self.span)
+    }
+
+    /// Returns `Spanned` which refers to the value.
+    pub fn as_ref(&self) -> Spanned<&T> {
+        Spanned::new(&self.value, self.span)
+    }
+
+    /// Converts the value, keeping the span.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Spanned<U> {
+        Spanned::new(f(self.value), self.span)
+    }
+
+    /// Computes the start position in the given source text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is out of bounds of the source.
+    pub fn position(&self, source: &str) -> TextPosition {
+        TextPosition::from_offset(source, self.span.start)
+    }
+
+    /// Computes the end position in the given source text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is out of bounds of the source.
+    pub fn end_position(&self, source: &str) -> TextPosition {
+        TextPosition::from_offset(source, self.span.end)
+    }
+
+    /// Computes the start position using the given line index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is out of bounds of the source.
+    #[cfg(feature = "alloc")]
+    pub fn position_in(&self, index: &LineIndex) -> TextPosition {
+        index.position(self.span.start)
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// Runs the parser and attaches the span of the consumed input to the output.
+///
+/// `input` should be a suffix of `source`, and the span is the byte range in
+/// `source`.
+///
+/// # Panics
+///
+/// Panics if `input` is not a part of `source`.
+#[cfg(feature = "nom-4")]
+pub fn nom_parse_spanned<'a, O, F>(
+    input: CompleteStr<'a>,
+    source: &'a str,
+    parser: F,
+) -> IResult<CompleteStr<'a>, Spanned<O>>
+where
+    F: FnOnce(CompleteStr<'a>) -> IResult<CompleteStr<'a>, O>,
+{
+    let source_start = source.as_ptr() as usize;
+    let input_start = input.as_ptr() as usize;
+    assert!(
+        source_start <= input_start && input_start + input.len() <= source_start + source.len(),
+        "Input should be a part of the source"
+    );
+    let start = input_start - source_start;
+    let (rest, value) = parser(input)?;
+    let end = start + (input.len() - rest.len());
+    Ok((rest, Spanned::new(value, Span::new(start, end))))
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_position() {
+        let source = "a\r\nbc\rd\u{E9}f\ng";
+        let index = LineIndex::new(source);
+        for &(offset, line, column) in &[
+            (0, 1, 1),
+            (2, 1, 3),
+            (3, 2, 1),
+            (6, 3, 1),
+            (9, 3, 3),
+            (11, 4, 1),
+            (12, 4, 2),
+        ] {
+            let pos = TextPosition::from_offset(source, offset);
+            assert_eq!(
+                (pos.offset(), pos.line(), pos.column()),
+                (offset, line, column)
+            );
+            assert_eq!(index.position(offset), pos);
+        }
+    }
+}
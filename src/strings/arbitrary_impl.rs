@@ -0,0 +1,71 @@
+//! `arbitrary` support for name types.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use strings::sample::{self, Entropy, NearMiss};
+use strings::NameKind;
+use strings::{NameString, NcnameString, NmtokenString, Qname, QnameStr, QnameString};
+
+impl<'a> Entropy for Unstructured<'a> {
+    type Error = ::arbitrary::Error;
+
+    fn below(&mut self, n: usize) -> Result<usize> {
+        self.int_in_range(0..=(n - 1))
+    }
+}
+
+/// Implements `Arbitrary` for the owned string type.
+macro_rules! impl_arbitrary_for_string {
+    ($owned:ident, $production:expr) => {
+        impl<'a> Arbitrary<'a> for $owned {
+            fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                let s = sample::name(u, $production)?;
+                Ok($owned::new(s).unwrap_or_else(|e| {
+                    panic!("Sampled name should be valid as `{}`: {}", $production, e)
+                }))
+            }
+        }
+    };
+}
+
+impl_arbitrary_for_string!(NameString, NameKind::Name);
+impl_arbitrary_for_string!(NcnameString, NameKind::Ncname);
+impl_arbitrary_for_string!(NmtokenString, NameKind::Nmtoken);
+impl_arbitrary_for_string!(QnameString, NameKind::Qname);
+
+impl<'a> Arbitrary<'a> for Qname {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        QnameString::arbitrary(u).map(|s| QnameStr::to_qname(&s))
+    }
+}
+
+impl<'a> Arbitrary<'a> for NearMiss {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let production = *u.choose(&[
+            NameKind::Name,
+            NameKind::Ncname,
+            NameKind::Nmtoken,
+            NameKind::Qname,
+        ])?;
+        sample::near_miss(u, production)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arbitrary_names() {
+        let bytes = (0..=255).cycle().take(4096).collect::<Vec<u8>>();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..32 {
+            Qname::arbitrary(&mut u).expect("Should never fail");
+            let near_miss = NearMiss::arbitrary(&mut u).expect("Should never fail");
+            assert_eq!(
+                near_miss.production().validate(near_miss.name()),
+                Err(near_miss.expected_error())
+            );
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Attribute value string types.
+//!
+//! Attribute values cannot contain their own quotation mark, so there are types
+//! for double-quoted and single-quoted values.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-AttValue>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+use strings::content::validate_chars;
+use strings::ContentError;
+use syntax::Quote;
+
+/// Validates the given string as `AttValue` content without references.
+fn validate_att_value_str<S: AsRef<str>>(s: S, quote: Quote) -> Result<S, ContentError> {
+    validate_chars(s.as_ref(), |c| c == '<' || c == '&' || c == quote.as_char())?;
+    Ok(s)
+}
+
+/// Validates the given string as double-quoted `AttValue` content.
+fn validate_att_value_dq_str<S: AsRef<str>>(s: S) -> Result<S, ContentError> {
+    validate_att_value_str(s, Quote::Double)
+}
+
+/// Validates the given string as single-quoted `AttValue` content.
+fn validate_att_value_sq_str<S: AsRef<str>>(s: S) -> Result<S, ContentError> {
+    validate_att_value_str(s, Quote::Single)
+}
+
+define_custom_string! {
+    validator = validate_att_value_dq_str;
+    error = ContentError;
+    borrowed AttValueDqStr {
+        /// Borrowed double-quoted attribute value.
+        ///
+        /// This consists of XML `Char`s, and does not contain `<`, `&` and `"`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-AttValue>.
+    }
+    owned AttValueDqString {
+        /// Owned double-quoted attribute value.
+        ///
+        /// This consists of XML `Char`s, and does not contain `<`, `&` and `"`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-AttValue>.
+    }
+}
+
+impl AttValueDqStr {
+    /// Quotation mark delimiting the value.
+    pub const QUOTE: Quote = Quote::Double;
+
+    /// Creates a new `AttValueDqStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{AttValueDqStr, ContentError};
+    /// # fn run() -> Result<(), ContentError> {
+    /// let s = AttValueDqStr::new("it's")?;
+    /// assert_eq!(s, "it's");
+    ///
+    /// assert!(AttValueDqStr::new("say \"hi\"").is_err());
+    /// assert!(AttValueDqStr::new("a < b").is_err());
+    /// assert!(AttValueDqStr::new("&amp;").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&AttValueDqStr, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `AttValueDqStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is valid double-quoted attribute value.
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&AttValueDqStr` has surely valid double-quoted attribute value.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it consists of XML `Char`s, and
+    /// * it does not contain `<`, `&` and `"`.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AttValueDqString {
+    /// Creates a new `AttValueDqString`.
+    pub fn new(s: String) -> Result<Self, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `AttValueDqString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`AttValueDqStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&AttValueDqStr`][`AttValueDqStr`] slice.
+    pub fn as_att_value_str(&self) -> &AttValueDqStr {
+        self.as_ref()
+    }
+}
+
+define_custom_string! {
+    validator = validate_att_value_sq_str;
+    error = ContentError;
+    borrowed AttValueSqStr {
+        /// Borrowed single-quoted attribute value.
+        ///
+        /// This consists of XML `Char`s, and does not contain `<`, `&` and `'`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-AttValue>.
+    }
+    owned AttValueSqString {
+        /// Owned single-quoted attribute value.
+        ///
+        /// This consists of XML `Char`s, and does not contain `<`, `&` and `'`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-AttValue>.
+    }
+}
+
+impl AttValueSqStr {
+    /// Quotation mark delimiting the value.
+    pub const QUOTE: Quote = Quote::Single;
+
+    /// Creates a new `AttValueSqStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{AttValueSqStr, ContentError};
+    /// # fn run() -> Result<(), ContentError> {
+    /// let s = AttValueSqStr::new("say \"hi\"")?;
+    /// assert_eq!(s, "say \"hi\"");
+    ///
+    /// assert!(AttValueSqStr::new("it's").is_err());
+    /// assert!(AttValueSqStr::new("a < b").is_err());
+    /// assert!(AttValueSqStr::new("&amp;").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&AttValueSqStr, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `AttValueSqStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is valid single-quoted attribute value.
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&AttValueSqStr` has surely valid single-quoted attribute value.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it consists of XML `Char`s, and
+    /// * it does not contain `<`, `&` and `'`.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AttValueSqString {
+    /// Creates a new `AttValueSqString`.
+    pub fn new(s: String) -> Result<Self, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `AttValueSqString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`AttValueSqStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&AttValueSqStr`][`AttValueSqStr`] slice.
+    pub fn as_att_value_str(&self) -> &AttValueSqStr {
+        self.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn validate_att_value() {
+        assert!(AttValueDqStr::new("").is_ok());
+        assert!(AttValueSqStr::new("").is_ok());
+        assert_eq!(
+            AttValueDqStr::new("say \"hi\""),
+            Err(ContentError::ForbiddenChar(4, '"'))
+        );
+        assert!(AttValueSqStr::new("say \"hi\"").is_ok());
+        assert_eq!(
+            AttValueSqString::new("it's".to_owned()),
+            Err(ContentError::ForbiddenChar(2, '\''))
+        );
+        assert_eq!(
+            AttValueDqString::new("\u{0}".to_owned()),
+            Err(ContentError::InvalidChar(0, '\u{0}'))
+        );
+    }
+}
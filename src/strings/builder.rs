@@ -0,0 +1,294 @@
+//! Incremental name builder.
+
+use std::string::String;
+
+use strings::{is_name_char, is_name_start_char, is_ncname_start_char};
+use strings::{NameError, NameString, NcnameString, Qname};
+
+/// Builder of names, which validates the characters one by one.
+///
+/// The characters are checked as `Name` characters when pushed, and the
+/// colons are tracked for `NCName` and `QName`, so the finished strings are
+/// not validated again.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::strings::{NameBuilder, NameError};
+/// # fn run() -> Result<(), NameError> {
+/// let mut builder = NameBuilder::new();
+/// let mut chars = "xlink:href=\"#a\"".chars().peekable();
+/// while let Some(&c) = chars.peek() {
+///     if !builder.push(c) {
+///         // `=` ends the name.
+///         break;
+///     }
+///     chars.next();
+/// }
+/// assert_eq!(builder.as_str(), "xlink:href");
+/// assert!(!builder.is_ncname());
+/// assert!(builder.is_qname());
+///
+/// let qname = builder.into_qname()?;
+/// assert_eq!(qname.prefix().map(|p| p.as_str()), Some("xlink"));
+/// assert_eq!(qname.local(), "href");
+/// # Ok(())
+/// # }
+/// # run().expect("Should never fail");
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameBuilder {
+    /// Accepted characters.
+    buf: String,
+    /// Position of the first colon.
+    colon: Option<usize>,
+    /// First error making the string invalid as `QName`, except for the
+    /// trailing colon.
+    qname_invalid: Option<(usize, char)>,
+}
+
+impl NameBuilder {
+    /// Creates a new empty `NameBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new empty `NameBuilder` with the given capacity in bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: String::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// Checks whether the given character can be pushed.
+    ///
+    /// If this returns `false`, the character ends the name (or, for the first
+    /// character, cannot start a name).
+    pub fn accepts(&self, c: char) -> bool {
+        if self.buf.is_empty() {
+            is_name_start_char(c)
+        } else {
+            is_name_char(c)
+        }
+    }
+
+    /// Pushes the character if it is accepted, and returns whether it is.
+    ///
+    /// If the character is not accepted, the builder is unchanged.
+    pub fn push(&mut self, c: char) -> bool {
+        if !self.accepts(c) {
+            return false;
+        }
+        let pos = self.buf.len();
+        if self.qname_invalid.is_none() {
+            let after_colon = self.colon.map(|colon| colon + 1) == Some(pos);
+            let valid = if after_colon {
+                is_ncname_start_char(c)
+            } else {
+                c != ':' || (pos != 0 && self.colon.is_none())
+            };
+            if !valid {
+                self.qname_invalid = Some((pos, c));
+            }
+        }
+        if c == ':' && self.colon.is_none() {
+            self.colon = Some(pos);
+        }
+        self.buf.push(c);
+        true
+    }
+
+    /// Returns the accepted string.
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    /// Returns the length of the accepted string in bytes.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Checks whether no characters are accepted.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Clears the builder, keeping the allocated buffer.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.colon = None;
+        self.qname_invalid = None;
+    }
+
+    /// Returns the position of the first colon, if any.
+    pub fn colon_position(&self) -> Option<usize> {
+        self.colon
+    }
+
+    /// Checks whether the accepted string is `Name`.
+    pub fn is_name(&self) -> bool {
+        !self.buf.is_empty()
+    }
+
+    /// Checks whether the accepted string is `NCName`.
+    pub fn is_ncname(&self) -> bool {
+        self.ncname_error().is_none()
+    }
+
+    /// Checks whether the accepted string is `QName`.
+    pub fn is_qname(&self) -> bool {
+        self.qname_error().is_none()
+    }
+
+    /// Returns the error of the accepted string as `Name`.
+    fn name_error(&self) -> Option<NameError> {
+        if self.buf.is_empty() {
+            Some(NameError::Empty)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the error of the accepted string as `NCName`.
+    fn ncname_error(&self) -> Option<NameError> {
+        self.name_error()
+            .or_else(|| self.colon.map(|pos| NameError::InvalidNameChar(pos, ':')))
+    }
+
+    /// Returns the error of the accepted string as `QName`.
+    fn qname_error(&self) -> Option<NameError> {
+        self.name_error()
+            .or_else(|| match (self.qname_invalid, self.colon) {
+                (Some((pos, c)), _) => Some(NameError::InvalidNameChar(pos, c)),
+                (None, Some(colon)) if colon + 1 == self.buf.len() => {
+                    Some(NameError::InvalidNameChar(colon, ':'))
+                },
+                (None, _) => None,
+            })
+    }
+
+    /// Returns the accepted string as `NameString`.
+    ///
+    /// # Failures
+    ///
+    /// Fails with [`NameError::Empty`] if no characters are accepted.
+    pub fn into_name_string(self) -> Result<NameString, NameError> {
+        match self.name_error() {
+            Some(e) => Err(e),
+            // This is safe because the characters are validated when pushed.
+            None => Ok(unsafe { NameString::new_unchecked(self.buf) }),
+        }
+    }
+
+    /// Returns the accepted string as `NcnameString`.
+    ///
+    /// # Failures
+    ///
+    /// Fails if the string is empty or contains a colon.
+    pub fn into_ncname_string(self) -> Result<NcnameString, NameError> {
+        match self.ncname_error() {
+            Some(e) => Err(e),
+            // This is safe because the characters are validated when pushed,
+            // and the string has no colons.
+            None => Ok(unsafe { NcnameString::new_unchecked(self.buf) }),
+        }
+    }
+
+    /// Returns the accepted string as `Qname`.
+    ///
+    /// # Failures
+    ///
+    /// Fails with the same error as [`QnameStr::new`][crate::strings::QnameStr::new]
+    /// if the string is not `QName`.
+    pub fn into_qname(mut self) -> Result<Qname, NameError> {
+        if let Some(e) = self.qname_error() {
+            return Err(e);
+        }
+        // These are safe because the characters are validated when pushed,
+        // and the colon separates two NCNames.
+        Ok(match self.colon {
+            Some(colon) => {
+                let local = self.buf.split_off(colon + 1);
+                self.buf.truncate(colon);
+                unsafe {
+                    Qname::from_prefix_and_local(
+                        NcnameString::new_unchecked(self.buf),
+                        NcnameString::new_unchecked(local),
+                    )
+                }
+            },
+            None => Qname::from_local(unsafe { NcnameString::new_unchecked(self.buf) }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+    use strings::{NameStr, NcnameStr, QnameStr};
+
+    fn build(s: &str) -> NameBuilder {
+        let mut builder = NameBuilder::new();
+        for c in s.chars() {
+            assert!(builder.push(c), "{:?} in {:?}", c, s);
+        }
+        builder
+    }
+
+    #[test]
+    fn push() {
+        let mut builder = NameBuilder::new();
+        assert!(!builder.push('-'));
+        assert!(builder.is_empty());
+        assert!(builder.push('a'));
+        assert!(builder.push('-'));
+        assert!(!builder.push(' '));
+        assert_eq!(builder.as_str(), "a-");
+        builder.clear();
+        assert!(builder.is_empty());
+        assert!(!builder.accepts('1'));
+    }
+
+    #[test]
+    fn same_as_validators() {
+        let cases = [
+            "a",
+            "a:b",
+            ":a",
+            "a:",
+            "::",
+            "a::b",
+            "a:b:c",
+            "a:-b:c",
+            "a:1",
+            "xml:lang",
+            "_.-",
+            "\u{E9}t\u{E9}:\u{E9}",
+        ];
+        for s in &cases {
+            assert_eq!(
+                build(s).into_name_string().map(|s| s.to_string()),
+                NameStr::new(s).map(|s| s.to_string()),
+                "{:?}",
+                s
+            );
+            assert_eq!(
+                build(s).into_ncname_string().map(|s| s.to_string()),
+                NcnameStr::new(s).map(|s| s.to_string()),
+                "{:?}",
+                s
+            );
+            assert_eq!(
+                build(s).into_qname(),
+                QnameStr::new(s).map(QnameStr::to_qname),
+                "{:?}",
+                s
+            );
+            assert_eq!(build(s).is_qname(), QnameStr::new(s).is_ok(), "{:?}", s);
+        }
+        assert_eq!(NameBuilder::new().into_qname(), Err(NameError::Empty));
+    }
+}
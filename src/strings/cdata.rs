@@ -0,0 +1,118 @@
+//! CDATA section content string types.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CData>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+use strings::content::{validate_chars, validate_no_sequence};
+use strings::ContentError;
+
+/// Validates the given string as `CData` consisting of `Char`s.
+fn validate_cdata_str<S: AsRef<str>>(s: S) -> Result<S, ContentError> {
+    validate_chars(s.as_ref(), |_| false)?;
+    validate_no_sequence(s.as_ref(), "]]>")?;
+    Ok(s)
+}
+
+define_custom_string! {
+    validator = validate_cdata_str;
+    error = ContentError;
+    borrowed CdataStr {
+        /// Borrowed CDATA section content.
+        ///
+        /// This consists of XML `Char`s, and does not contain `]]>`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CData>.
+    }
+    owned CdataString {
+        /// Owned CDATA section content.
+        ///
+        /// This consists of XML `Char`s, and does not contain `]]>`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CData>.
+    }
+}
+
+impl CdataStr {
+    /// Creates a new `CdataStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{CdataStr, ContentError};
+    /// # fn run() -> Result<(), ContentError> {
+    /// let s = CdataStr::new("<a> & <b>")?;
+    /// assert_eq!(s, "<a> & <b>");
+    ///
+    /// assert!(CdataStr::new("]]>").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&CdataStr, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `CdataStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is valid CDATA section content.
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&CdataStr` has surely valid CDATA section content.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it consists of XML `Char`s, and
+    /// * it does not contain `]]>`.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CdataString {
+    /// Creates a new `CdataString`.
+    pub fn new(s: String) -> Result<Self, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `CdataString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`CdataStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&CdataStr`][`CdataStr`] slice.
+    pub fn as_cdata_str(&self) -> &CdataStr {
+        self.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn validate_cdata() {
+        assert!(CdataStr::new("").is_ok());
+        assert!(CdataStr::new("]]&gt;").is_ok());
+        assert_eq!(
+            CdataStr::new("a]]>b"),
+            Err(ContentError::ForbiddenSequence(1, "]]>"))
+        );
+        assert_eq!(
+            CdataString::new("\u{1}".to_owned()),
+            Err(ContentError::InvalidChar(0, '\u{1}'))
+        );
+    }
+}
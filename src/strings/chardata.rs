@@ -0,0 +1,124 @@
+//! Character data string types.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CharData>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+use strings::content::{validate_chars, validate_no_sequence};
+use strings::ContentError;
+
+/// Validates the given string as `CharData` consisting of `Char`s.
+fn validate_chardata_str<S: AsRef<str>>(s: S) -> Result<S, ContentError> {
+    validate_chars(s.as_ref(), |c| c == '<' || c == '&')?;
+    validate_no_sequence(s.as_ref(), "]]>")?;
+    Ok(s)
+}
+
+define_custom_string! {
+    validator = validate_chardata_str;
+    error = ContentError;
+    borrowed CharDataStr {
+        /// Borrowed character data.
+        ///
+        /// This consists of XML `Char`s, and does not contain `<`, `&` and `]]>`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CharData>.
+    }
+    owned CharDataString {
+        /// Owned character data.
+        ///
+        /// This consists of XML `Char`s, and does not contain `<`, `&` and `]]>`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CharData>.
+    }
+}
+
+impl CharDataStr {
+    /// Creates a new `CharDataStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{CharDataStr, ContentError};
+    /// # fn run() -> Result<(), ContentError> {
+    /// let s = CharDataStr::new("a > b")?;
+    /// assert_eq!(s, "a > b");
+    ///
+    /// assert!(CharDataStr::new("a < b").is_err());
+    /// assert!(CharDataStr::new("a & b").is_err());
+    /// assert!(CharDataStr::new("]]>").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&CharDataStr, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `CharDataStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is valid character data.
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&CharDataStr` has surely valid character data.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it consists of XML `Char`s, and
+    /// * it does not contain `<`, `&` and `]]>`.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CharDataString {
+    /// Creates a new `CharDataString`.
+    pub fn new(s: String) -> Result<Self, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `CharDataString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`CharDataStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&CharDataStr`][`CharDataStr`] slice.
+    pub fn as_chardata_str(&self) -> &CharDataStr {
+        self.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn validate_chardata() {
+        assert!(CharDataStr::new("").is_ok());
+        assert!(CharDataStr::new("]]").is_ok());
+        assert_eq!(
+            CharDataStr::new("a\u{0}"),
+            Err(ContentError::InvalidChar(1, '\u{0}'))
+        );
+        assert_eq!(
+            CharDataStr::new("ab<"),
+            Err(ContentError::ForbiddenChar(2, '<'))
+        );
+        assert_eq!(
+            CharDataString::new("a]]>".to_owned()),
+            Err(ContentError::ForbiddenSequence(1, "]]>"))
+        );
+    }
+}
@@ -0,0 +1,50 @@
+//! Character predicates.
+
+/// Checks whether the given character is XML `Char`.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-Char>.
+pub fn is_xml_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{9}'
+            | '\u{A}'
+            | '\u{D}'
+            | '\u{20}'..='\u{D7FF}'
+            | '\u{E000}'..='\u{FFFD}'
+            | '\u{10000}'..='\u{10FFFF}'
+    )
+}
+
+/// Checks whether the given character is XML 1.1 `Char`.
+///
+/// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Char>.
+pub fn is_xml11_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..='\u{10FFFF}'
+    )
+}
+
+/// Checks whether the given character is XML 1.1 `RestrictedChar`.
+///
+/// Restricted characters can appear in XML 1.1 documents only as character
+/// references.
+///
+/// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-RestrictedChar>.
+pub fn is_restricted_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1}'..='\u{8}'
+            | '\u{B}'..='\u{C}'
+            | '\u{E}'..='\u{1F}'
+            | '\u{7F}'..='\u{84}'
+            | '\u{86}'..='\u{9F}'
+    )
+}
+
+/// Checks whether the given character is XML whitespace character.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-S>.
+pub fn is_whitespace_char(c: char) -> bool {
+    matches!(c, '\u{20}' | '\u{9}' | '\u{D}' | '\u{A}')
+}
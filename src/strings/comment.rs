@@ -0,0 +1,129 @@
+//! Comment string types.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-Comment>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+use strings::content::{validate_chars, validate_no_sequence};
+use strings::ContentError;
+
+/// Validates the given string as comment text.
+fn validate_comment_str<S: AsRef<str>>(s: S) -> Result<S, ContentError> {
+    {
+        let s = s.as_ref();
+        validate_chars(s, |_| false)?;
+        validate_no_sequence(s, "--")?;
+        if s.ends_with('-') {
+            return Err(ContentError::TrailingHyphen(s.len() - 1));
+        }
+    }
+    Ok(s)
+}
+
+define_custom_string! {
+    validator = validate_comment_str;
+    error = ContentError;
+    borrowed CommentStr {
+        /// Borrowed comment text.
+        ///
+        /// This consists of XML `Char`s, does not contain `--`, and does not
+        /// end with `-`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-Comment>.
+    }
+    owned CommentString {
+        /// Owned comment text.
+        ///
+        /// This consists of XML `Char`s, does not contain `--`, and does not
+        /// end with `-`.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-Comment>.
+    }
+}
+
+impl CommentStr {
+    /// Creates a new `CommentStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{CommentStr, ContentError};
+    /// # fn run() -> Result<(), ContentError> {
+    /// let s = CommentStr::new(" <a> - b ")?;
+    /// assert_eq!(s, " <a> - b ");
+    ///
+    /// assert!(CommentStr::new("a -- b").is_err());
+    /// assert!(CommentStr::new("a-").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&CommentStr, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `CommentStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is valid comment text.
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&CommentStr` has surely valid comment text.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it consists of XML `Char`s, and
+    /// * it does not contain `--`, and
+    /// * it does not end with `-`.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CommentString {
+    /// Creates a new `CommentString`.
+    pub fn new(s: String) -> Result<Self, ContentError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `CommentString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`CommentStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&CommentStr`][`CommentStr`] slice.
+    pub fn as_comment_str(&self) -> &CommentStr {
+        self.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn validate_comment() {
+        assert!(CommentStr::new("").is_ok());
+        assert!(CommentStr::new("-a-").is_err());
+        assert_eq!(
+            CommentStr::new("a--b"),
+            Err(ContentError::ForbiddenSequence(1, "--"))
+        );
+        assert_eq!(CommentStr::new("ab-"), Err(ContentError::TrailingHyphen(2)));
+        assert_eq!(
+            CommentString::new("\u{FFFF}".to_owned()),
+            Err(ContentError::InvalidChar(0, '\u{FFFF}'))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Content string errors and common validation.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+
+use strings::is_xml_char;
+
+/// Content string error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ContentError {
+    /// Has a character which is not XML `Char`.
+    InvalidChar(usize, char),
+    /// Has a character which is not allowed in the content.
+    ForbiddenChar(usize, char),
+    /// Has a sequence which is not allowed in the content.
+    ForbiddenSequence(usize, &'static str),
+    /// Ends with a hyphen (`-`).
+    ///
+    /// This is not allowed for comments.
+    TrailingHyphen(usize),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ContentError {}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContentError::InvalidChar(pos, c) => {
+                write!(f, "Invalid character at byte position {}: {:?}", pos, c)
+            },
+            ContentError::ForbiddenChar(pos, c) => {
+                write!(f, "Forbidden character at byte position {}: {:?}", pos, c)
+            },
+            ContentError::ForbiddenSequence(pos, seq) => {
+                write!(f, "Forbidden sequence `{}` at byte position {}", seq, pos)
+            },
+            ContentError::TrailingHyphen(pos) => {
+                write!(f, "Content should not end with `-` (byte position {})", pos)
+            },
+        }
+    }
+}
+
+/// Validates that the string consists of XML `Char`s except the forbidden ones.
+pub(crate) fn validate_chars<F>(s: &str, forbidden: F) -> Result<(), ContentError>
+where
+    F: Fn(char) -> bool,
+{
+    for (pos, c) in s.char_indices() {
+        if !is_xml_char(c) {
+            return Err(ContentError::InvalidChar(pos, c));
+        }
+        if forbidden(c) {
+            return Err(ContentError::ForbiddenChar(pos, c));
+        }
+    }
+    Ok(())
+}
+
+/// Validates that the string does not contain the forbidden sequence.
+pub(crate) fn validate_no_sequence(s: &str, seq: &'static str) -> Result<(), ContentError> {
+    match s.find(seq) {
+        Some(pos) => Err(ContentError::ForbiddenSequence(pos, seq)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,175 @@
+//! Encoding name string types.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-EncName>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+#[cfg(feature = "nom-4")]
+use nom::{self, types::CompleteStr};
+
+use strings::NameError;
+
+/// Checks whether the given character is encoding name start character.
+fn is_encname_start_char(c: char) -> bool {
+    c.is_ascii_alphabetic()
+}
+
+/// Checks whether the given character is encoding name character.
+fn is_encname_char(c: char) -> bool {
+    matches!(c, 'A'..='Z' | 'a'..='z' | '0'..='9' | '.' | '_' | '-')
+}
+
+/// Validates the given string as `EncName`.
+fn validate_encname_str<S: AsRef<str>>(s: S) -> Result<S, NameError> {
+    {
+        let mut chars = s.as_ref().char_indices();
+        match chars.next() {
+            None => return Err(NameError::Empty),
+            Some((_, c)) if !is_encname_start_char(c) => {
+                return Err(NameError::InvalidNameChar(0, c))
+            },
+            Some(_) => {},
+        }
+        if let Some((pos, c)) = chars.find(|&(_, c)| !is_encname_char(c)) {
+            return Err(NameError::InvalidNameChar(pos, c));
+        }
+    }
+    Ok(s)
+}
+
+define_custom_string! {
+    validator = validate_encname_str;
+    error = NameError;
+    borrowed EncNameStr {
+        /// Borrowed encoding name.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-EncName>.
+    }
+    owned EncNameString {
+        /// Owned encoding name.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-EncName>.
+    }
+}
+
+impl EncNameStr {
+    /// Creates a new `EncNameStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{EncNameStr, NameError};
+    /// # fn run() -> Result<(), NameError> {
+    /// let s = EncNameStr::new("ISO-8859-1")?;
+    /// assert_eq!(s, "ISO-8859-1");
+    ///
+    /// assert!(EncNameStr::new("8859-1").is_err());
+    /// assert!(EncNameStr::new("UTF 8").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&EncNameStr, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `EncNameStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is `EncName` (defined in XML spec).
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&EncNameStr` has surely `EncName` string.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it matches `[A-Za-z] ([A-Za-z0-9._] | '-')*`.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Checks whether the encoding name matches the given one ignoring ASCII case.
+    ///
+    /// Encoding names are case-insensitive.
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        self.as_str().eq_ignore_ascii_case(other)
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl EncNameStr {
+    named!(
+        pub nom_parse<CompleteStr<'_>, &Self>,
+        map!(
+            recognize!(pair!(
+                verify!(nom::anychar, is_encname_start_char),
+                take_while!(is_encname_char)
+            )),
+            |s| Self::new(*s).unwrap_or_else(|e| {
+                panic!("Parser is inconsistent with validator of `EncNameStr`: {}", e)
+            })
+        )
+    );
+}
+
+#[cfg(feature = "alloc")]
+impl EncNameString {
+    /// Creates a new `EncNameString`.
+    pub fn new(s: String) -> Result<Self, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `EncNameString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`EncNameStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&EncNameStr`][`EncNameStr`] slice.
+    pub fn as_encname_str(&self) -> &EncNameStr {
+        self.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn validate_encname() {
+        assert!(EncNameStr::new("UTF-8").is_ok());
+        assert!(EncNameStr::new("x.y_z").is_ok());
+        assert_eq!(EncNameStr::new(""), Err(NameError::Empty));
+        assert_eq!(
+            EncNameStr::new("-utf"),
+            Err(NameError::InvalidNameChar(0, '-'))
+        );
+        assert_eq!(
+            EncNameString::new("utf:8".to_owned()),
+            Err(NameError::InvalidNameChar(3, ':'))
+        );
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[cfg(test)]
+mod nom_tests {
+    use super::*;
+
+    #[test]
+    fn parse_encname() {
+        let s = EncNameStr::new("UTF-8").expect("Should never fail");
+        assert_eq!(EncNameStr::nom_parse("UTF-8'".into()), Ok(("'".into(), s)));
+        assert!(EncNameStr::nom_parse("8bit".into()).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! Name validation over decoded characters of non-UTF-8 buffers.
+
+use std::string::String;
+
+use strings::{is_name_char, is_name_start_char, is_ncname_char, is_ncname_start_char};
+use strings::{NameError, NameKind};
+
+/// Validation state.
+#[derive(Debug, Clone, Copy)]
+struct State {
+    /// Kind of name.
+    kind: NameKind,
+    /// Whether the next character is the first one.
+    first: bool,
+    /// Position of the colon, if the previous character is the colon of a QName.
+    after_colon: Option<usize>,
+    /// Whether a colon appeared in a QName.
+    has_colon: bool,
+}
+
+impl State {
+    /// Creates a new `State`.
+    fn new(kind: NameKind) -> Self {
+        Self {
+            kind,
+            first: true,
+            after_colon: None,
+            has_colon: false,
+        }
+    }
+
+    /// Checks the character at the given position and updates the state.
+    ///
+    /// The state is not updated if the character is not acceptable.
+    fn accept(&mut self, c: char, pos: usize) -> bool {
+        let ok = match self.kind {
+            NameKind::Name if self.first => is_name_start_char(c),
+            NameKind::Name | NameKind::Nmtoken => is_name_char(c),
+            NameKind::Ncname if self.first => is_ncname_start_char(c),
+            NameKind::Ncname => is_ncname_char(c),
+            NameKind::Qname if c == ':' => !self.first && !self.has_colon,
+            NameKind::Qname if self.first || self.after_colon.is_some() => is_ncname_start_char(c),
+            NameKind::Qname => is_ncname_char(c),
+        };
+        if ok {
+            self.first = false;
+            self.after_colon = None;
+            if self.kind == NameKind::Qname && c == ':' {
+                self.has_colon = true;
+                self.after_colon = Some(pos);
+            }
+        }
+        ok
+    }
+}
+
+/// Decodes and validates the name.
+///
+/// The positions are in the code units of the source buffer.
+pub(crate) fn decode_name<I>(chars: I, kind: NameKind) -> Result<String, NameError>
+where
+    I: IntoIterator<Item = (usize, Result<char, NameError>)>,
+{
+    let mut state = State::new(kind);
+    let mut s = String::new();
+    for (pos, c) in chars {
+        let c = c?;
+        if !state.accept(c, pos) {
+            return Err(NameError::InvalidNameChar(pos, c));
+        }
+        s.push(c);
+    }
+    if s.is_empty() {
+        return Err(NameError::Empty);
+    }
+    if let Some(colon) = state.after_colon {
+        return Err(NameError::InvalidNameChar(colon, ':'));
+    }
+    Ok(s)
+}
+
+/// Returns the length of the longest prefix which is the name.
+///
+/// The length is in the code units of the source buffer, whose total length
+/// is `len`.
+pub(crate) fn scan_name<I>(chars: I, kind: NameKind, len: usize) -> usize
+where
+    I: IntoIterator<Item = (usize, Result<char, NameError>)>,
+{
+    let mut state = State::new(kind);
+    let mut end = len;
+    for (pos, c) in chars {
+        match c {
+            Ok(c) if state.accept(c, pos) => {},
+            _ => {
+                end = pos;
+                break;
+            },
+        }
+    }
+    // A trailing colon is not a part of the QName.
+    state.after_colon.unwrap_or(end)
+}
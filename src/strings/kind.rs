@@ -0,0 +1,121 @@
+//! Kinds of XML names.
+
+use std::fmt;
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+#[cfg(feature = "alloc")]
+use strings::{is_name_char, is_name_start_char, is_ncname_char, is_ncname_start_char};
+use strings::{NameError, NameStr, NcnameStr, NmtokenStr, QnameStr};
+
+/// Kind of XML name, i.e. the production which a name is validated as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NameKind {
+    /// `Name`.
+    Name,
+    /// `NCName`.
+    Ncname,
+    /// `Nmtoken`.
+    Nmtoken,
+    /// `QName`.
+    Qname,
+}
+
+impl NameKind {
+    /// Returns the name of the production.
+    pub fn name(self) -> &'static str {
+        match self {
+            NameKind::Name => "Name",
+            NameKind::Ncname => "NCName",
+            NameKind::Nmtoken => "Nmtoken",
+            NameKind::Qname => "QName",
+        }
+    }
+
+    /// Returns the URL of the production in the spec.
+    pub fn spec_url(self) -> &'static str {
+        match self {
+            NameKind::Name => "https://www.w3.org/TR/xml11/#NT-Name",
+            NameKind::Ncname => "https://www.w3.org/TR/REC-xml-names/#NT-NCName",
+            NameKind::Nmtoken => "https://www.w3.org/TR/xml11/#NT-Nmtoken",
+            NameKind::Qname => "https://www.w3.org/TR/REC-xml-names/#NT-QName",
+        }
+    }
+
+    /// Validates the given string as the production.
+    pub fn validate(self, s: &str) -> Result<(), NameError> {
+        match self {
+            NameKind::Name => NameStr::new(s).map(|_| ()),
+            NameKind::Ncname => NcnameStr::new(s).map(|_| ()),
+            NameKind::Nmtoken => NmtokenStr::new(s).map(|_| ()),
+            NameKind::Qname => QnameStr::new(s).map(|_| ()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl NameKind {
+    /// Suggests a valid name similar to the given string.
+    ///
+    /// Invalid characters are replaced with `_`, and `_` is prepended if the
+    /// string cannot start the name.
+    /// Returns `None` if the string is already valid or no name is suggested.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::NameKind;
+    /// assert_eq!(NameKind::Name.suggest("1st item").as_ref().map(|s| &**s), Some("_1st_item"));
+    /// assert_eq!(NameKind::Qname.suggest("a:b:c").as_ref().map(|s| &**s), Some("a:b_c"));
+    /// assert_eq!(NameKind::Ncname.suggest("foo"), None);
+    /// assert_eq!(NameKind::Ncname.suggest(""), None);
+    /// ```
+    pub fn suggest(self, s: &str) -> Option<String> {
+        if s.is_empty() || self.validate(s).is_ok() {
+            return None;
+        }
+        let suggestion = match self {
+            NameKind::Name => sanitize(s, is_name_char, is_name_start_char),
+            NameKind::Ncname => sanitize(s, is_ncname_char, is_ncname_start_char),
+            NameKind::Nmtoken => s
+                .chars()
+                .map(|c| if is_name_char(c) { c } else { '_' })
+                .collect(),
+            NameKind::Qname => match s.find(':') {
+                Some(colon) if colon > 0 && colon + 1 < s.len() => {
+                    let prefix = sanitize(&s[..colon], is_ncname_char, is_ncname_start_char);
+                    let local = sanitize(&s[colon + 1..], is_ncname_char, is_ncname_start_char);
+                    format!("{}:{}", prefix, local)
+                },
+                _ => sanitize(s, is_ncname_char, is_ncname_start_char),
+            },
+        };
+        match self.validate(&suggestion) {
+            Ok(()) => Some(suggestion),
+            Err(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for NameKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Replaces invalid characters with `_`, and prepends `_` if necessary.
+#[cfg(feature = "alloc")]
+fn sanitize<F, G>(s: &str, is_char: F, is_start_char: G) -> String
+where
+    F: Fn(char) -> bool,
+    G: Fn(char) -> bool,
+{
+    let mut out = String::with_capacity(s.len() + 1);
+    for (i, c) in s.chars().enumerate() {
+        if i == 0 && !is_start_char(c) && is_char(c) {
+            out.push('_');
+        }
+        out.push(if is_char(c) { c } else { '_' });
+    }
+    out
+}
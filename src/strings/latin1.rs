@@ -0,0 +1,102 @@
+//! Name validation over ISO-8859-1 (Latin-1) buffers.
+//!
+//! Positions in errors and scan results are in bytes.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use xmlop_datatypes::strings::latin1;
+//! let bytes = b"caf\xE9 au lait";
+//! assert_eq!(latin1::scan_name(bytes), 4);
+//! assert_eq!(latin1::to_name_string(&bytes[..4]).unwrap(), "caf\u{E9}");
+//! ```
+
+use strings::encoded::{self, decode_name};
+use strings::NameKind;
+use strings::{NameError, NameString, NcnameString, NmtokenString, QnameString};
+
+/// Decodes the Latin-1 buffer into characters with their positions.
+fn chars(bytes: &[u8]) -> impl Iterator<Item = (usize, Result<char, NameError>)> + '_ {
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(pos, &b)| (pos, Ok(char::from(b))))
+}
+
+/// Validates the Latin-1 buffer as `Name` and converts it to [`NameString`].
+pub fn to_name_string(bytes: &[u8]) -> Result<NameString, NameError> {
+    let s = decode_name(chars(bytes), NameKind::Name)?;
+    unsafe {
+        // This is safe because the string is validated as `Name`.
+        Ok(NameString::new_unchecked(s))
+    }
+}
+
+/// Validates the Latin-1 buffer as `NCName` and converts it to [`NcnameString`].
+pub fn to_ncname_string(bytes: &[u8]) -> Result<NcnameString, NameError> {
+    let s = decode_name(chars(bytes), NameKind::Ncname)?;
+    unsafe {
+        // This is safe because the string is validated as `NCName`.
+        Ok(NcnameString::new_unchecked(s))
+    }
+}
+
+/// Validates the Latin-1 buffer as `Nmtoken` and converts it to [`NmtokenString`].
+pub fn to_nmtoken_string(bytes: &[u8]) -> Result<NmtokenString, NameError> {
+    let s = decode_name(chars(bytes), NameKind::Nmtoken)?;
+    unsafe {
+        // This is safe because the string is validated as `Nmtoken`.
+        Ok(NmtokenString::new_unchecked(s))
+    }
+}
+
+/// Validates the Latin-1 buffer as `QName` and converts it to [`QnameString`].
+pub fn to_qname_string(bytes: &[u8]) -> Result<QnameString, NameError> {
+    let s = decode_name(chars(bytes), NameKind::Qname)?;
+    unsafe {
+        // This is safe because the string is validated as `QName`.
+        Ok(QnameString::new_unchecked(s))
+    }
+}
+
+/// Returns the length of the longest prefix which is `Name`, or 0 if none.
+pub fn scan_name(bytes: &[u8]) -> usize {
+    encoded::scan_name(chars(bytes), NameKind::Name, bytes.len())
+}
+
+/// Returns the length of the longest prefix which is `NCName`, or 0 if none.
+pub fn scan_ncname(bytes: &[u8]) -> usize {
+    encoded::scan_name(chars(bytes), NameKind::Ncname, bytes.len())
+}
+
+/// Returns the length of the longest prefix which is `Nmtoken`, or 0 if none.
+pub fn scan_nmtoken(bytes: &[u8]) -> usize {
+    encoded::scan_name(chars(bytes), NameKind::Nmtoken, bytes.len())
+}
+
+/// Returns the length of the longest prefix which is `QName`, or 0 if none.
+pub fn scan_qname(bytes: &[u8]) -> usize {
+    encoded::scan_name(chars(bytes), NameKind::Qname, bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin1_names() {
+        assert_eq!(
+            to_name_string(b"\xC0\xD7"),
+            Err(NameError::InvalidNameChar(1, '\u{D7}'))
+        );
+        assert_eq!(
+            to_qname_string(b"p:\xB7"),
+            Err(NameError::InvalidNameChar(2, '\u{B7}'))
+        );
+        assert_eq!(to_nmtoken_string(b"\xB7").unwrap(), "\u{B7}");
+        assert_eq!(to_ncname_string(b""), Err(NameError::Empty));
+        assert_eq!(scan_qname(b"p:l\xE9 "), 4);
+        assert_eq!(scan_ncname(b"p:l"), 1);
+        assert_eq!(scan_nmtoken(b"\xF7"), 0);
+    }
+}
@@ -0,0 +1,296 @@
+//! Validation limits for untrusted input.
+
+use std::fmt;
+#[cfg(feature = "alloc")]
+use std::vec::Vec;
+
+#[cfg(feature = "nom-4")]
+use nom::{self, types::CompleteStr, ErrorKind, IResult};
+
+use strings::NameError;
+#[cfg(feature = "alloc")]
+use strings::{is_whitespace_char, NameStr};
+
+/// Limit which is exceeded.
+///
+/// Each variant has the configured limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Limit {
+    /// Name length in bytes.
+    NameBytes(usize),
+    /// Name length in characters.
+    NameChars(usize),
+    /// Number of list items.
+    ListItems(usize),
+    /// Prefix length in bytes.
+    PrefixBytes(usize),
+    /// Local part length in bytes.
+    LocalBytes(usize),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Limit::NameBytes(limit) => write!(f, "name length exceeds {} bytes", limit),
+            Limit::NameChars(limit) => write!(f, "name length exceeds {} characters", limit),
+            Limit::ListItems(limit) => write!(f, "list has more than {} items", limit),
+            Limit::PrefixBytes(limit) => write!(f, "prefix length exceeds {} bytes", limit),
+            Limit::LocalBytes(limit) => write!(f, "local part length exceeds {} bytes", limit),
+        }
+    }
+}
+
+/// Validation limits.
+///
+/// The default limits are suitable for untrusted documents.
+/// Lengths are checked before the characters are validated, so overlong
+/// inputs are refused without scanning them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ValidationLimits {
+    /// Maximum name length in bytes.
+    pub max_name_bytes: usize,
+    /// Maximum name length in characters.
+    pub max_name_chars: usize,
+    /// Maximum number of items in a whitespace-separated list.
+    pub max_list_items: usize,
+    /// Maximum length of a QName prefix in bytes.
+    pub max_prefix_bytes: usize,
+    /// Maximum length of a QName local part in bytes.
+    pub max_local_bytes: usize,
+}
+
+impl ValidationLimits {
+    /// Returns limits which never fail.
+    pub fn unlimited() -> Self {
+        Self {
+            max_name_bytes: usize::MAX,
+            max_name_chars: usize::MAX,
+            max_list_items: usize::MAX,
+            max_prefix_bytes: usize::MAX,
+            max_local_bytes: usize::MAX,
+        }
+    }
+
+    /// Checks the length of the name.
+    pub(crate) fn check_name(&self, s: &str) -> Result<(), NameError> {
+        if s.len() > self.max_name_bytes {
+            return Err(NameError::LimitExceeded(Limit::NameBytes(
+                self.max_name_bytes,
+            )));
+        }
+        // A character takes at least one byte.
+        if s.len() > self.max_name_chars && s.chars().nth(self.max_name_chars).is_some() {
+            return Err(NameError::LimitExceeded(Limit::NameChars(
+                self.max_name_chars,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the length of the `NCName`, which is also a local part.
+    pub(crate) fn check_ncname(&self, s: &str) -> Result<(), NameError> {
+        self.check_name(s)?;
+        if s.len() > self.max_local_bytes {
+            return Err(NameError::LimitExceeded(Limit::LocalBytes(
+                self.max_local_bytes,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the lengths of the name, its prefix and its local part.
+    ///
+    /// The prefix is the part before the first colon, if any.
+    pub(crate) fn check_qname(&self, s: &str) -> Result<(), NameError> {
+        self.check_name(s)?;
+        let (prefix_len, local_len) = match s.find(':') {
+            Some(colon) => (colon, s.len() - colon - 1),
+            None => (0, s.len()),
+        };
+        if prefix_len > self.max_prefix_bytes {
+            return Err(NameError::LimitExceeded(Limit::PrefixBytes(
+                self.max_prefix_bytes,
+            )));
+        }
+        if local_len > self.max_local_bytes {
+            return Err(NameError::LimitExceeded(Limit::LocalBytes(
+                self.max_local_bytes,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the number of list items.
+    #[cfg(feature = "alloc")]
+    fn check_list_items(&self, count: usize) -> Result<(), NameError> {
+        if count > self.max_list_items {
+            return Err(NameError::LimitExceeded(Limit::ListItems(
+                self.max_list_items,
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        Self {
+            max_name_bytes: 4096,
+            max_name_chars: 1024,
+            max_list_items: 1024,
+            max_prefix_bytes: 1024,
+            max_local_bytes: 4096,
+        }
+    }
+}
+
+/// Parses whitespace-separated names (`Names`).
+///
+/// Leading and trailing whitespaces are allowed.
+/// Byte positions in errors are relative to `s`.
+///
+/// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Names>.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::strings::{parse_names, Limit, NameError, ValidationLimits};
+/// # fn run() -> Result<(), NameError> {
+/// let limits = ValidationLimits::default();
+/// let names = parse_names(" foo bar:baz ", &limits)?;
+/// assert_eq!(names, ["foo", "bar:baz"]);
+///
+/// let limits = ValidationLimits {
+///     max_list_items: 2,
+///     ..ValidationLimits::default()
+/// };
+/// assert_eq!(
+///     parse_names("a b c", &limits),
+///     Err(NameError::LimitExceeded(Limit::ListItems(2)))
+/// );
+/// # Ok(())
+/// # }
+/// # run().expect("Should never fail");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn parse_names<'a>(
+    s: &'a str,
+    limits: &ValidationLimits,
+) -> Result<Vec<&'a NameStr>, NameError> {
+    let mut names = Vec::new();
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(is_whitespace_char);
+        if rest.is_empty() {
+            break;
+        }
+        limits.check_list_items(names.len() + 1)?;
+        let len = rest.find(is_whitespace_char).unwrap_or(rest.len());
+        let offset = s.len() - rest.len();
+        let name = NameStr::new_with_limits(&rest[..len], limits).map_err(|e| match e {
+            NameError::InvalidNameChar(pos, c) => NameError::InvalidNameChar(offset + pos, c),
+            e => e,
+        })?;
+        names.push(name);
+        rest = &rest[len..];
+    }
+    if names.is_empty() {
+        return Err(NameError::Empty);
+    }
+    Ok(names)
+}
+
+/// Runs the name parser, consuming at most `max_bytes` bytes.
+///
+/// The parser sees a few bytes more than the limit (enough for the lookahead
+/// of the name parsers), so that the result is the same as the unlimited
+/// parser when the limit is not exceeded.
+/// Exceeding the limit is reported as a failure with `ErrorKind::TooLarge`.
+#[cfg(feature = "nom-4")]
+pub(crate) fn nom_parse_limited<'a, O, F, C>(
+    input: CompleteStr<'a>,
+    max_bytes: usize,
+    parser: F,
+    check: C,
+) -> IResult<CompleteStr<'a>, O>
+where
+    F: Fn(CompleteStr<'a>) -> IResult<CompleteStr<'a>, O>,
+    C: Fn(&str) -> Result<(), NameError>,
+{
+    /// Bytes visible to the parser beyond the limit.
+    const LOOKAHEAD: usize = 8;
+
+    let mut end = max_bytes.saturating_add(LOOKAHEAD).min(input.len());
+    while !input.is_char_boundary(end) {
+        end += 1;
+    }
+    let (rest, out) = parser(CompleteStr(&input[..end]))?;
+    let consumed = end - rest.len();
+    if consumed > max_bytes || check(&input[..consumed]).is_err() {
+        return Err(nom::Err::Failure(error_position!(
+            input,
+            ErrorKind::TooLarge
+        )));
+    }
+    Ok((CompleteStr(&input[consumed..]), out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_name_limits() {
+        let limits = ValidationLimits {
+            max_name_bytes: 6,
+            max_name_chars: 2,
+            ..ValidationLimits::unlimited()
+        };
+        assert_eq!(limits.check_name("ab"), Ok(()));
+        assert_eq!(limits.check_name("\u{3042}\u{3044}"), Ok(()));
+        assert_eq!(
+            limits.check_name("abc"),
+            Err(NameError::LimitExceeded(Limit::NameChars(2)))
+        );
+        assert_eq!(
+            limits.check_name("abcdefg"),
+            Err(NameError::LimitExceeded(Limit::NameBytes(6)))
+        );
+
+        let limits = ValidationLimits {
+            max_prefix_bytes: 2,
+            max_local_bytes: 3,
+            ..ValidationLimits::unlimited()
+        };
+        assert_eq!(limits.check_qname("ab:cde"), Ok(()));
+        assert_eq!(
+            limits.check_qname("abc:d"),
+            Err(NameError::LimitExceeded(Limit::PrefixBytes(2)))
+        );
+        assert_eq!(
+            limits.check_qname("a:bcde"),
+            Err(NameError::LimitExceeded(Limit::LocalBytes(3)))
+        );
+        assert_eq!(
+            limits.check_qname("bcde"),
+            Err(NameError::LimitExceeded(Limit::LocalBytes(3)))
+        );
+        assert_eq!(limits.check_ncname("bcd"), Ok(()));
+        assert_eq!(
+            limits.check_ncname("bcde"),
+            Err(NameError::LimitExceeded(Limit::LocalBytes(3)))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn parse_name_list() {
+        let limits = ValidationLimits::default();
+        assert_eq!(parse_names("", &limits), Err(NameError::Empty));
+        assert_eq!(parse_names(" \t", &limits), Err(NameError::Empty));
+        assert_eq!(
+            parse_names("foo 1bar", &limits),
+            Err(NameError::InvalidNameChar(4, '1'))
+        );
+    }
+}
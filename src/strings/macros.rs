@@ -1,97 +1,48 @@
 //! Macros for string types definition.
 
-/// Implement comparations.
-macro_rules! impl_cmp {
-    ($Inner:ty, $Lhs:ty, $Rhs:ty) => {
-        impl PartialEq<$Rhs> for $Lhs {
-            fn eq(&self, rhs: &$Rhs) -> bool {
-                AsRef::<$Inner>::as_ref(self).eq(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-        impl PartialEq<$Lhs> for $Rhs {
-            fn eq(&self, rhs: &$Lhs) -> bool {
-                AsRef::<$Inner>::as_ref(self).eq(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-        impl PartialOrd<$Rhs> for $Lhs {
-            fn partial_cmp(&self, rhs: &$Rhs) -> Option<::std::cmp::Ordering> {
-                AsRef::<$Inner>::as_ref(self).partial_cmp(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-        impl PartialOrd<$Lhs> for $Rhs {
-            fn partial_cmp(&self, rhs: &$Lhs) -> Option<::std::cmp::Ordering> {
-                AsRef::<$Inner>::as_ref(self).partial_cmp(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-    };
-    ($Inner:ty, $Lhs:ty, $Rhs:ty, $($lts:tt)*) => {
-        impl<$($lts)*> PartialEq<$Rhs> for $Lhs {
-            fn eq(&self, rhs: &$Rhs) -> bool {
-                AsRef::<$Inner>::as_ref(self).eq(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-        impl<$($lts)*> PartialEq<$Lhs> for $Rhs {
-            fn eq(&self, rhs: &$Lhs) -> bool {
-                AsRef::<$Inner>::as_ref(self).eq(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-        impl<$($lts)*> PartialOrd<$Rhs> for $Lhs {
-            fn partial_cmp(&self, rhs: &$Rhs) -> Option<::std::cmp::Ordering> {
-                AsRef::<$Inner>::as_ref(self).partial_cmp(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-        impl<$($lts)*> PartialOrd<$Lhs> for $Rhs {
-            fn partial_cmp(&self, rhs: &$Lhs) -> Option<::std::cmp::Ordering> {
-                AsRef::<$Inner>::as_ref(self).partial_cmp(AsRef::<$Inner>::as_ref(rhs))
-            }
-        }
-    };
-}
-
 /// Define custom string types and implement basic methods.
+///
+/// The validator is a function which takes `S: AsRef<str>` and returns
+/// `Result<S, E>`, where `E` is the given error type.
+///
+/// Trait impls are shared with the public [`define_xml_string!`] macro, so
+/// the types of this crate and the user-defined types have the same set.
 macro_rules! define_custom_string {
     (
+        validator = $validator:path;
+        error = $error:ty;
         borrowed $borrowed:ident { $(#[$borrowed_meta:meta])* }
         owned $owned:ident { $(#[$owned_meta:meta])* }
         $($feature:ident { $($feature_inner:tt)* })*
     ) => {
-        define_custom_string!(@borrowed, $borrowed, $owned, $($borrowed_meta)*);
-        define_custom_string!(@owned, $borrowed, $owned, $($owned_meta)*);
-        define_custom_string!(@cmp, $borrowed, $owned);
+        define_custom_string!(@borrowed, $validator, $error, $borrowed, $($borrowed_meta)*);
+        #[cfg(feature = "alloc")]
+        define_custom_string!(@owned, $validator, $error, $borrowed, $owned, $($owned_meta)*);
         $(
             define_custom_string!(@feature, $borrowed, $owned, $feature { $($feature_inner)* });
         )*
     };
-    (@borrowed, $borrowed:ident, $owned:ident, $($meta:meta)*) => {
-        // `derive_hash_xor_eq`: clippy lint.
-        #[allow(unknown_lints, derive_hash_xor_eq)]
-        #[derive(Debug, Eq, Hash, OpaqueTypedefUnsized)]
-        #[opaque_typedef(
-            derive(
-                AsRef(Deref, Self_),
-                Deref,
-                Display,
-                Into(Arc, Box, Rc, Inner),
-                PartialEq(Self_, Inner, InnerRev, InnerCow, SelfCow, SelfCowRev),
-                PartialOrd(Self_, Inner, InnerRev, InnerCow, SelfCow, SelfCowRev),
-                Ord,
-            )
-        )]
+    (@borrowed, $validator:path, $error:ty, $borrowed:ident, $($meta:meta)*) => {
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
         #[repr(transparent)]
         $(#[$meta])*
         pub struct $borrowed(str);
 
         impl $borrowed {
-            /// Creates a borrowed string from the string slice.
-            ///
-            /// This is intended to be used by `opaque_typedef`, and it is because this
-            /// function is not `unsafe`.
-            fn from_str_unchecked_implicitly_unsafe(s: &str) -> &Self {
-                unsafe {
-                    // It is caller's (`opaque_typedef`'s) responsibility to ensure that
-                    // this is safe.
-                    <Self as ::opaque_typedef::OpaqueTypedefUnsized>::from_inner_unchecked(s)
-                }
+            /// Creates a borrowed string from the string slice after validation.
+            fn try_from_inner(s: &str) -> Result<&Self, $error> {
+                let s = $validator(s)?;
+                Ok(unsafe {
+                    // This is safe because the string is validated.
+                    Self::from_inner_unchecked(s)
+                })
+            }
+
+            /// Creates a borrowed string from the string slice without validation.
+            unsafe fn from_inner_unchecked(s: &str) -> &Self {
+                // This is safe because the type is `#[repr(transparent)]`.
+                // It is caller's responsibility to ensure that the string is valid.
+                &*(s as *const str as *const Self)
             }
 
             /// Returns a reference to the inner string as `&str`.
@@ -100,104 +51,119 @@ macro_rules! define_custom_string {
             }
         }
 
-        impl ToOwned for $borrowed {
-            type Owned = $owned;
+        define_xml_string!(@borrowed_impls, $borrowed);
+    };
+    (
+        @owned, $validator:path, $error:ty, $borrowed:ident, $owned:ident,
+        $($meta:meta)*
+    ) => {
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $(#[$meta])*
+        pub struct $owned(::std::string::String);
 
-            fn to_owned(&self) -> Self::Owned {
-                unsafe {
-                    // This is safe because `&self.0` is validated at `self` creation.
-                    <$owned as ::opaque_typedef::OpaqueTypedef>::from_inner_unchecked(
-                        self.0.to_owned()
-                    )
-                }
+        impl $owned {
+            /// Creates an owned string from the string after validation.
+            fn try_from_inner(s: ::std::string::String) -> Result<Self, $error> {
+                $validator(s).map($owned)
             }
-        }
 
-        impl<'a> From<&'a $borrowed> for String {
-            fn from(s: &'a $borrowed) -> Self {
-                s.as_str().into()
+            /// Creates an owned string from the string without validation.
+            ///
+            /// It is caller's responsibility to ensure that the string is valid.
+            unsafe fn from_inner_unchecked(s: ::std::string::String) -> Self {
+                $owned(s)
             }
-        }
-    };
-    (@owned, $borrowed:ident, $owned:ident, $($meta:meta)*) => {
-        // `derive_hash_xor_eq`: clippy lint.
-        #[allow(unknown_lints, derive_hash_xor_eq)]
-        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedef)]
-        #[opaque_typedef(
-            derive(
-                AsRef(Deref, Inner),
-                Deref,
-                Display,
-                IntoInner,
-                PartialEq(Inner, InnerRev),
-                PartialOrd(Inner, InnerRev)
-            )
-        )]
-        $(#[$meta])*
-        pub struct $owned(String);
 
-        impl $owned {
             /// Returns a reference to the inner string as `&str`.
             pub fn as_str(&self) -> &str {
                 self.0.as_str()
             }
         }
 
-        impl ::std::borrow::Borrow<$borrowed> for $owned {
-            fn borrow(&self) -> &$borrowed {
-                self.as_ref()
+        define_xml_string!(@owned_impls, $borrowed, $owned);
+    };
+    (
+        @feature, $borrowed:ident, $owned:ident,
+        mutation { start = $is_start_char:path, char = $is_char:path }
+    ) => {
+        #[cfg(feature = "alloc")]
+        impl $owned {
+            /// Appends the given character.
+            ///
+            /// # Failures
+            ///
+            /// Fails if the character is not allowed, and then `self` is unchanged.
+            pub fn push(&mut self, c: char) -> Result<(), NameError> {
+                if !$is_char(c) {
+                    return Err(NameError::InvalidNameChar(self.0.len(), c));
+                }
+                self.0.push(c);
+                Ok(())
             }
-        }
 
-        impl AsRef<str> for $owned {
-            fn as_ref(&self) -> &str {
-                self.as_str()
+            /// Appends the given string.
+            ///
+            /// Only the appended string is validated.
+            ///
+            /// # Failures
+            ///
+            /// Fails if the string has a character which is not allowed, and then
+            /// `self` is unchanged.
+            /// The error position is the position in the result string.
+            pub fn push_str(&mut self, s: &str) -> Result<(), NameError> {
+                if let Some((pos, c)) = s.char_indices().find(|&(_, c)| !$is_char(c)) {
+                    return Err(NameError::InvalidNameChar(self.0.len() + pos, c));
+                }
+                self.0.push_str(s);
+                Ok(())
             }
-        }
 
-        impl<'a> From<&'a $borrowed> for $owned {
-            fn from(s: &'a $borrowed) -> Self {
-                s.to_owned()
+            /// Inserts the given character at the given byte position.
+            ///
+            /// # Failures
+            ///
+            /// Fails if the character is not allowed at the position, and then
+            /// `self` is unchanged.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `idx` is larger than the length, or it does not lie on a
+            /// char boundary.
+            pub fn insert(&mut self, idx: usize, c: char) -> Result<(), NameError> {
+                let valid = if idx == 0 { $is_start_char(c) } else { $is_char(c) };
+                if !valid {
+                    return Err(NameError::InvalidNameChar(idx, c));
+                }
+                self.0.insert(idx, c);
+                Ok(())
             }
-        }
-    };
-    (@cmp, $borrowed:ident, $owned:ident) => {
-        // $borrowed - $borrowed
-        impl_cmp!($borrowed, &'a $borrowed, $borrowed, 'a);
-        // $owned - $borrowed
-        impl_cmp!($borrowed, $owned, $borrowed);
-        impl_cmp!($borrowed, &'a $owned, $borrowed, 'a);
-        impl_cmp!($borrowed, $owned, &'a $borrowed, 'a);
-        // $owned - $owned
-        impl_cmp!($borrowed, &'a $owned, $owned, 'a);
-    };
-    (@feature, $borrowed:ident, $owned:ident, extra_impl { $($inner:ident),* }) => {
-        $(
-            define_custom_string!(@extra_impl, $borrowed, $owned, $inner);
-        )*
-    };
-    (@extra_impl, $borrowed:ident, $owned:ident, str_cmp) => {
-        // $borrowed - String
-        impl_cmp!(str, $borrowed, String);
-        impl_cmp!(str, &'a $borrowed, String, 'a);
-        impl_cmp!(str, $borrowed, &'a String, 'a);
-        // $owned - str
-        impl_cmp!(str, $owned, str);
-        impl_cmp!(str, &'a $owned, str, 'a);
-        impl_cmp!(str, $owned, &'a str, 'a);
-    };
-    (@extra_impl, $borrowed:ident, $owned:ident, pub_new) => {
-        impl $borrowed {
-            /// Creates a new string slice.
-            pub fn new(s: &str) -> &$borrowed {
-                <Self as ::opaque_typedef::OpaqueTypedefUnsized>::from_inner(s)
+
+            /// Shortens the string to the given length in bytes.
+            ///
+            /// If `new_len` is not less than the current length, this does nothing.
+            ///
+            /// # Failures
+            ///
+            /// Fails with [`NameError::Empty`] if `new_len` is zero, and then `self`
+            /// is unchanged.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `new_len` does not lie on a char boundary.
+            pub fn truncate(&mut self, new_len: usize) -> Result<(), NameError> {
+                if new_len == 0 {
+                    return Err(NameError::Empty);
+                }
+                self.0.truncate(new_len);
+                Ok(())
             }
-        }
 
-        impl $owned {
-            /// Creates a new owned string.
-            pub fn new(s: String) -> $owned {
-                <Self as ::opaque_typedef::OpaqueTypedef>::from_inner(s)
+            /// Appends the given name.
+            ///
+            /// This never fails because the characters of the name are always
+            /// allowed after the first character.
+            pub fn concat(&mut self, other: &$borrowed) {
+                self.0.push_str(other.as_str());
             }
         }
     };
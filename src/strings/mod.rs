@@ -1,15 +1,83 @@
 //! String types.
 
-pub(self) use self::name::validate_name_str;
+pub use self::attvalue::{AttValueDqStr, AttValueSqStr};
+#[cfg(feature = "alloc")]
+pub use self::attvalue::{AttValueDqString, AttValueSqString};
+#[cfg(feature = "alloc")]
+pub use self::builder::NameBuilder;
+pub use self::cdata::CdataStr;
+#[cfg(feature = "alloc")]
+pub use self::cdata::CdataString;
+pub use self::chardata::CharDataStr;
+#[cfg(feature = "alloc")]
+pub use self::chardata::CharDataString;
+pub use self::chars::{is_restricted_char, is_whitespace_char, is_xml11_char, is_xml_char};
+pub use self::comment::CommentStr;
+#[cfg(feature = "alloc")]
+pub use self::comment::CommentString;
+pub use self::content::ContentError;
+pub use self::encname::EncNameStr;
+#[cfg(feature = "alloc")]
+pub use self::encname::EncNameString;
+pub use self::kind::NameKind;
+#[cfg(feature = "alloc")]
+pub use self::limits::parse_names;
+pub use self::limits::{Limit, ValidationLimits};
+use self::name::validate_name_str;
+#[cfg(feature = "alloc")]
+pub use self::name::NameString;
 pub use self::name::{is_name_char, is_name_start_char};
-pub use self::name::{NameError, NameStr, NameString};
+pub use self::name::{NameError, NameStr};
+pub use self::ncname::NcnameStr;
+#[cfg(feature = "alloc")]
+pub use self::ncname::NcnameString;
 pub use self::ncname::{is_ncname_char, is_ncname_start_char};
-pub use self::ncname::{NcnameStr, NcnameString};
+pub use self::nmtoken::NmtokenStr;
+#[cfg(feature = "alloc")]
+pub use self::nmtoken::NmtokenString;
+pub use self::pitarget::PiTargetStr;
+#[cfg(feature = "alloc")]
+pub use self::pitarget::PiTargetString;
+#[cfg(feature = "alloc")]
 pub use self::qname::Qname;
+pub use self::qname::QnameStr;
+#[cfg(feature = "alloc")]
+pub use self::qname::QnameString;
+#[cfg(any(feature = "arbitrary", feature = "proptest", feature = "quickcheck"))]
+pub use self::sample::NearMiss;
 
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+mod attvalue;
+#[cfg(feature = "alloc")]
+mod builder;
+mod cdata;
+mod chardata;
+mod chars;
+mod comment;
+mod content;
+mod encname;
+#[cfg(feature = "alloc")]
+mod encoded;
+mod kind;
+#[cfg(feature = "alloc")]
+pub mod latin1;
+mod limits;
 mod name;
 mod ncname;
+mod nmtoken;
+mod pitarget;
 mod qname;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impl;
+#[cfg(any(feature = "arbitrary", feature = "proptest", feature = "quickcheck"))]
+mod sample;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(feature = "alloc")]
+pub mod utf16;
@@ -1,48 +1,56 @@
 //! Name string types.
-///
-/// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Name>.
+//!
+//! See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Name>.
+
+#[cfg(feature = "std")]
 use std::error;
 use std::fmt;
+#[cfg(feature = "alloc")]
+use std::string::String;
 
 #[cfg(feature = "nom-4")]
 use nom::{self, types::CompleteStr};
-use opaque_typedef::{OpaqueTypedef, OpaqueTypedefUnsized};
+
+#[cfg(feature = "nom-4")]
+use position::{self, Spanned};
+#[cfg(feature = "nom-4")]
+use strings::limits::nom_parse_limited;
+use strings::{Limit, ValidationLimits};
 
 /// Checks whether the given character is name start character.
 ///
 /// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-NameStartChar>.
-pub fn is_name_start_char(c: char) -> bool {
-    match c {
+pub const fn is_name_start_char(c: char) -> bool {
+    matches!(
+        c,
         ':'
-        | 'A'..='Z'
-        | '_'
-        | 'a'..='z'
-        | '\u{C0}'..='\u{D6}'
-        | '\u{D8}'..='\u{F6}'
-        | '\u{F8}'..='\u{2FF}'
-        | '\u{370}'..='\u{37D}'
-        | '\u{37F}'..='\u{1FFF}'
-        | '\u{200C}'..='\u{200D}'
-        | '\u{2070}'..='\u{218F}'
-        | '\u{2C00}'..='\u{2FEF}'
-        | '\u{3001}'..='\u{D7FF}'
-        | '\u{F900}'..='\u{FDCF}'
-        | '\u{FDF0}'..='\u{FFFD}'
-        | '\u{10000}'..='\u{EFFFF}' => true,
-        _ => false,
-    }
+            | 'A'..='Z'
+            | '_'
+            | 'a'..='z'
+            | '\u{C0}'..='\u{D6}'
+            | '\u{D8}'..='\u{F6}'
+            | '\u{F8}'..='\u{2FF}'
+            | '\u{370}'..='\u{37D}'
+            | '\u{37F}'..='\u{1FFF}'
+            | '\u{200C}'..='\u{200D}'
+            | '\u{2070}'..='\u{218F}'
+            | '\u{2C00}'..='\u{2FEF}'
+            | '\u{3001}'..='\u{D7FF}'
+            | '\u{F900}'..='\u{FDCF}'
+            | '\u{FDF0}'..='\u{FFFD}'
+            | '\u{10000}'..='\u{EFFFF}'
+    )
 }
 
 /// Checks whether the given character is name start character.
 ///
 /// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-NameChar>.
-pub fn is_name_char(c: char) -> bool {
-    is_name_start_char(c) || match c {
-        '-' | '.' | '0'..='9' | '\u{B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}' => {
-            true
-        },
-        _ => false,
-    }
+pub const fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(
+            c,
+            '-' | '.' | '0'..='9' | '\u{B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}'
+        )
 }
 
 /// XML name string error.
@@ -50,10 +58,20 @@ pub fn is_name_char(c: char) -> bool {
 pub enum NameError {
     /// Got empty string.
     Empty,
-    /// Has invalid character.
+    /// Has invalid character at the given byte position.
+    ///
+    /// The position is a byte offset into the string, not a character index,
+    /// so it can be used to slice the string.
     InvalidNameChar(usize, char),
+    /// Exceeds the validation limit.
+    LimitExceeded(Limit),
+    /// The name is reserved and cannot be used here.
+    ReservedName,
+    /// Has an unpaired UTF-16 surrogate at the given code unit position.
+    UnpairedSurrogate(usize),
 }
 
+#[cfg(feature = "std")]
 impl error::Error for NameError {}
 
 impl fmt::Display for NameError {
@@ -65,6 +83,11 @@ impl fmt::Display for NameError {
                 "Invalid name character at byte position {}: {:?}",
                 pos, c
             ),
+            NameError::LimitExceeded(limit) => write!(f, "XML name string is too long: {}", limit),
+            NameError::ReservedName => f.write_str("XML name string is reserved"),
+            NameError::UnpairedSurrogate(pos) => {
+                write!(f, "Unpaired UTF-16 surrogate at code unit position {}", pos)
+            },
         }
     }
 }
@@ -77,7 +100,7 @@ pub(crate) fn validate_name_str<S: AsRef<str>>(s: S) -> Result<S, NameError> {
     {
         let s = s.as_ref();
         assert!(!s.is_empty());
-        let mut chars = s.chars().enumerate();
+        let mut chars = s.char_indices();
         let (_, head) = chars
             .next()
             .unwrap_or_else(|| unreachable!("Should never fail because the string is empty"));
@@ -92,37 +115,19 @@ pub(crate) fn validate_name_str<S: AsRef<str>>(s: S) -> Result<S, NameError> {
 }
 
 define_custom_string! {
+    validator = validate_name_str;
+    error = NameError;
     borrowed NameStr {
         /// Borrowed XML Name.
         ///
         /// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Name>.
-        #[opaque_typedef(
-            validation(
-                validator = "validate_name_str",
-                error_type = "NameError",
-                error_msg = "Failed to create `NameStr`"
-            )
-        )]
     }
     owned NameString {
         /// Owned XML Name.
         ///
         /// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Name>.
-        #[opaque_typedef(
-            deref(
-                target = "NameStr",
-                deref = "NameStr::from_str_unchecked_implicitly_unsafe"
-            )
-        )]
-        #[opaque_typedef(
-            validation(
-                validator = "validate_name_str",
-                error_type = "NameError",
-                error_msg = "Failed to create `NameString`"
-            )
-        )]
     }
-    extra_impl { str_cmp }
+    mutation { start = is_name_start_char, char = is_name_char }
 }
 
 impl NameStr {
@@ -143,7 +148,37 @@ impl NameStr {
     /// # run().expect("Should never fail");
     /// ```
     pub fn new(s: &str) -> Result<&NameStr, NameError> {
-        <Self as OpaqueTypedefUnsized>::try_from_inner(s)
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `NameStr` if it does not exceed the limits.
+    ///
+    /// The limits are checked before the characters are validated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{NameStr, Limit, NameError, ValidationLimits};
+    /// # fn run() -> Result<(), NameError> {
+    /// let limits = ValidationLimits {
+    ///     max_name_bytes: 7,
+    ///     ..ValidationLimits::default()
+    /// };
+    /// assert!(NameStr::new_with_limits("foo:bar", &limits).is_ok());
+    /// assert_eq!(
+    ///     NameStr::new_with_limits("foo:barbaz", &limits),
+    ///     Err(NameError::LimitExceeded(Limit::NameBytes(7)))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new_with_limits<'a>(
+        s: &'a str,
+        limits: &ValidationLimits,
+    ) -> Result<&'a NameStr, NameError> {
+        limits.check_name(s)?;
+        Self::new(s)
     }
 
     /// Creates a new `NameStr` from the given string without validation.
@@ -160,7 +195,7 @@ impl NameStr {
     /// * it is XML `Name`.
     pub unsafe fn from_str_unchecked(s: &str) -> &Self {
         // It is caller's responsibility to ensure that this is safe.
-        <Self as OpaqueTypedefUnsized>::from_inner_unchecked(s)
+        Self::from_inner_unchecked(s)
     }
 }
 
@@ -168,7 +203,7 @@ impl NameStr {
 #[allow(missing_docs)]
 impl NameStr {
     named!(
-        pub nom_parse<CompleteStr, &Self>,
+        pub nom_parse<CompleteStr<'_>, &Self>,
         map!(
             preceded!(
                 verify!(
@@ -182,8 +217,31 @@ impl NameStr {
             })
         )
     );
+
+    /// Parses the string if it does not exceed the limits.
+    ///
+    /// Exceeding the limits is reported as a failure with `ErrorKind::TooLarge`.
+    pub fn nom_parse_with_limits<'a>(
+        input: CompleteStr<'a>,
+        limits: &ValidationLimits,
+    ) -> nom::IResult<CompleteStr<'a>, &'a Self> {
+        nom_parse_limited(input, limits.max_name_bytes, Self::nom_parse, |s| {
+            limits.check_name(s)
+        })
+    }
+
+    /// Parses the string and returns it with its span in `source`.
+    ///
+    /// `input` should be a suffix of `source`.
+    pub fn nom_parse_spanned<'a>(
+        input: CompleteStr<'a>,
+        source: &'a str,
+    ) -> nom::IResult<CompleteStr<'a>, Spanned<&'a Self>> {
+        position::nom_parse_spanned(input, source, Self::nom_parse)
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl NameString {
     /// Creates a new `NameString`.
     ///
@@ -202,7 +260,15 @@ impl NameString {
     /// # run().expect("Should never fail");
     /// ```
     pub fn new(s: String) -> Result<Self, NameError> {
-        <Self as OpaqueTypedef>::try_from_inner(s)
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `NameString` if it does not exceed the limits.
+    ///
+    /// See [`NameStr::new_with_limits`].
+    pub fn new_with_limits(s: String, limits: &ValidationLimits) -> Result<Self, NameError> {
+        limits.check_name(&s)?;
+        Self::new(s)
     }
 
     /// Creates a new `NameString` from the given string without validation.
@@ -218,7 +284,7 @@ impl NameString {
     ///
     /// * it is XML `Name`.
     pub unsafe fn new_unchecked(s: String) -> Self {
-        <Self as OpaqueTypedef>::from_inner_unchecked(s)
+        Self::from_inner_unchecked(s)
     }
 
     /// Returns [`&NameStr`][`NameStr`] slice.
@@ -251,6 +317,40 @@ impl NameString {
     );
 }
 
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn error_position_is_byte_offset() {
+        let s = "\u{E9}t\u{E9} x";
+        let err = NameStr::new(s).expect_err("Should fail");
+        assert_eq!(err, NameError::InvalidNameChar(5, ' '));
+        assert_eq!(&s[5..], " x");
+    }
+
+    #[test]
+    fn mutate_name() {
+        let mut s = NameString::new("item".to_owned()).expect("Should never fail");
+        s.push(':').expect("Should never fail");
+        s.push_str("x-1").expect("Should never fail");
+        assert_eq!(s, "item:x-1");
+        assert_eq!(s.push(' '), Err(NameError::InvalidNameChar(8, ' ')));
+        assert_eq!(s.push_str("2 3"), Err(NameError::InvalidNameChar(9, ' ')));
+        assert_eq!(s, "item:x-1");
+        assert_eq!(s.insert(0, '1'), Err(NameError::InvalidNameChar(0, '1')));
+        s.insert(4, '1').expect("Should never fail");
+        s.concat(NameStr::new("y").expect("Should never fail"));
+        assert_eq!(s, "item1:x-1y");
+        assert_eq!(s.truncate(0), Err(NameError::Empty));
+        s.truncate(4).expect("Should never fail");
+        assert_eq!(s, "item");
+    }
+}
+
 #[cfg(feature = "nom-4")]
 #[cfg(test)]
 mod nom_tests {
@@ -1,21 +1,29 @@
 //! NCName string types.
-///
-/// See <https://www.w3.org/TR/REC-xml-names/#NT-NCName>.
+//!
+//! See <https://www.w3.org/TR/REC-xml-names/#NT-NCName>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
 
 #[cfg(feature = "nom-4")]
 use nom::{self, types::CompleteStr};
-use opaque_typedef::{OpaqueTypedef, OpaqueTypedefUnsized};
 
-use strings::NameError;
+#[cfg(feature = "nom-4")]
+use position::{self, Spanned};
+#[cfg(feature = "nom-4")]
+use strings::limits::nom_parse_limited;
+#[cfg(feature = "alloc")]
+use strings::Qname;
 use strings::{is_name_char, is_name_start_char, validate_name_str};
+use strings::{NameError, ValidationLimits};
 
 /// Checks whether the given character is NCName start character.
-pub fn is_ncname_start_char(c: char) -> bool {
+pub const fn is_ncname_start_char(c: char) -> bool {
     c != ':' && is_name_start_char(c)
 }
 
 /// Checks whether the given character is NCName character.
-pub fn is_ncname_char(c: char) -> bool {
+pub const fn is_ncname_char(c: char) -> bool {
     c != ':' && is_name_char(c)
 }
 
@@ -29,37 +37,19 @@ fn validate_ncname_str<S: AsRef<str>>(s: S) -> Result<S, NameError> {
 }
 
 define_custom_string! {
+    validator = validate_ncname_str;
+    error = NameError;
     borrowed NcnameStr {
         /// Borrowed NCName, name string without colon (`:`).
         ///
         /// See <https://www.w3.org/TR/REC-xml-names/#NT-NCName>.
-        #[opaque_typedef(
-            validation(
-                validator = "validate_ncname_str",
-                error_type = "NameError",
-                error_msg = "Failed to create `NcnameStr`"
-            )
-        )]
     }
     owned NcnameString {
         /// Owned NCName, name string without colon (`:`).
         ///
         /// See <https://www.w3.org/TR/REC-xml-names/#NT-NCName>.
-        #[opaque_typedef(
-            deref(
-                target = "NcnameStr",
-                deref = "NcnameStr::from_str_unchecked_implicitly_unsafe"
-            )
-        )]
-        #[opaque_typedef(
-            validation(
-                validator = "validate_ncname_str",
-                error_type = "NameError",
-                error_msg = "Failed to create `NcnameString`"
-            )
-        )]
     }
-    extra_impl { str_cmp }
+    mutation { start = is_ncname_start_char, char = is_ncname_char }
 }
 
 impl NcnameStr {
@@ -81,7 +71,48 @@ impl NcnameStr {
     /// # run().expect("Should never fail");
     /// ```
     pub fn new(s: &str) -> Result<&NcnameStr, NameError> {
-        <Self as OpaqueTypedefUnsized>::try_from_inner(s)
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `NcnameStr` if it does not exceed the limits.
+    ///
+    /// `NCName` is checked against both the name limits and
+    /// [`max_local_bytes`][`ValidationLimits::max_local_bytes`].
+    /// The limits are checked before the characters are validated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{NcnameStr, Limit, NameError, ValidationLimits};
+    /// # fn run() -> Result<(), NameError> {
+    /// let limits = ValidationLimits {
+    ///     max_name_bytes: 7,
+    ///     ..ValidationLimits::default()
+    /// };
+    /// assert!(NcnameStr::new_with_limits("foo-bar", &limits).is_ok());
+    /// assert_eq!(
+    ///     NcnameStr::new_with_limits("foo-barbaz", &limits),
+    ///     Err(NameError::LimitExceeded(Limit::NameBytes(7)))
+    /// );
+    ///
+    /// let limits = ValidationLimits {
+    ///     max_local_bytes: 3,
+    ///     ..ValidationLimits::default()
+    /// };
+    /// assert_eq!(
+    ///     NcnameStr::new_with_limits("foo-bar", &limits),
+    ///     Err(NameError::LimitExceeded(Limit::LocalBytes(3)))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new_with_limits<'a>(
+        s: &'a str,
+        limits: &ValidationLimits,
+    ) -> Result<&'a NcnameStr, NameError> {
+        limits.check_ncname(s)?;
+        Self::new(s)
     }
 
     /// Creates a new `NcnameStr` from the given string without validation.
@@ -99,7 +130,7 @@ impl NcnameStr {
     /// * it does not contain colons (`:`).
     pub unsafe fn from_str_unchecked(s: &str) -> &Self {
         // It is caller's responsibility to ensure that this is safe.
-        <Self as OpaqueTypedefUnsized>::from_inner_unchecked(s)
+        Self::from_inner_unchecked(s)
     }
 }
 
@@ -107,7 +138,7 @@ impl NcnameStr {
 #[allow(missing_docs)]
 impl NcnameStr {
     named!(
-        pub nom_parse<CompleteStr, &Self>,
+        pub nom_parse<CompleteStr<'_>, &Self>,
         map!(
             delimited!(
                 verify!(peek!(nom::anychar), is_ncname_start_char),
@@ -127,8 +158,32 @@ impl NcnameStr {
             }
         )
     );
+
+    /// Parses the string if it does not exceed the limits.
+    ///
+    /// Exceeding the limits is reported as a failure with `ErrorKind::TooLarge`.
+    pub fn nom_parse_with_limits<'a>(
+        input: CompleteStr<'a>,
+        limits: &ValidationLimits,
+    ) -> nom::IResult<CompleteStr<'a>, &'a Self> {
+        let max_bytes = limits.max_name_bytes.min(limits.max_local_bytes);
+        nom_parse_limited(input, max_bytes, Self::nom_parse, |s| {
+            limits.check_ncname(s)
+        })
+    }
+
+    /// Parses the string and returns it with its span in `source`.
+    ///
+    /// `input` should be a suffix of `source`.
+    pub fn nom_parse_spanned<'a>(
+        input: CompleteStr<'a>,
+        source: &'a str,
+    ) -> nom::IResult<CompleteStr<'a>, Spanned<&'a Self>> {
+        position::nom_parse_spanned(input, source, Self::nom_parse)
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl NcnameString {
     /// Creates a new `NcnameString`.
     ///
@@ -148,7 +203,15 @@ impl NcnameString {
     /// # run().expect("Should never fail");
     /// ```
     pub fn new(s: String) -> Result<Self, NameError> {
-        <Self as OpaqueTypedef>::try_from_inner(s)
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `NcnameString` if it does not exceed the limits.
+    ///
+    /// See [`NcnameStr::new_with_limits`].
+    pub fn new_with_limits(s: String, limits: &ValidationLimits) -> Result<Self, NameError> {
+        limits.check_ncname(&s)?;
+        Self::new(s)
     }
 
     /// Creates a new `NcnameString` from the given string without validation.
@@ -165,7 +228,7 @@ impl NcnameString {
     /// * it is XML `Name`, and
     /// * it does not contain colons (`:`).
     pub unsafe fn new_unchecked(s: String) -> Self {
-        <Self as OpaqueTypedef>::from_inner_unchecked(s)
+        Self::from_inner_unchecked(s)
     }
 
     /// Returns [`&NcnameStr`][`NcnameStr`] slice.
@@ -184,6 +247,24 @@ impl NcnameString {
     pub fn as_name_str(&self) -> &NcnameStr {
         self.as_ref()
     }
+
+    /// Creates a [`Qname`] with the given prefix and `self` as the local part.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{NcnameString, NameError};
+    /// # fn run() -> Result<(), NameError> {
+    /// let prefix = NcnameString::new("xlink".to_owned())?;
+    /// let local = NcnameString::new("href".to_owned())?;
+    /// assert_eq!(local.with_prefix(prefix).to_string(), "xlink:href");
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn with_prefix<P: Into<NcnameString>>(self, prefix: P) -> Qname {
+        Qname::from_prefix_and_local(prefix, self)
+    }
 }
 
 #[cfg(feature = "nom-4")]
@@ -198,6 +279,31 @@ impl NcnameString {
     );
 }
 
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn mutate_ncname() {
+        let mut s = NcnameString::new("item".to_owned()).expect("Should never fail");
+        s.push('-').expect("Should never fail");
+        s.push_str("1").expect("Should never fail");
+        assert_eq!(s, "item-1");
+        assert_eq!(s.push(':'), Err(NameError::InvalidNameChar(6, ':')));
+        assert_eq!(s.push_str("2:3"), Err(NameError::InvalidNameChar(7, ':')));
+        assert_eq!(s.insert(0, '-'), Err(NameError::InvalidNameChar(0, '-')));
+        s.insert(0, '_').expect("Should never fail");
+        s.concat(NcnameStr::new("x").expect("Should never fail"));
+        assert_eq!(s, "_item-1x");
+        assert_eq!(s.truncate(0), Err(NameError::Empty));
+        s.truncate(5).expect("Should never fail");
+        assert_eq!(s, "_item");
+    }
+}
+
 #[cfg(feature = "nom-4")]
 #[cfg(test)]
 mod nom_tests {
@@ -0,0 +1,129 @@
+//! Name token string types.
+//!
+//! See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Nmtoken>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+#[cfg(feature = "nom-4")]
+use nom::types::CompleteStr;
+
+use strings::{is_name_char, NameError};
+
+/// Validates the given string as `Nmtoken`.
+fn validate_nmtoken_str<S: AsRef<str>>(s: S) -> Result<S, NameError> {
+    if s.as_ref().is_empty() {
+        return Err(NameError::Empty);
+    }
+    if let Some((pos, c)) = s.as_ref().char_indices().find(|&(_, c)| !is_name_char(c)) {
+        return Err(NameError::InvalidNameChar(pos, c));
+    }
+    Ok(s)
+}
+
+define_custom_string! {
+    validator = validate_nmtoken_str;
+    error = NameError;
+    borrowed NmtokenStr {
+        /// Borrowed name token.
+        ///
+        /// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Nmtoken>.
+    }
+    owned NmtokenString {
+        /// Owned name token.
+        ///
+        /// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#NT-Nmtoken>.
+    }
+}
+
+impl NmtokenStr {
+    /// Creates a new `NmtokenStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{NameError, NmtokenStr};
+    /// # fn run() -> Result<(), NameError> {
+    /// let s = NmtokenStr::new("123-abc")?;
+    /// assert_eq!(s, "123-abc");
+    ///
+    /// assert!(NmtokenStr::new("a b").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&NmtokenStr, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `NmtokenStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is `Nmtoken` (defined in XML spec).
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&NmtokenStr` has surely `Nmtoken` string.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it is not empty, and
+    /// * it consists of name characters.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl NmtokenStr {
+    named!(
+        pub nom_parse<CompleteStr<'_>, &Self>,
+        map!(take_while1!(is_name_char), |s| {
+            Self::new(*s).unwrap_or_else(|e| {
+                panic!("Parser is inconsistent with validator of `NmtokenStr`: {}", e)
+            })
+        })
+    );
+}
+
+#[cfg(feature = "alloc")]
+impl NmtokenString {
+    /// Creates a new `NmtokenString`.
+    pub fn new(s: String) -> Result<Self, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `NmtokenString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`NmtokenStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&NmtokenStr`][`NmtokenStr`] slice.
+    pub fn as_nmtoken_str(&self) -> &NmtokenStr {
+        self.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn validate_nmtoken() {
+        assert!(NmtokenStr::new("-1.5").is_ok());
+        assert_eq!(NmtokenStr::new(""), Err(NameError::Empty));
+        assert_eq!(
+            NmtokenString::new("ab@".to_owned()),
+            Err(NameError::InvalidNameChar(2, '@'))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Processing instruction target string types.
+//!
+//! See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-PITarget>.
+
+#[cfg(feature = "alloc")]
+use std::string::String;
+
+#[cfg(feature = "nom-4")]
+use nom::types::CompleteStr;
+
+use strings::{validate_name_str, NameError, NameStr};
+
+/// Validates the given string as `PITarget`.
+fn validate_pi_target_str<S: AsRef<str>>(s: S) -> Result<S, NameError> {
+    let s = validate_name_str(s)?;
+    if s.as_ref().eq_ignore_ascii_case("xml") {
+        return Err(NameError::ReservedName);
+    }
+    Ok(s)
+}
+
+define_custom_string! {
+    validator = validate_pi_target_str;
+    error = NameError;
+    borrowed PiTargetStr {
+        /// Borrowed processing instruction target.
+        ///
+        /// This is XML `Name` other than `xml` in any case.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-PITarget>.
+    }
+    owned PiTargetString {
+        /// Owned processing instruction target.
+        ///
+        /// This is XML `Name` other than `xml` in any case.
+        ///
+        /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-PITarget>.
+    }
+}
+
+impl PiTargetStr {
+    /// Creates a new `PiTargetStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{NameError, PiTargetStr};
+    /// # fn run() -> Result<(), NameError> {
+    /// let s = PiTargetStr::new("xml-stylesheet")?;
+    /// assert_eq!(s, "xml-stylesheet");
+    ///
+    /// assert_eq!(PiTargetStr::new("XmL"), Err(NameError::ReservedName));
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&PiTargetStr, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `PiTargetStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is `PITarget` (defined in XML spec).
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&PiTargetStr` has surely `PITarget` string.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it is XML `Name`, and
+    /// * it is not `xml` in any case.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns the string as [`&NameStr`][`NameStr`].
+    pub fn as_name_str(&self) -> &NameStr {
+        unsafe {
+            // This is safe because `PITarget` is always XML `Name`.
+            NameStr::from_str_unchecked(self.as_str())
+        }
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl PiTargetStr {
+    named!(
+        pub nom_parse<CompleteStr<'_>, &Self>,
+        map_opt!(NameStr::nom_parse, |s| Self::new(NameStr::as_str(s)).ok())
+    );
+}
+
+#[cfg(feature = "alloc")]
+impl PiTargetString {
+    /// Creates a new `PiTargetString`.
+    pub fn new(s: String) -> Result<Self, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `PiTargetString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// See [`PiTargetStr::from_str_unchecked`].
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&PiTargetStr`][`PiTargetStr`] slice.
+    pub fn as_pi_target_str(&self) -> &PiTargetStr {
+        self.as_ref()
+    }
+}
+
+impl<'a> From<&'a PiTargetStr> for &'a NameStr {
+    fn from(s: &'a PiTargetStr) -> Self {
+        s.as_name_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+
+    use super::*;
+
+    #[test]
+    fn validate_pi_target() {
+        assert!(PiTargetStr::new("xml-model").is_ok());
+        assert!(PiTargetStr::new("xmlx").is_ok());
+        assert_eq!(PiTargetStr::new("xml"), Err(NameError::ReservedName));
+        assert_eq!(
+            PiTargetString::new("XML".to_owned()),
+            Err(NameError::ReservedName)
+        );
+        assert_eq!(
+            PiTargetStr::new("1xml"),
+            Err(NameError::InvalidNameChar(0, '1'))
+        );
+    }
+}
@@ -1,13 +1,294 @@
 //! QName string types.
 
+#[cfg(feature = "alloc")]
+use std::borrow::ToOwned;
+#[cfg(feature = "alloc")]
 use std::fmt;
+#[cfg(feature = "alloc")]
+use std::string::{String, ToString};
 
 #[cfg(feature = "nom-4")]
-use nom::types::CompleteStr;
+use nom::{self, types::CompleteStr};
 
-use strings::{NcnameStr, NcnameString};
+#[cfg(feature = "nom-4")]
+use position::{self, Spanned};
+#[cfg(feature = "nom-4")]
+use strings::limits::nom_parse_limited;
+#[cfg(feature = "alloc")]
+use strings::NcnameString;
+use strings::{is_ncname_start_char, validate_name_str, NameError, NameStr};
+use strings::{NcnameStr, ValidationLimits};
+
+/// Validates the given string as `QName`.
+fn validate_qname_str<S: AsRef<str>>(s: S) -> Result<S, NameError> {
+    let s = validate_name_str(s)?;
+    {
+        let s = s.as_ref();
+        let mut colons = s.match_indices(':').map(|(pos, _)| pos);
+        if let Some(colon) = colons.next() {
+            if colon == 0 {
+                return Err(NameError::InvalidNameChar(0, ':'));
+            }
+            match s[(colon + 1)..].chars().next() {
+                Some(c) if is_ncname_start_char(c) => {},
+                Some(c) => return Err(NameError::InvalidNameChar(colon + 1, c)),
+                None => return Err(NameError::InvalidNameChar(colon, ':')),
+            }
+            if let Some(pos) = colons.next() {
+                return Err(NameError::InvalidNameChar(pos, ':'));
+            }
+        }
+    }
+    Ok(s)
+}
+
+define_custom_string! {
+    validator = validate_qname_str;
+    error = NameError;
+    borrowed QnameStr {
+        /// Borrowed QName string, `NCName` optionally prefixed by `NCName` and a colon.
+        ///
+        /// See <https://www.w3.org/TR/REC-xml-names/#NT-QName>.
+    }
+    owned QnameString {
+        /// Owned QName string, `NCName` optionally prefixed by `NCName` and a colon.
+        ///
+        /// See <https://www.w3.org/TR/REC-xml-names/#NT-QName>.
+    }
+}
+
+impl QnameStr {
+    /// Creates a new `QnameStr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{QnameStr, NameError};
+    /// # fn run() -> Result<(), NameError> {
+    /// let s = QnameStr::new("foo:bar")?;
+    /// assert_eq!(s.prefix().map(|p| p.as_str()), Some("foo"));
+    /// assert_eq!(s.local(), "bar");
+    ///
+    /// assert!(QnameStr::new("foo:bar:baz").is_err());
+    /// assert!(QnameStr::new(":bar").is_err());
+    /// assert!(QnameStr::new("foo:").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: &str) -> Result<&QnameStr, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `QnameStr` if it does not exceed the limits.
+    ///
+    /// The limits are checked before the characters are validated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{QnameStr, Limit, NameError, ValidationLimits};
+    /// # fn run() -> Result<(), NameError> {
+    /// let limits = ValidationLimits {
+    ///     max_name_bytes: 7,
+    ///     ..ValidationLimits::default()
+    /// };
+    /// assert!(QnameStr::new_with_limits("foo:bar", &limits).is_ok());
+    /// assert_eq!(
+    ///     QnameStr::new_with_limits("foo:barbaz", &limits),
+    ///     Err(NameError::LimitExceeded(Limit::NameBytes(7)))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new_with_limits<'a>(
+        s: &'a str,
+        limits: &ValidationLimits,
+    ) -> Result<&'a QnameStr, NameError> {
+        limits.check_qname(s)?;
+        Self::new(s)
+    }
+
+    /// Creates a new `QnameStr` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is `QName` (defined in XML namespaces spec).
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `&QnameStr` has surely `QName` string.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it is XML `Name`, and
+    /// * it contains at most one colon (`:`), and
+    /// * both sides of the colon (if any) are `NCName`s.
+    pub unsafe fn from_str_unchecked(s: &str) -> &Self {
+        // It is caller's responsibility to ensure that this is safe.
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns the prefix and the local part.
+    pub fn prefix_and_local(&self) -> (Option<&NcnameStr>, &NcnameStr) {
+        let s = self.as_str();
+        unsafe {
+            // These are safe because `self` is validated as `QName` at creation.
+            match s.find(':') {
+                Some(colon) => (
+                    Some(NcnameStr::from_str_unchecked(&s[..colon])),
+                    NcnameStr::from_str_unchecked(&s[(colon + 1)..]),
+                ),
+                None => (None, NcnameStr::from_str_unchecked(s)),
+            }
+        }
+    }
+
+    /// Returns the prefix if available.
+    pub fn prefix(&self) -> Option<&NcnameStr> {
+        self.prefix_and_local().0
+    }
+
+    /// Returns the local part.
+    pub fn local(&self) -> &NcnameStr {
+        self.prefix_and_local().1
+    }
+
+    /// Returns the string as [`&NameStr`][`NameStr`].
+    pub fn as_name_str(&self) -> &NameStr {
+        unsafe {
+            // This is safe because `QName` is always XML `Name`.
+            NameStr::from_str_unchecked(self.as_str())
+        }
+    }
+
+    /// Creates a new [`Qname`] from `self`.
+    #[cfg(feature = "alloc")]
+    pub fn to_qname(&self) -> Qname {
+        let (prefix, local) = self.prefix_and_local();
+        Qname::new(prefix.map(ToOwned::to_owned), local.to_owned())
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl QnameStr {
+    named!(
+        pub nom_parse<CompleteStr<'_>, &Self>,
+        map!(
+            recognize!(
+                pair!(
+                    NcnameStr::nom_parse,
+                    opt!(complete!(preceded!(char!(':'), NcnameStr::nom_parse)))
+                )
+            ),
+            |s| {
+                Self::new(*s).unwrap_or_else(|e| {
+                    panic!(
+                        "Parser is inconsistent with validator of `QnameStr`: {}",
+                        e
+                    )
+                })
+            }
+        )
+    );
+
+    /// Parses the string if it does not exceed the limits.
+    ///
+    /// Exceeding the limits is reported as a failure with `ErrorKind::TooLarge`.
+    pub fn nom_parse_with_limits<'a>(
+        input: CompleteStr<'a>,
+        limits: &ValidationLimits,
+    ) -> nom::IResult<CompleteStr<'a>, &'a Self> {
+        nom_parse_limited(input, limits.max_name_bytes, Self::nom_parse, |s| {
+            limits.check_qname(s)
+        })
+    }
+
+    /// Parses the string and returns it with its span in `source`.
+    ///
+    /// `input` should be a suffix of `source`.
+    pub fn nom_parse_spanned<'a>(
+        input: CompleteStr<'a>,
+        source: &'a str,
+    ) -> nom::IResult<CompleteStr<'a>, Spanned<&'a Self>> {
+        position::nom_parse_spanned(input, source, Self::nom_parse)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl QnameString {
+    /// Creates a new `QnameString`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{QnameString, NameError};
+    /// # fn run() -> Result<(), NameError> {
+    /// let s1 = "foo:bar".to_owned();
+    /// let s2 = QnameString::new(s1.clone())?;
+    /// assert_eq!(s1, s2);
+    ///
+    /// assert!(QnameString::new("foo:bar:baz".to_owned()).is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(s: String) -> Result<Self, NameError> {
+        Self::try_from_inner(s)
+    }
+
+    /// Creates a new `QnameString` if it does not exceed the limits.
+    ///
+    /// See [`QnameStr::new_with_limits`].
+    pub fn new_with_limits(s: String, limits: &ValidationLimits) -> Result<Self, NameError> {
+        limits.check_qname(&s)?;
+        Self::new(s)
+    }
+
+    /// Creates a new `QnameString` from the given string without validation.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because it does not check that the string passed
+    /// to it is `QName` (defined in XML namespaces spec).
+    /// If this constraint is violated, undefined behavior results, as the rest
+    /// of Rust assumes that `QnameString` has surely `QName` string.
+    ///
+    /// So, the argument should fulfill:
+    ///
+    /// * it is XML `Name`, and
+    /// * it contains at most one colon (`:`), and
+    /// * both sides of the colon (if any) are `NCName`s.
+    pub unsafe fn new_unchecked(s: String) -> Self {
+        Self::from_inner_unchecked(s)
+    }
+
+    /// Returns [`&QnameStr`][`QnameStr`] slice.
+    pub fn as_qname_str(&self) -> &QnameStr {
+        self.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a QnameStr> for Qname {
+    fn from(s: &'a QnameStr) -> Self {
+        s.to_qname()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a Qname> for QnameString {
+    fn from(q: &'a Qname) -> Self {
+        unsafe {
+            // This is safe because the lexical form of `Qname` is always `QName`.
+            QnameString::new_unchecked(q.to_string())
+        }
+    }
+}
 
 /// QName.
+#[cfg(feature = "alloc")]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Qname {
     /// Prefix part.
@@ -16,6 +297,7 @@ pub struct Qname {
     local: NcnameString,
 }
 
+#[cfg(feature = "alloc")]
 impl Qname {
     /// Creates a new `Qname` from the given optional prefix and local part.
     pub fn new<P, L>(prefix: P, local: L) -> Self
@@ -63,8 +345,30 @@ impl Qname {
     pub fn deconstruct(self) -> (Option<NcnameString>, NcnameString) {
         (self.prefix, self.local)
     }
+
+    /// Creates a new `Qname` with the same prefix and the given local part.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{NcnameStr, QnameStr, NameError};
+    /// # fn run() -> Result<(), NameError> {
+    /// let rect = QnameStr::new("svg:rect")?.to_qname();
+    /// let circle = rect.join(NcnameStr::new("circle")?);
+    /// assert_eq!(circle.to_string(), "svg:circle");
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn join<L: Into<NcnameString>>(&self, local: L) -> Self {
+        Self {
+            prefix: self.prefix.clone(),
+            local: local.into(),
+        }
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl fmt::Display for Qname {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(prefix) = self.prefix.as_ref() {
@@ -94,4 +398,136 @@ impl Qname {
             )
         )
     );
+
+    /// Parses the name and returns it with its span in `source`.
+    ///
+    /// `input` should be a suffix of `source`.
+    pub fn nom_parse_spanned<'a>(
+        input: CompleteStr<'a>,
+        source: &'a str,
+    ) -> nom::IResult<CompleteStr<'a>, Spanned<Self>> {
+        position::nom_parse_spanned(input, source, Self::nom_parse)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strings::Limit;
+
+    #[test]
+    fn validate_qname() {
+        assert!(QnameStr::new("foo").is_ok());
+        assert!(QnameStr::new("foo:bar").is_ok());
+        assert_eq!(QnameStr::new(""), Err(NameError::Empty));
+        assert_eq!(
+            QnameStr::new(":foo"),
+            Err(NameError::InvalidNameChar(0, ':'))
+        );
+        assert_eq!(
+            QnameStr::new("foo:"),
+            Err(NameError::InvalidNameChar(3, ':'))
+        );
+        assert_eq!(
+            QnameStr::new("foo:-bar"),
+            Err(NameError::InvalidNameChar(4, '-'))
+        );
+        assert_eq!(
+            QnameStr::new("a:b:c"),
+            Err(NameError::InvalidNameChar(3, ':'))
+        );
+    }
+
+    #[test]
+    fn validate_qname_with_limits() {
+        let limits = ValidationLimits {
+            max_prefix_bytes: 3,
+            ..ValidationLimits::unlimited()
+        };
+        assert!(QnameStr::new_with_limits("foo:bar", &limits).is_ok());
+        assert_eq!(
+            QnameStr::new_with_limits("fooo:bar", &limits),
+            Err(NameError::LimitExceeded(Limit::PrefixBytes(3)))
+        );
+        assert_eq!(
+            QnameString::new_with_limits("fooo:bar".to_owned(), &limits),
+            Err(NameError::LimitExceeded(Limit::PrefixBytes(3)))
+        );
+    }
+
+    #[test]
+    fn qname_str_to_qname() {
+        let s = QnameStr::new("foo:bar").expect("Should never fail");
+        let q = s.to_qname();
+        assert_eq!(q.prefix().map(NcnameStr::as_str), Some("foo"));
+        assert_eq!(q.local(), "bar");
+        assert_eq!(QnameString::from(&q), *s);
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[cfg(test)]
+mod nom_tests {
+    use super::*;
+    use position::Span;
+
+    #[test]
+    fn parse_spanned_qname() {
+        let source = "<a:b/>";
+        let (rest, name) = QnameStr::nom_parse_spanned(CompleteStr(&source[1..]), source)
+            .expect("Should never fail");
+        assert_eq!(*rest, "/>");
+        assert_eq!(*name.value(), "a:b");
+        assert_eq!(name.span(), Span::new(1, 4));
+
+        let (_, name) =
+            Qname::nom_parse_spanned(CompleteStr(&source[1..]), source).expect("Should never fail");
+        assert_eq!(name.prefix().map(NcnameStr::as_str), Some("a"));
+        assert_eq!(name.position(source).column(), 2);
+    }
+
+    #[test]
+    fn parse_qname_str() {
+        let s = QnameStr::new("foo:bar").expect("Should never fail");
+        assert_eq!(QnameStr::nom_parse("foo:bar>".into()), Ok((">".into(), s)));
+
+        let s = QnameStr::new("foo").expect("Should never fail");
+        assert_eq!(QnameStr::nom_parse("foo:".into()), Ok((":".into(), s)));
+        assert_eq!(QnameStr::nom_parse("foo".into()), Ok(("".into(), s)));
+
+        assert!(QnameStr::nom_parse(":foo".into()).is_err());
+    }
+
+    #[test]
+    fn parse_qname_str_with_limits() {
+        use nom::{Err, ErrorKind};
+
+        let limits = ValidationLimits {
+            max_name_bytes: 3,
+            ..ValidationLimits::unlimited()
+        };
+        let s = QnameStr::new("foo").expect("Should never fail");
+        assert_eq!(
+            QnameStr::nom_parse_with_limits("foo>".into(), &limits),
+            Ok((">".into(), s))
+        );
+        assert_eq!(
+            QnameStr::nom_parse_with_limits("foo:".into(), &limits),
+            Ok((":".into(), s))
+        );
+        assert_eq!(
+            QnameStr::nom_parse_with_limits("foo:b".into(), &limits),
+            Err(Err::Failure(error_position!(
+                "foo:b".into(),
+                ErrorKind::TooLarge
+            )))
+        );
+
+        let limits = ValidationLimits {
+            max_local_bytes: 2,
+            ..ValidationLimits::unlimited()
+        };
+        assert!(QnameStr::nom_parse_with_limits("f:bar".into(), &limits).is_err());
+    }
 }
@@ -0,0 +1,79 @@
+//! `quickcheck` support for name types.
+
+use quickcheck::{Arbitrary, Gen};
+
+use strings::sample::{self, Entropy, NearMiss};
+use strings::NameKind;
+use strings::{NameString, NcnameString, NmtokenString, Qname, QnameStr, QnameString};
+
+impl Entropy for Gen {
+    type Error = ::std::convert::Infallible;
+
+    fn below(&mut self, n: usize) -> Result<usize, Self::Error> {
+        Ok(u32::arbitrary(self) as usize % n)
+    }
+}
+
+/// Unwraps the infallible result.
+fn infallible<T>(res: Result<T, ::std::convert::Infallible>) -> T {
+    match res {
+        Ok(v) => v,
+        Err(e) => match e {},
+    }
+}
+
+/// Implements `Arbitrary` for the owned string type.
+macro_rules! impl_arbitrary_for_string {
+    ($owned:ident, $production:expr) => {
+        impl Arbitrary for $owned {
+            fn arbitrary(g: &mut Gen) -> Self {
+                let s = infallible(sample::name(g, $production));
+                $owned::new(s).unwrap_or_else(|e| {
+                    panic!("Sampled name should be valid as `{}`: {}", $production, e)
+                })
+            }
+        }
+    };
+}
+
+impl_arbitrary_for_string!(NameString, NameKind::Name);
+impl_arbitrary_for_string!(NcnameString, NameKind::Ncname);
+impl_arbitrary_for_string!(NmtokenString, NameKind::Nmtoken);
+impl_arbitrary_for_string!(QnameString, NameKind::Qname);
+
+impl Arbitrary for Qname {
+    fn arbitrary(g: &mut Gen) -> Self {
+        QnameStr::to_qname(&QnameString::arbitrary(g))
+    }
+}
+
+impl Arbitrary for NearMiss {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let production = *g
+            .choose(&[
+                NameKind::Name,
+                NameKind::Ncname,
+                NameKind::Nmtoken,
+                NameKind::Qname,
+            ])
+            .unwrap_or_else(|| unreachable!("Should never fail: the slice is not empty"));
+        infallible(sample::near_miss(g, production))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::QuickCheck;
+
+    use super::*;
+
+    #[test]
+    fn quickcheck_names() {
+        fn prop(name: NameString, near_miss: NearMiss) -> bool {
+            NameKind::Name.validate(name.as_str()).is_ok()
+                && near_miss.production().validate(near_miss.name())
+                    == Err(near_miss.expected_error())
+        }
+        QuickCheck::new().quickcheck(prop as fn(NameString, NearMiss) -> bool);
+    }
+}
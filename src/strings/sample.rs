@@ -0,0 +1,246 @@
+//! Random sampling of names for property testing.
+
+use strings::{NameError, NameKind};
+
+/// Name start characters except `:`.
+const NCNAME_START_RANGES: &[(char, char)] = &[
+    ('A', 'Z'),
+    ('_', '_'),
+    ('a', 'z'),
+    ('\u{C0}', '\u{D6}'),
+    ('\u{D8}', '\u{F6}'),
+    ('\u{F8}', '\u{2FF}'),
+    ('\u{370}', '\u{37D}'),
+    ('\u{37F}', '\u{1FFF}'),
+    ('\u{200C}', '\u{200D}'),
+    ('\u{2070}', '\u{218F}'),
+    ('\u{2C00}', '\u{2FEF}'),
+    ('\u{3001}', '\u{D7FF}'),
+    ('\u{F900}', '\u{FDCF}'),
+    ('\u{FDF0}', '\u{FFFD}'),
+    ('\u{10000}', '\u{EFFFF}'),
+];
+
+/// Colon.
+const COLON_RANGES: &[(char, char)] = &[(':', ':')];
+
+/// Name characters which are not name start characters.
+const NAME_EXTRA_RANGES: &[(char, char)] = &[
+    ('-', '.'),
+    ('0', '9'),
+    ('\u{B7}', '\u{B7}'),
+    ('\u{300}', '\u{36F}'),
+    ('\u{203F}', '\u{2040}'),
+];
+
+/// Characters which are not name characters.
+const INVALID_RANGES: &[(char, char)] = &[
+    ('\u{0}', ','),
+    ('/', '/'),
+    (';', '@'),
+    ('[', '^'),
+    ('`', '`'),
+    ('{', '\u{B6}'),
+    ('\u{B8}', '\u{BF}'),
+    ('\u{D7}', '\u{D7}'),
+    ('\u{F7}', '\u{F7}'),
+    ('\u{37E}', '\u{37E}'),
+    ('\u{2000}', '\u{200B}'),
+    ('\u{200E}', '\u{203E}'),
+    ('\u{2041}', '\u{206F}'),
+    ('\u{2190}', '\u{2BFF}'),
+    ('\u{2FF0}', '\u{3000}'),
+    ('\u{E000}', '\u{F8FF}'),
+    ('\u{FDD0}', '\u{FDEF}'),
+    ('\u{FFFE}', '\u{FFFF}'),
+    ('\u{F0000}', '\u{10FFFF}'),
+];
+
+/// Whitespace characters separating list items.
+#[cfg(feature = "proptest")]
+const WHITESPACE_RANGES: &[(char, char)] =
+    &[('\u{9}', '\u{A}'), ('\u{D}', '\u{D}'), ('\u{20}', '\u{20}')];
+
+/// Maximum number of characters in a sampled name (or a QName part).
+pub(crate) const MAX_NAME_CHARS: usize = 16;
+
+/// Maximum number of items in a sampled list.
+#[cfg(feature = "proptest")]
+pub(crate) const MAX_LIST_ITEMS: usize = 8;
+
+/// Source of randomness.
+pub(crate) trait Entropy {
+    /// Error on exhaustion.
+    type Error;
+
+    /// Returns a number less than `n`.
+    ///
+    /// `n` is not zero.
+    fn below(&mut self, n: usize) -> Result<usize, Self::Error>;
+}
+
+/// Returns the number of characters in the range.
+fn range_len(&(start, end): &(char, char)) -> usize {
+    end as usize - start as usize + 1
+}
+
+/// Samples a character from the union of the given range sets.
+///
+/// A range is chosen uniformly first, so that small ranges such as ASCII
+/// letters are sampled as often as the large ones.
+fn sample_char<E: Entropy>(e: &mut E, sets: &[&[(char, char)]]) -> Result<char, E::Error> {
+    let count = sets.iter().map(|set| set.len()).sum();
+    let range = sets
+        .iter()
+        .flat_map(|set| set.iter())
+        .nth(e.below(count)?)
+        .unwrap_or_else(|| unreachable!("Should never fail: the index is less than the count"));
+    let offset = e.below(range_len(range))?;
+    let c = ::std::char::from_u32(range.0 as u32 + offset as u32);
+    Ok(c.unwrap_or_else(|| unreachable!("Should never fail: the ranges have no surrogates")))
+}
+
+/// Samples characters into the buffer.
+fn push_chars<E: Entropy>(
+    e: &mut E,
+    out: &mut String,
+    start: &[&[(char, char)]],
+    rest: &[&[(char, char)]],
+) -> Result<(), E::Error> {
+    let len = e.below(MAX_NAME_CHARS)? + 1;
+    out.push(sample_char(e, start)?);
+    for _ in 1..len {
+        out.push(sample_char(e, rest)?);
+    }
+    Ok(())
+}
+
+/// Samples a valid name of the production.
+pub(crate) fn name<E: Entropy>(e: &mut E, production: NameKind) -> Result<String, E::Error> {
+    const NCNAME_START: &[&[(char, char)]] = &[NCNAME_START_RANGES];
+    const NCNAME_CHAR: &[&[(char, char)]] = &[NCNAME_START_RANGES, NAME_EXTRA_RANGES];
+    const NAME_START: &[&[(char, char)]] = &[NCNAME_START_RANGES, COLON_RANGES];
+    const NAME_CHAR: &[&[(char, char)]] = &[NCNAME_START_RANGES, COLON_RANGES, NAME_EXTRA_RANGES];
+
+    let mut out = String::new();
+    match production {
+        NameKind::Name => push_chars(e, &mut out, NAME_START, NAME_CHAR)?,
+        NameKind::Ncname => push_chars(e, &mut out, NCNAME_START, NCNAME_CHAR)?,
+        NameKind::Nmtoken => push_chars(e, &mut out, NAME_CHAR, NAME_CHAR)?,
+        NameKind::Qname => {
+            push_chars(e, &mut out, NCNAME_START, NCNAME_CHAR)?;
+            if e.below(2)? == 1 {
+                out.push(':');
+                push_chars(e, &mut out, NCNAME_START, NCNAME_CHAR)?;
+            }
+        },
+    }
+    Ok(out)
+}
+
+/// Samples a whitespace-separated list of valid names of the production.
+#[cfg(feature = "proptest")]
+pub(crate) fn list<E: Entropy>(e: &mut E, production: NameKind) -> Result<String, E::Error> {
+    let mut out = name(e, production)?;
+    for _ in 0..e.below(MAX_LIST_ITEMS)? {
+        for _ in 0..=e.below(2)? {
+            out.push(sample_char(e, &[WHITESPACE_RANGES])?);
+        }
+        out.push_str(&name(e, production)?);
+    }
+    Ok(out)
+}
+
+/// Samples a name with exactly one invalid character.
+pub(crate) fn near_miss<E: Entropy>(e: &mut E, production: NameKind) -> Result<NearMiss, E::Error> {
+    let mut name = name(e, production)?;
+    let index = e.below(name.chars().count() + 1)?;
+    let position = name
+        .char_indices()
+        .nth(index)
+        .map_or(name.len(), |(pos, _)| pos);
+    let invalid = sample_char(e, &[INVALID_RANGES])?;
+    name.insert(position, invalid);
+    Ok(NearMiss {
+        production,
+        name,
+        position,
+        invalid,
+    })
+}
+
+/// Name with exactly one invalid character.
+///
+/// The name is valid except for the invalid character, so validating it
+/// always fails at the known position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NearMiss {
+    /// Production which the name is almost valid as.
+    production: NameKind,
+    /// Name.
+    name: String,
+    /// Byte position of the invalid character.
+    position: usize,
+    /// Invalid character.
+    invalid: char,
+}
+
+impl NearMiss {
+    /// Returns the production which the name is almost valid as.
+    pub fn production(&self) -> NameKind {
+        self.production
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the byte position of the invalid character.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the invalid character.
+    pub fn invalid_char(&self) -> char {
+        self.invalid
+    }
+
+    /// Returns the error which validation of the name results in.
+    pub fn expected_error(&self) -> NameError {
+        NameError::InvalidNameChar(self.position, self.invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::char;
+    use strings::{is_name_char, is_name_start_char};
+
+    #[test]
+    fn char_ranges() {
+        let contains = |sets: &[&[(char, char)]], c: char| {
+            sets.iter()
+                .flat_map(|set| set.iter())
+                .any(|&(start, end)| start <= c && c <= end)
+        };
+        for c in (0..=0x10_FFFF).filter_map(char::from_u32) {
+            assert_eq!(
+                is_name_start_char(c),
+                contains(&[NCNAME_START_RANGES, COLON_RANGES], c),
+                "{:?}",
+                c
+            );
+            assert_eq!(
+                is_name_char(c),
+                contains(&[NCNAME_START_RANGES, COLON_RANGES, NAME_EXTRA_RANGES], c),
+                "{:?}",
+                c
+            );
+            if contains(&[INVALID_RANGES], c) {
+                assert!(!is_name_char(c), "{:?}", c);
+            }
+        }
+    }
+}
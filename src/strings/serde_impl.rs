@@ -0,0 +1,176 @@
+//! `serde` support for name types.
+//!
+//! Names are serialized as strings, and validated on deserialization.
+//! Borrowed names can be deserialized without copy if the deserializer
+//! supports borrowed strings.
+
+use std::borrow::ToOwned;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+
+use strings::{NameError, NameStr, NameString, NcnameStr, NcnameString, NmtokenStr};
+use strings::{NmtokenString, Qname, QnameStr, QnameString};
+
+/// Visitor which validates and converts a string.
+struct StrVisitor<T, F> {
+    /// Expected production.
+    expecting: &'static str,
+    /// Conversion.
+    convert: F,
+    /// Output type.
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T, F> StrVisitor<T, F> {
+    /// Creates a new `StrVisitor`.
+    fn new(expecting: &'static str, convert: F) -> Self {
+        Self {
+            expecting,
+            convert,
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, F> Visitor<'de> for StrVisitor<T, F>
+where
+    F: FnOnce(&str) -> Result<T, NameError>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        (self.convert)(v).map_err(E::custom)
+    }
+}
+
+/// Visitor which validates a borrowed string.
+struct BorrowedStrVisitor<T: ?Sized + 'static, F> {
+    /// Expected production.
+    expecting: &'static str,
+    /// Validation.
+    validate: F,
+    /// Output type.
+    _output: PhantomData<fn() -> &'static T>,
+}
+
+impl<'de, T, F> Visitor<'de> for BorrowedStrVisitor<T, F>
+where
+    T: ?Sized + 'de + 'static,
+    F: FnOnce(&'de str) -> Result<&'de T, NameError>,
+{
+    type Value = &'de T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a borrowed {}", self.expecting)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<&'de T, E> {
+        (self.validate)(v).map_err(E::custom)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` for the string types.
+macro_rules! impl_serde_for_string {
+    ($borrowed:ident, $owned:ident, $expecting:expr) => {
+        impl Serialize for $borrowed {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $owned {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de: 'a, 'a> Deserialize<'de> for &'a $borrowed {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(BorrowedStrVisitor {
+                    expecting: $expecting,
+                    validate: $borrowed::new,
+                    _output: PhantomData,
+                })
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $owned {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(StrVisitor::new($expecting, |s: &str| {
+                    $owned::new(s.to_owned())
+                }))
+            }
+        }
+    };
+}
+
+impl_serde_for_string!(NameStr, NameString, "an XML Name");
+impl_serde_for_string!(NcnameStr, NcnameString, "an XML NCName");
+impl_serde_for_string!(NmtokenStr, NmtokenString, "an XML Nmtoken");
+impl_serde_for_string!(QnameStr, QnameString, "an XML QName");
+
+impl Serialize for Qname {
+    /// Serializes the name as its lexical `prefix:local` form.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Qname {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(StrVisitor::new("an XML QName", |s: &str| {
+            QnameStr::new(s).map(QnameStr::to_qname)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let q: Qname = serde_json::from_str("\"foo:bar\"").expect("Should never fail");
+        assert_eq!(q.prefix().map(NcnameStr::as_str), Some("foo"));
+        assert_eq!(
+            serde_json::to_string(&q).expect("Should never fail"),
+            "\"foo:bar\""
+        );
+
+        let names: Vec<NameString> =
+            serde_json::from_str("[\"a\", \"b:c\"]").expect("Should never fail");
+        assert_eq!(names, ["a", "b:c"]);
+        assert_eq!(
+            serde_json::to_string(&names).expect("Should never fail"),
+            "[\"a\",\"b:c\"]"
+        );
+    }
+
+    #[test]
+    fn deserialize_borrowed() {
+        let source = "\"foo\"";
+        let name: &NcnameStr = serde_json::from_str(source).expect("Should never fail");
+        assert_eq!(name, "foo");
+        assert_eq!(name.as_str().as_ptr(), source[1..].as_ptr());
+
+        assert!(serde_json::from_str::<&NameStr>("\"a\\u0062\"").is_err());
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let err = serde_json::from_str::<NcnameString>("\"a:b\"").expect_err("Should fail");
+        assert!(err
+            .to_string()
+            .starts_with(&NameError::InvalidNameChar(1, ':').to_string()));
+        assert!(serde_json::from_str::<Qname>("\"a:b:c\"").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! `proptest` strategies for names.
+//!
+//! Characters are sampled across all the ranges of the name characters.
+//! The `near_miss_*` strategies generate names with exactly one invalid
+//! character at a known position.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate proptest;
+//! # extern crate xmlop_datatypes;
+//! # use proptest::test_runner::TestRunner;
+//! # use xmlop_datatypes::strings::NameKind;
+//! # use xmlop_datatypes::strings::{strategy, NcnameStr};
+//! # fn main() {
+//! let mut runner = TestRunner::default();
+//! runner
+//!     .run(&strategy::ncname(), |name| {
+//!         assert!(NcnameStr::new(name.as_str()).is_ok());
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! runner
+//!     .run(&strategy::near_miss(NameKind::Ncname), |near_miss| {
+//!         let err = NcnameStr::new(near_miss.name()).unwrap_err();
+//!         assert_eq!(err, near_miss.expected_error());
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! # }
+//! ```
+
+use std::convert::Infallible;
+
+use proptest::collection::vec;
+use proptest::prelude::{any, Strategy};
+
+use strings::sample::{self, Entropy, NearMiss};
+use strings::NameKind;
+use strings::{NameString, NcnameString, NmtokenString, Qname, QnameStr, QnameString};
+
+/// Entropy replaying the sampled numbers.
+///
+/// Replaying numbers instead of using the RNG directly lets proptest shrink
+/// the generated names.
+struct Replay<'a> {
+    /// Numbers.
+    numbers: &'a [u32],
+    /// Index of the next number.
+    next: usize,
+}
+
+impl<'a> Entropy for Replay<'a> {
+    type Error = Infallible;
+
+    fn below(&mut self, n: usize) -> Result<usize, Infallible> {
+        let v = self.numbers.get(self.next).map_or(0, |&v| v as usize);
+        self.next += 1;
+        Ok(v % n)
+    }
+}
+
+/// Returns the strategy which samples with the given function.
+fn sampled<T, F>(f: F) -> impl Strategy<Value = T>
+where
+    T: ::std::fmt::Debug,
+    F: Fn(&mut Replay) -> Result<T, Infallible>,
+{
+    vec(any::<u32>(), 0..512).prop_map(move |numbers| {
+        let mut replay = Replay {
+            numbers: &numbers,
+            next: 0,
+        };
+        match f(&mut replay) {
+            Ok(v) => v,
+            Err(e) => match e {},
+        }
+    })
+}
+
+/// Returns the strategy which generates valid names of the production.
+fn valid(production: NameKind) -> impl Strategy<Value = String> {
+    sampled(move |e| sample::name(e, production))
+}
+
+/// Returns the strategy for `Name`.
+pub fn name() -> impl Strategy<Value = NameString> {
+    valid(NameKind::Name).prop_map(|s| NameString::new(s).expect("Should be valid Name"))
+}
+
+/// Returns the strategy for `NCName`.
+pub fn ncname() -> impl Strategy<Value = NcnameString> {
+    valid(NameKind::Ncname).prop_map(|s| NcnameString::new(s).expect("Should be valid NCName"))
+}
+
+/// Returns the strategy for `Nmtoken`.
+pub fn nmtoken() -> impl Strategy<Value = NmtokenString> {
+    valid(NameKind::Nmtoken).prop_map(|s| NmtokenString::new(s).expect("Should be valid Nmtoken"))
+}
+
+/// Returns the strategy for `QName` strings.
+pub fn qname_string() -> impl Strategy<Value = QnameString> {
+    valid(NameKind::Qname).prop_map(|s| QnameString::new(s).expect("Should be valid QName"))
+}
+
+/// Returns the strategy for [`Qname`].
+pub fn qname() -> impl Strategy<Value = Qname> {
+    qname_string().prop_map(|s| QnameStr::to_qname(&s))
+}
+
+/// Returns the strategy for whitespace-separated lists of `Name` (`Names`).
+pub fn names() -> impl Strategy<Value = String> {
+    sampled(|e| sample::list(e, NameKind::Name))
+}
+
+/// Returns the strategy for whitespace-separated lists of `Nmtoken` (`Nmtokens`).
+pub fn nmtokens() -> impl Strategy<Value = String> {
+    sampled(|e| sample::list(e, NameKind::Nmtoken))
+}
+
+/// Returns the strategy for names of the production with one invalid character.
+pub fn near_miss(production: NameKind) -> impl Strategy<Value = NearMiss> {
+    sampled(move |e| sample::near_miss(e, production))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strings::{parse_names, ValidationLimits};
+
+    proptest! {
+        #[test]
+        fn valid_names(name in name(), qname in qname(), nmtoken in nmtoken()) {
+            prop_assert!(NameKind::Name.validate(name.as_str()).is_ok());
+            prop_assert!(NameKind::Nmtoken.validate(nmtoken.as_str()).is_ok());
+            let s = qname.to_string();
+            prop_assert_eq!(QnameStr::new(&s).map(QnameStr::to_qname), Ok(qname));
+        }
+
+        #[test]
+        fn valid_lists(names in names()) {
+            prop_assert!(parse_names(&names, &ValidationLimits::unlimited()).is_ok());
+        }
+
+        #[test]
+        fn near_misses(
+            name in near_miss(NameKind::Name),
+            ncname in near_miss(NameKind::Ncname),
+            qname in near_miss(NameKind::Qname),
+        ) {
+            for near_miss in &[name, ncname, qname] {
+                prop_assert_eq!(
+                    near_miss.production().validate(near_miss.name()),
+                    Err(near_miss.expected_error())
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Name validation over UTF-16 buffers.
+//!
+//! Positions in errors and scan results are in UTF-16 code units.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use xmlop_datatypes::strings::{utf16, NameError};
+//! let units = "foo:bar baz".encode_utf16().collect::<Vec<_>>();
+//! assert_eq!(utf16::scan_qname(&units), 7);
+//! assert_eq!(utf16::to_qname_string(&units[..7]).unwrap(), "foo:bar");
+//!
+//! assert_eq!(
+//!     utf16::to_name_string(&[0x61, 0xD800]),
+//!     Err(NameError::UnpairedSurrogate(1))
+//! );
+//! ```
+
+use std::char;
+
+use strings::encoded::{self, decode_name};
+use strings::NameKind;
+use strings::{NameError, NameString, NcnameString, NmtokenString, QnameString};
+
+/// Decodes the UTF-16 buffer into characters with their positions.
+fn chars(units: &[u16]) -> impl Iterator<Item = (usize, Result<char, NameError>)> + '_ {
+    let mut pos = 0;
+    char::decode_utf16(units.iter().cloned()).map(move |c| {
+        let start = pos;
+        match c {
+            Ok(c) => {
+                pos += c.len_utf16();
+                (start, Ok(c))
+            },
+            Err(_) => {
+                pos += 1;
+                (start, Err(NameError::UnpairedSurrogate(start)))
+            },
+        }
+    })
+}
+
+/// Validates the UTF-16 buffer as `Name` and converts it to [`NameString`].
+pub fn to_name_string(units: &[u16]) -> Result<NameString, NameError> {
+    let s = decode_name(chars(units), NameKind::Name)?;
+    unsafe {
+        // This is safe because the string is validated as `Name`.
+        Ok(NameString::new_unchecked(s))
+    }
+}
+
+/// Validates the UTF-16 buffer as `NCName` and converts it to [`NcnameString`].
+pub fn to_ncname_string(units: &[u16]) -> Result<NcnameString, NameError> {
+    let s = decode_name(chars(units), NameKind::Ncname)?;
+    unsafe {
+        // This is safe because the string is validated as `NCName`.
+        Ok(NcnameString::new_unchecked(s))
+    }
+}
+
+/// Validates the UTF-16 buffer as `Nmtoken` and converts it to [`NmtokenString`].
+pub fn to_nmtoken_string(units: &[u16]) -> Result<NmtokenString, NameError> {
+    let s = decode_name(chars(units), NameKind::Nmtoken)?;
+    unsafe {
+        // This is safe because the string is validated as `Nmtoken`.
+        Ok(NmtokenString::new_unchecked(s))
+    }
+}
+
+/// Validates the UTF-16 buffer as `QName` and converts it to [`QnameString`].
+pub fn to_qname_string(units: &[u16]) -> Result<QnameString, NameError> {
+    let s = decode_name(chars(units), NameKind::Qname)?;
+    unsafe {
+        // This is safe because the string is validated as `QName`.
+        Ok(QnameString::new_unchecked(s))
+    }
+}
+
+/// Returns the length of the longest prefix which is `Name`, or 0 if none.
+pub fn scan_name(units: &[u16]) -> usize {
+    encoded::scan_name(chars(units), NameKind::Name, units.len())
+}
+
+/// Returns the length of the longest prefix which is `NCName`, or 0 if none.
+pub fn scan_ncname(units: &[u16]) -> usize {
+    encoded::scan_name(chars(units), NameKind::Ncname, units.len())
+}
+
+/// Returns the length of the longest prefix which is `Nmtoken`, or 0 if none.
+pub fn scan_nmtoken(units: &[u16]) -> usize {
+    encoded::scan_name(chars(units), NameKind::Nmtoken, units.len())
+}
+
+/// Returns the length of the longest prefix which is `QName`, or 0 if none.
+pub fn scan_qname(units: &[u16]) -> usize {
+    encoded::scan_name(chars(units), NameKind::Qname, units.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn utf16(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    #[test]
+    fn utf16_names() {
+        // U+10000 is a name start character encoded as a surrogate pair.
+        let units = utf16("\u{10000}a\u{F0000}");
+        assert_eq!(
+            to_name_string(&units),
+            Err(NameError::InvalidNameChar(3, '\u{F0000}'))
+        );
+        assert!(to_name_string(&units[..3]).is_ok());
+        assert_eq!(
+            to_ncname_string(&utf16("a:b")),
+            Err(NameError::InvalidNameChar(1, ':'))
+        );
+        assert_eq!(to_nmtoken_string(&utf16("1a")).unwrap(), "1a");
+        assert_eq!(
+            to_qname_string(&utf16("\u{10000}:")),
+            Err(NameError::InvalidNameChar(2, ':'))
+        );
+        assert_eq!(to_qname_string(&[]), Err(NameError::Empty));
+        assert_eq!(
+            to_name_string(&[0xDC00, 0x61]),
+            Err(NameError::UnpairedSurrogate(0))
+        );
+    }
+
+    #[test]
+    fn utf16_scan() {
+        assert_eq!(scan_name(&utf16("\u{10000}b c")), 3);
+        assert_eq!(scan_name(&utf16("1b")), 0);
+        assert_eq!(scan_nmtoken(&utf16("1b")), 2);
+        assert_eq!(scan_ncname(&utf16("a:b")), 1);
+        assert_eq!(scan_qname(&utf16("a:b:c")), 3);
+        assert_eq!(scan_qname(&utf16("a:1")), 1);
+        assert_eq!(scan_qname(&utf16("a:")), 1);
+        assert_eq!(scan_name(&[0x61, 0xD800, 0x61]), 1);
+    }
+}
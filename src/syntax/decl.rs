@@ -0,0 +1,483 @@
+//! XML and text declarations.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "nom-4")]
+use nom::{types::CompleteStr, IResult};
+
+use strings::EncNameStr;
+#[cfg(feature = "nom-4")]
+use syntax::{eq, whitespace};
+
+/// Declaration error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeclError {
+    /// Invalid version number.
+    InvalidVersionNum,
+    /// Invalid standalone value.
+    InvalidStandalone,
+}
+
+#[cfg(feature = "std")]
+impl error::Error for DeclError {}
+
+impl fmt::Display for DeclError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeclError::InvalidVersionNum => f.write_str("Invalid XML version number"),
+            DeclError::InvalidStandalone => f.write_str("Standalone should be `yes` or `no`"),
+        }
+    }
+}
+
+/// XML version number.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-VersionNum>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VersionNum {
+    /// XML 1.0.
+    V1_0,
+    /// XML 1.1.
+    V1_1,
+    /// Other `1.x` version, accepted only in lenient mode.
+    ///
+    /// This has the minor version number, which is neither 0 nor 1.
+    Other(MinorVersion),
+}
+
+/// Minor version number of [`VersionNum::Other`], which is neither 0 nor 1.
+///
+/// This can only be created by [`VersionNum`], so that `1.0` and `1.1` are
+/// always represented by [`VersionNum::V1_0`] and [`VersionNum::V1_1`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MinorVersion(u32);
+
+impl MinorVersion {
+    /// Returns the minor version number.
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl VersionNum {
+    /// Parses `1.0` or `1.1`.
+    pub fn parse_strict(s: &str) -> Result<Self, DeclError> {
+        match s {
+            "1.0" => Ok(VersionNum::V1_0),
+            "1.1" => Ok(VersionNum::V1_1),
+            _ => Err(DeclError::InvalidVersionNum),
+        }
+    }
+
+    /// Parses any `1.x` version (`'1.' [0-9]+`).
+    ///
+    /// This is how XML 1.0 (fifth edition) processors accept documents with
+    /// future versions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::syntax::VersionNum;
+    /// assert_eq!(VersionNum::parse_lenient("1.0"), Ok(VersionNum::V1_0));
+    /// assert_eq!(VersionNum::parse_lenient("1.5"), Ok(VersionNum::from_minor(5)));
+    /// assert!(VersionNum::parse_lenient("2.0").is_err());
+    /// assert!(VersionNum::parse_strict("1.5").is_err());
+    /// ```
+    pub fn parse_lenient(s: &str) -> Result<Self, DeclError> {
+        let minor = match s.strip_prefix("1.") {
+            Some(minor) if !minor.is_empty() && minor.bytes().all(|b| b.is_ascii_digit()) => minor,
+            _ => return Err(DeclError::InvalidVersionNum),
+        };
+        minor
+            .parse::<u32>()
+            .map(Self::from_minor)
+            .map_err(|_| DeclError::InvalidVersionNum)
+    }
+
+    /// Creates `1.x` version from the minor version number.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::syntax::VersionNum;
+    /// assert_eq!(VersionNum::from_minor(1), VersionNum::V1_1);
+    /// assert_eq!(VersionNum::from_minor(5).minor(), 5);
+    /// ```
+    pub fn from_minor(minor: u32) -> Self {
+        match minor {
+            0 => VersionNum::V1_0,
+            1 => VersionNum::V1_1,
+            minor => VersionNum::Other(MinorVersion(minor)),
+        }
+    }
+
+    /// Returns the minor version number.
+    pub fn minor(self) -> u32 {
+        match self {
+            VersionNum::V1_0 => 0,
+            VersionNum::V1_1 => 1,
+            VersionNum::Other(minor) => minor.get(),
+        }
+    }
+}
+
+impl FromStr for VersionNum {
+    type Err = DeclError;
+
+    /// Parses the version number in strict mode.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_strict(s)
+    }
+}
+
+impl fmt::Display for VersionNum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "1.{}", self.minor())
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl VersionNum {
+    named!(
+        pub nom_parse<CompleteStr<'_>, Self>,
+        alt!(
+            tag!("1.0") => { |_| VersionNum::V1_0 } |
+            tag!("1.1") => { |_| VersionNum::V1_1 }
+        )
+    );
+
+    named!(
+        pub nom_parse_lenient<CompleteStr<'_>, Self>,
+        map_res!(
+            recognize!(pair!(tag!("1."), take_while1!(|c: char| c.is_ascii_digit()))),
+            |s: CompleteStr<'_>| Self::parse_lenient(s.0)
+        )
+    );
+}
+
+/// Standalone document declaration value.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-SDDecl>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Standalone {
+    /// `yes`.
+    Yes,
+    /// `no`.
+    No,
+}
+
+impl Standalone {
+    /// Returns the string representation.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Standalone::Yes => "yes",
+            Standalone::No => "no",
+        }
+    }
+
+    /// Returns `true` for `yes`.
+    pub fn as_bool(self) -> bool {
+        self == Standalone::Yes
+    }
+}
+
+impl From<bool> for Standalone {
+    fn from(v: bool) -> Self {
+        if v {
+            Standalone::Yes
+        } else {
+            Standalone::No
+        }
+    }
+}
+
+impl FromStr for Standalone {
+    type Err = DeclError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yes" => Ok(Standalone::Yes),
+            "no" => Ok(Standalone::No),
+            _ => Err(DeclError::InvalidStandalone),
+        }
+    }
+}
+
+impl fmt::Display for Standalone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl Standalone {
+    named!(
+        pub nom_parse<CompleteStr<'_>, Self>,
+        alt!(
+            tag!("yes") => { |_| Standalone::Yes } |
+            tag!("no") => { |_| Standalone::No }
+        )
+    );
+}
+
+/// XML declaration.
+///
+/// `Display` writes the canonical form, with double quotes and single spaces.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-XMLDecl>.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::strings::EncNameStr;
+/// # use xmlop_datatypes::syntax::{Standalone, VersionNum, XmlDecl};
+/// let decl = XmlDecl {
+///     version: VersionNum::V1_0,
+///     encoding: Some(EncNameStr::new("UTF-8").unwrap()),
+///     standalone: Some(Standalone::Yes),
+/// };
+/// assert_eq!(
+///     decl.to_string(),
+///     "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct XmlDecl<'a> {
+    /// Version.
+    pub version: VersionNum,
+    /// Encoding name.
+    pub encoding: Option<&'a EncNameStr>,
+    /// Standalone document declaration.
+    pub standalone: Option<Standalone>,
+}
+
+impl<'a> fmt::Display for XmlDecl<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<?xml version=\"{}\"", self.version)?;
+        if let Some(encoding) = self.encoding {
+            write!(f, " encoding=\"{}\"", encoding)?;
+        }
+        if let Some(standalone) = self.standalone {
+            write!(f, " standalone=\"{}\"", standalone)?;
+        }
+        f.write_str("?>")
+    }
+}
+
+#[cfg(feature = "nom-4")]
+impl<'a> XmlDecl<'a> {
+    /// Parses an XML declaration with version 1.0 or 1.1.
+    pub fn nom_parse(input: CompleteStr<'a>) -> IResult<CompleteStr<'a>, Self> {
+        xml_decl(input, VersionNum::nom_parse)
+    }
+
+    /// Parses an XML declaration with any `1.x` version.
+    pub fn nom_parse_lenient(input: CompleteStr<'a>) -> IResult<CompleteStr<'a>, Self> {
+        xml_decl(input, VersionNum::nom_parse_lenient)
+    }
+}
+
+/// Text declaration of an external parsed entity.
+///
+/// `Display` writes the canonical form, with double quotes and single spaces.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-TextDecl>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextDecl<'a> {
+    /// Version.
+    pub version: Option<VersionNum>,
+    /// Encoding name.
+    pub encoding: &'a EncNameStr,
+}
+
+impl<'a> fmt::Display for TextDecl<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<?xml")?;
+        if let Some(version) = self.version {
+            write!(f, " version=\"{}\"", version)?;
+        }
+        write!(f, " encoding=\"{}\"?>", self.encoding)
+    }
+}
+
+#[cfg(feature = "nom-4")]
+impl<'a> TextDecl<'a> {
+    /// Parses a text declaration with version 1.0 or 1.1.
+    pub fn nom_parse(input: CompleteStr<'a>) -> IResult<CompleteStr<'a>, Self> {
+        text_decl(input, VersionNum::nom_parse)
+    }
+
+    /// Parses a text declaration with any `1.x` version.
+    pub fn nom_parse_lenient(input: CompleteStr<'a>) -> IResult<CompleteStr<'a>, Self> {
+        text_decl(input, VersionNum::nom_parse_lenient)
+    }
+}
+
+/// Parses the value quoted by either quotation mark.
+#[cfg(feature = "nom-4")]
+fn quoted<'a, O, F>(input: CompleteStr<'a>, parser: F) -> IResult<CompleteStr<'a>, O>
+where
+    F: Fn(CompleteStr<'a>) -> IResult<CompleteStr<'a>, O>,
+{
+    alt!(
+        input,
+        delimited!(char!('"'), call!(&parser), char!('"'))
+            | delimited!(char!('\''), call!(&parser), char!('\''))
+    )
+}
+
+/// Parses a pseudo-attribute in a declaration (`S name Eq quoted-value`).
+#[cfg(feature = "nom-4")]
+fn decl_attr<'a, O, F>(
+    input: CompleteStr<'a>,
+    name: &'static str,
+    parser: F,
+) -> IResult<CompleteStr<'a>, O>
+where
+    F: Fn(CompleteStr<'a>) -> IResult<CompleteStr<'a>, O>,
+{
+    preceded!(
+        input,
+        tuple!(whitespace, tag!(name), eq),
+        call!(quoted, &parser)
+    )
+}
+
+/// Parses an XML declaration with the given version parser.
+#[cfg(feature = "nom-4")]
+fn xml_decl<'a, F>(input: CompleteStr<'a>, version: F) -> IResult<CompleteStr<'a>, XmlDecl<'a>>
+where
+    F: Fn(CompleteStr<'a>) -> IResult<CompleteStr<'a>, VersionNum>,
+{
+    do_parse!(
+        input,
+        tag!("<?xml")
+            >> version: call!(decl_attr, "version", &version)
+            >> encoding: opt!(complete!(call!(decl_attr, "encoding", EncNameStr::nom_parse)))
+            >> standalone: opt!(complete!(call!(decl_attr, "standalone", Standalone::nom_parse)))
+            >> opt!(whitespace)
+            >> tag!("?>")
+            >> (XmlDecl {
+                version,
+                encoding,
+                standalone,
+            })
+    )
+}
+
+/// Parses a text declaration with the given version parser.
+#[cfg(feature = "nom-4")]
+fn text_decl<'a, F>(input: CompleteStr<'a>, version: F) -> IResult<CompleteStr<'a>, TextDecl<'a>>
+where
+    F: Fn(CompleteStr<'a>) -> IResult<CompleteStr<'a>, VersionNum>,
+{
+    do_parse!(
+        input,
+        tag!("<?xml")
+            >> version: opt!(complete!(call!(decl_attr, "version", &version)))
+            >> encoding: call!(decl_attr, "encoding", EncNameStr::nom_parse)
+            >> opt!(whitespace)
+            >> tag!("?>")
+            >> (TextDecl { version, encoding })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_num() {
+        assert_eq!("1.1".parse(), Ok(VersionNum::V1_1));
+        assert_eq!(
+            "1.01".parse::<VersionNum>(),
+            Err(DeclError::InvalidVersionNum)
+        );
+        assert_eq!(VersionNum::parse_lenient("1.01"), Ok(VersionNum::V1_1));
+        assert_eq!(
+            VersionNum::parse_lenient("1."),
+            Err(DeclError::InvalidVersionNum)
+        );
+        assert_eq!(
+            VersionNum::parse_lenient("1.99999999999"),
+            Err(DeclError::InvalidVersionNum)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decl_display() {
+        use std::string::ToString;
+
+        assert_eq!(VersionNum::from_minor(2).to_string(), "1.2");
+        let encoding = EncNameStr::new("Shift_JIS").expect("Should never fail");
+        let decl = TextDecl {
+            version: None,
+            encoding,
+        };
+        assert_eq!(decl.to_string(), "<?xml encoding=\"Shift_JIS\"?>");
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[cfg(test)]
+mod nom_tests {
+    use super::*;
+
+    #[test]
+    fn parse_xml_decl() {
+        let encoding = EncNameStr::new("utf-8").expect("Should never fail");
+        let decl = XmlDecl {
+            version: VersionNum::V1_0,
+            encoding: Some(encoding),
+            standalone: Some(Standalone::No),
+        };
+        assert_eq!(
+            XmlDecl::nom_parse(
+                "<?xml version='1.0' encoding=\"utf-8\" standalone='no' ?><a/>".into()
+            ),
+            Ok(("<a/>".into(), decl))
+        );
+        let decl = XmlDecl {
+            version: VersionNum::V1_1,
+            encoding: None,
+            standalone: None,
+        };
+        assert_eq!(
+            XmlDecl::nom_parse("<?xml version=\"1.1\"?>".into()),
+            Ok(("".into(), decl))
+        );
+
+        assert!(XmlDecl::nom_parse("<?xml?>".into()).is_err());
+        assert!(XmlDecl::nom_parse("<?xml version='1.0\"?>".into()).is_err());
+        assert!(XmlDecl::nom_parse("<?xml version='1.2'?>".into()).is_err());
+        assert!(XmlDecl::nom_parse("<?xml version='1.0' standalone='maybe'?>".into()).is_err());
+        assert!(XmlDecl::nom_parse(
+            "<?xml version='1.0' standalone='no' encoding='utf-8'?>".into()
+        )
+        .is_err());
+        assert_eq!(
+            XmlDecl::nom_parse_lenient("<?xml version='1.2'?>".into()).map(|(_, d)| d.version),
+            Ok(VersionNum::from_minor(2))
+        );
+    }
+
+    #[test]
+    fn parse_text_decl() {
+        let encoding = EncNameStr::new("EUC-JP").expect("Should never fail");
+        let decl = TextDecl {
+            version: None,
+            encoding,
+        };
+        assert_eq!(
+            TextDecl::nom_parse("<?xml encoding='EUC-JP'?>".into()),
+            Ok(("".into(), decl))
+        );
+        assert!(TextDecl::nom_parse("<?xml version='1.0'?>".into()).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Quoted literals.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+
+#[cfg(feature = "nom-4")]
+use nom::types::CompleteStr;
+
+/// Quotation mark of a literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Quote {
+    /// Single quote (`'`).
+    Single,
+    /// Double quote (`"`).
+    Double,
+}
+
+impl Quote {
+    /// Returns the quotation mark as a character.
+    pub fn as_char(self) -> char {
+        match self {
+            Quote::Single => '\'',
+            Quote::Double => '"',
+        }
+    }
+
+    /// Returns the quotation mark as a string slice.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Quote::Single => "'",
+            Quote::Double => "\"",
+        }
+    }
+}
+
+/// Quoted literal error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LiteralError {
+    /// The value contains the quotation mark at the given byte position.
+    ContainsQuote(usize, Quote),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for LiteralError {}
+
+impl fmt::Display for LiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LiteralError::ContainsQuote(pos, quote) => write!(
+                f,
+                "Quoted literal contains its quotation mark at byte position {}: {:?}",
+                pos,
+                quote.as_char()
+            ),
+        }
+    }
+}
+
+/// Single- or double-quoted literal.
+///
+/// The value never contains the quotation mark used to delimit it.
+/// Note that the value is stored as is: references are not expanded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QuotedLiteral<'a> {
+    /// Quotation mark.
+    quote: Quote,
+    /// Value without quotation marks.
+    value: &'a str,
+}
+
+impl<'a> QuotedLiteral<'a> {
+    /// Creates a new `QuotedLiteral`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::syntax::{LiteralError, Quote, QuotedLiteral};
+    /// # fn run() -> Result<(), LiteralError> {
+    /// let lit = QuotedLiteral::new(Quote::Single, "say \"hello\"")?;
+    /// assert_eq!(lit.to_string(), "'say \"hello\"'");
+    ///
+    /// assert!(QuotedLiteral::new(Quote::Double, "say \"hello\"").is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(quote: Quote, value: &'a str) -> Result<Self, LiteralError> {
+        if let Some(pos) = value.find(quote.as_char()) {
+            return Err(LiteralError::ContainsQuote(pos, quote));
+        }
+        Ok(Self { quote, value })
+    }
+
+    /// Returns the quotation mark.
+    pub fn quote(&self) -> Quote {
+        self.quote
+    }
+
+    /// Returns the value without quotation marks.
+    pub fn value(&self) -> &'a str {
+        self.value
+    }
+}
+
+impl<'a> fmt::Display for QuotedLiteral<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let quote = self.quote.as_str();
+        f.write_str(quote)?;
+        f.write_str(self.value)?;
+        f.write_str(quote)
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl<'a> QuotedLiteral<'a> {
+    named!(
+        pub nom_parse<CompleteStr<'a>, Self>,
+        alt!(
+            delimited!(char!('"'), take_till!(|c| c == '"'), char!('"')) => {
+                |s: CompleteStr<'a>| Self { quote: Quote::Double, value: s.0 }
+            } |
+            delimited!(char!('\''), take_till!(|c| c == '\''), char!('\'')) => {
+                |s: CompleteStr<'a>| Self { quote: Quote::Single, value: s.0 }
+            }
+        )
+    );
+}
+
+#[cfg(feature = "nom-4")]
+#[cfg(test)]
+mod nom_tests {
+    use nom::{Err, ErrorKind};
+
+    use super::*;
+
+    #[test]
+    fn parse_quoted_literal() {
+        let res = QuotedLiteral::nom_parse("\"it's\" rest".into());
+        let lit = QuotedLiteral::new(Quote::Double, "it's").expect("Should never fail");
+        assert_eq!(res, Ok((" rest".into(), lit)));
+
+        let res = QuotedLiteral::nom_parse("'a \"b\"'".into());
+        let lit = QuotedLiteral::new(Quote::Single, "a \"b\"").expect("Should never fail");
+        assert_eq!(res, Ok(("".into(), lit)));
+
+        let res = QuotedLiteral::nom_parse("'unterminated".into());
+        assert!(res.is_err());
+
+        let res = QuotedLiteral::nom_parse("bare".into());
+        assert_eq!(
+            res,
+            Err(Err::Error(error_position!("bare".into(), ErrorKind::Alt)))
+        );
+    }
+}
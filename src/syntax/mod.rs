@@ -0,0 +1,20 @@
+//! Lexical primitives of XML.
+
+pub use self::decl::{DeclError, MinorVersion, Standalone, TextDecl, VersionNum, XmlDecl};
+pub use self::literal::{LiteralError, Quote, QuotedLiteral};
+pub use self::pi::{PiError, ProcessingInstruction};
+#[cfg(feature = "alloc")]
+pub use self::pi::{PseudoAttributeError, PseudoAttributeErrorKind, PseudoAttributes};
+#[cfg(feature = "alloc")]
+pub(crate) use self::reference::ReferenceParseError;
+pub use self::reference::{CharRef, CharRefError, CharRefRadix};
+pub use self::reference::{EntityRef, PeReference, Reference};
+#[cfg(feature = "nom-4")]
+pub use self::whitespace::{eq, whitespace};
+
+mod decl;
+mod literal;
+mod pi;
+mod reference;
+#[cfg(feature = "nom-4")]
+mod whitespace;
@@ -0,0 +1,443 @@
+//! Processing instructions and pseudo-attributes.
+
+#[cfg(feature = "alloc")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+
+#[cfg(feature = "nom-4")]
+use nom::types::CompleteStr;
+
+#[cfg(feature = "alloc")]
+use escape::{unescape, UnescapeError, UnescapeErrorKind};
+#[cfg(feature = "alloc")]
+use strings::{is_name_char, is_whitespace_char, NameStr};
+use strings::{is_xml_char, PiTargetStr};
+#[cfg(feature = "nom-4")]
+use syntax::whitespace;
+
+/// Processing instruction error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PiError {
+    /// The data has a character which is not XML `Char`.
+    InvalidChar(usize, char),
+    /// The data contains `?>` at the given byte position.
+    ContainsPiEnd(usize),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for PiError {}
+
+impl fmt::Display for PiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PiError::InvalidChar(pos, c) => {
+                write!(f, "Invalid character at byte position {}: {:?}", pos, c)
+            },
+            PiError::ContainsPiEnd(pos) => write!(
+                f,
+                "Processing instruction data should not contain `?>` (byte position {})",
+                pos
+            ),
+        }
+    }
+}
+
+/// Processing instruction.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-PI>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProcessingInstruction<'a> {
+    /// Target.
+    target: &'a PiTargetStr,
+    /// Data.
+    data: Option<&'a str>,
+}
+
+impl<'a> ProcessingInstruction<'a> {
+    /// Creates a new `ProcessingInstruction`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::PiTargetStr;
+    /// # use xmlop_datatypes::syntax::{PiError, ProcessingInstruction};
+    /// # fn run() -> Result<(), PiError> {
+    /// let target = PiTargetStr::new("xml-stylesheet").expect("Should never fail");
+    /// let pi = ProcessingInstruction::new(target, Some("href=\"style.css\""))?;
+    /// assert_eq!(pi.to_string(), "<?xml-stylesheet href=\"style.css\"?>");
+    ///
+    /// assert_eq!(
+    ///     ProcessingInstruction::new(target, Some("a?>b")),
+    ///     Err(PiError::ContainsPiEnd(1))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(target: &'a PiTargetStr, data: Option<&'a str>) -> Result<Self, PiError> {
+        if let Some(data) = data {
+            if let Some((pos, c)) = data.char_indices().find(|&(_, c)| !is_xml_char(c)) {
+                return Err(PiError::InvalidChar(pos, c));
+            }
+            if let Some(pos) = data.find("?>") {
+                return Err(PiError::ContainsPiEnd(pos));
+            }
+        }
+        Ok(Self { target, data })
+    }
+
+    /// Returns the target.
+    pub fn target(&self) -> &'a PiTargetStr {
+        self.target
+    }
+
+    /// Returns the data.
+    pub fn data(&self) -> Option<&'a str> {
+        self.data
+    }
+
+    /// Returns an iterator of pseudo-attributes in the data.
+    #[cfg(feature = "alloc")]
+    pub fn pseudo_attributes(&self) -> PseudoAttributes<'a> {
+        PseudoAttributes::new(self.data.unwrap_or(""))
+    }
+}
+
+impl<'a> fmt::Display for ProcessingInstruction<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.data {
+            Some(data) => write!(f, "<?{} {}?>", self.target, data),
+            None => write!(f, "<?{}?>", self.target),
+        }
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl<'a> ProcessingInstruction<'a> {
+    named!(
+        pub nom_parse<CompleteStr<'a>, Self>,
+        map_opt!(
+            preceded!(
+                tag!("<?"),
+                pair!(
+                    PiTargetStr::nom_parse,
+                    alt!(
+                        tag!("?>") => { |_| None } |
+                        delimited!(whitespace, take_until!("?>"), tag!("?>")) => {
+                            |s: CompleteStr<'a>| Some(s.0)
+                        }
+                    )
+                )
+            ),
+            |(target, data)| Self::new(target, data).ok()
+        )
+    );
+}
+
+/// Kind of a pseudo-attribute error.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PseudoAttributeErrorKind {
+    /// Pseudo-attributes are not separated by whitespaces.
+    ExpectedWhitespace,
+    /// Expected a name.
+    ExpectedName,
+    /// Expected `=`.
+    ExpectedEq,
+    /// Expected a quotation mark.
+    ExpectedQuote,
+    /// The value has no closing quotation mark.
+    UnterminatedValue,
+    /// The value contains `<`.
+    LtInValue,
+    /// The value has an invalid reference.
+    InvalidReference(UnescapeErrorKind),
+}
+
+/// Pseudo-attribute error.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PseudoAttributeError {
+    /// Error kind.
+    kind: PseudoAttributeErrorKind,
+    /// Byte position of the error in the data.
+    position: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl PseudoAttributeError {
+    /// Creates a new `PseudoAttributeError`.
+    fn new(kind: PseudoAttributeErrorKind, position: usize) -> Self {
+        Self { kind, position }
+    }
+
+    /// Returns the error kind.
+    pub fn kind(&self) -> &PseudoAttributeErrorKind {
+        &self.kind
+    }
+
+    /// Returns the byte position of the error in the data.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for PseudoAttributeError {}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for PseudoAttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let expected = match &self.kind {
+            PseudoAttributeErrorKind::ExpectedWhitespace => "whitespace",
+            PseudoAttributeErrorKind::ExpectedName => "pseudo-attribute name",
+            PseudoAttributeErrorKind::ExpectedEq => "`=`",
+            PseudoAttributeErrorKind::ExpectedQuote => "quotation mark",
+            PseudoAttributeErrorKind::UnterminatedValue => {
+                return write!(
+                    f,
+                    "Unterminated pseudo-attribute value at byte position {}",
+                    self.position
+                );
+            },
+            PseudoAttributeErrorKind::LtInValue => {
+                return write!(
+                    f,
+                    "Pseudo-attribute value should not contain `<` (byte position {})",
+                    self.position
+                );
+            },
+            PseudoAttributeErrorKind::InvalidReference(kind) => {
+                return UnescapeError::new(kind.clone(), self.position).fmt(f);
+            },
+        };
+        write!(
+            f,
+            "Expected {} at byte position {}",
+            expected, self.position
+        )
+    }
+}
+
+/// Iterator of pseudo-attributes in processing instruction data.
+///
+/// Values are unescaped: character references and predefined entity references
+/// are expanded.
+/// The iterator stops after an error.
+///
+/// See <https://www.w3.org/TR/xml-stylesheet/#NT-PseudoAtts>.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::syntax::PseudoAttributes;
+/// let data = "href='style.css' title=\"A &amp; B\"";
+/// let attrs = PseudoAttributes::new(data)
+///     .map(|res| res.map(|(name, value)| (name.as_str(), value.into_owned())))
+///     .collect::<Result<Vec<_>, _>>();
+/// assert_eq!(
+///     attrs,
+///     Ok(vec![
+///         ("href", "style.css".to_owned()),
+///         ("title", "A & B".to_owned()),
+///     ])
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct PseudoAttributes<'a> {
+    /// Data.
+    data: &'a str,
+    /// Current byte position.
+    pos: usize,
+    /// Whether the iteration is finished.
+    finished: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> PseudoAttributes<'a> {
+    /// Creates a new `PseudoAttributes` iterator over the given data.
+    pub fn new(data: &'a str) -> Self {
+        Self {
+            data,
+            pos: 0,
+            finished: false,
+        }
+    }
+
+    /// Reads the next pseudo-attribute.
+    fn read(&mut self) -> Result<Option<(&'a NameStr, Cow<'a, str>)>, PseudoAttributeError> {
+        use self::PseudoAttributeErrorKind as Kind;
+
+        let data = self.data;
+        let ws_len = self.skip_whitespace();
+        if self.pos == data.len() {
+            return Ok(None);
+        }
+        if ws_len == 0 && self.pos != 0 {
+            return Err(PseudoAttributeError::new(
+                Kind::ExpectedWhitespace,
+                self.pos,
+            ));
+        }
+
+        let rest = &data[self.pos..];
+        let name_len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        let name = NameStr::new(&rest[..name_len])
+            .map_err(|_| PseudoAttributeError::new(Kind::ExpectedName, self.pos))?;
+        self.pos += name_len;
+
+        self.skip_whitespace();
+        if !data[self.pos..].starts_with('=') {
+            return Err(PseudoAttributeError::new(Kind::ExpectedEq, self.pos));
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+
+        let quote = match data[self.pos..].chars().next() {
+            Some(c @ '"') | Some(c @ '\'') => c,
+            _ => return Err(PseudoAttributeError::new(Kind::ExpectedQuote, self.pos)),
+        };
+        let value_start = self.pos + 1;
+        let value_len = data[value_start..]
+            .find(quote)
+            .ok_or_else(|| PseudoAttributeError::new(Kind::UnterminatedValue, self.pos))?;
+        let raw = &data[value_start..(value_start + value_len)];
+        if let Some(pos) = raw.find('<') {
+            return Err(PseudoAttributeError::new(
+                Kind::LtInValue,
+                value_start + pos,
+            ));
+        }
+        let value = unescape(raw).map_err(|e| {
+            PseudoAttributeError::new(
+                Kind::InvalidReference(e.kind().clone()),
+                value_start + e.position(),
+            )
+        })?;
+        self.pos = value_start + value_len + 1;
+
+        Ok(Some((name, value)))
+    }
+
+    /// Skips whitespaces and returns the skipped length.
+    fn skip_whitespace(&mut self) -> usize {
+        let rest = &self.data[self.pos..];
+        let len = rest.len() - rest.trim_start_matches(is_whitespace_char).len();
+        self.pos += len;
+        len
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Iterator for PseudoAttributes<'a> {
+    type Item = Result<(&'a NameStr, Cow<'a, str>), PseudoAttributeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let res = self.read().transpose();
+        if !matches!(res, Some(Ok(_))) {
+            self.finished = true;
+        }
+        res
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn collect(data: &str) -> Result<Vec<(String, String)>, PseudoAttributeError> {
+        PseudoAttributes::new(data)
+            .map(|res| res.map(|(name, value)| (name.as_str().to_owned(), value.into_owned())))
+            .collect()
+    }
+
+    #[test]
+    fn parse_pseudo_attributes() {
+        assert_eq!(collect(""), Ok(vec![]));
+        assert_eq!(collect("  "), Ok(vec![]));
+        assert_eq!(
+            collect(" type = \"text/xsl\"\thref='a.xsl&#x3F;v=1' "),
+            Ok(vec![
+                ("type".to_owned(), "text/xsl".to_owned()),
+                ("href".to_owned(), "a.xsl?v=1".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn pseudo_attribute_errors() {
+        let kind = |data| collect(data).map_err(|e| (e.kind().clone(), e.position()));
+        assert_eq!(
+            kind("a='1'b='2'"),
+            Err((PseudoAttributeErrorKind::ExpectedWhitespace, 5))
+        );
+        assert_eq!(
+            kind("1='1'"),
+            Err((PseudoAttributeErrorKind::ExpectedName, 0))
+        );
+        assert_eq!(
+            kind("a '1'"),
+            Err((PseudoAttributeErrorKind::ExpectedEq, 2))
+        );
+        assert_eq!(
+            kind("a=1"),
+            Err((PseudoAttributeErrorKind::ExpectedQuote, 2))
+        );
+        assert_eq!(
+            kind("a=\"1"),
+            Err((PseudoAttributeErrorKind::UnterminatedValue, 2))
+        );
+        assert_eq!(kind("a='<'"), Err((PseudoAttributeErrorKind::LtInValue, 3)));
+        assert!(matches!(
+            kind("a='&foo;'"),
+            Err((PseudoAttributeErrorKind::InvalidReference(_), 3))
+        ));
+    }
+
+    #[test]
+    fn pi_pseudo_attributes() {
+        let target = PiTargetStr::new("xml-model").expect("Should never fail");
+        let pi =
+            ProcessingInstruction::new(target, Some("href='a.rng'")).expect("Should never fail");
+        assert_eq!(pi.pseudo_attributes().count(), 1);
+        let pi = ProcessingInstruction::new(target, None).expect("Should never fail");
+        assert_eq!(pi.pseudo_attributes().count(), 0);
+        assert_eq!(pi.to_string(), "<?xml-model?>");
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[cfg(test)]
+mod nom_tests {
+    use super::*;
+
+    #[test]
+    fn parse_pi() {
+        let target = PiTargetStr::new("xml-stylesheet").expect("Should never fail");
+        let pi =
+            ProcessingInstruction::new(target, Some("href='a.css' ")).expect("Should never fail");
+        assert_eq!(
+            ProcessingInstruction::nom_parse("<?xml-stylesheet href='a.css' ?>rest".into()),
+            Ok(("rest".into(), pi))
+        );
+        let pi = ProcessingInstruction::new(target, None).expect("Should never fail");
+        assert_eq!(
+            ProcessingInstruction::nom_parse("<?xml-stylesheet?>".into()),
+            Ok(("".into(), pi))
+        );
+        assert!(ProcessingInstruction::nom_parse("<?xml version='1.0'?>".into()).is_err());
+        assert!(ProcessingInstruction::nom_parse("<?foo?bar?>".into()).is_err());
+    }
+}
@@ -0,0 +1,420 @@
+//! Character and entity references.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+
+#[cfg(feature = "nom-4")]
+use nom::types::CompleteStr;
+
+#[cfg(feature = "alloc")]
+use strings::is_name_char;
+use strings::{is_xml_char, NameStr};
+
+/// Character reference error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CharRefError {
+    /// The referenced code point is not an XML `Char`.
+    InvalidChar(u32),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for CharRefError {}
+
+impl fmt::Display for CharRefError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CharRefError::InvalidChar(code) => write!(
+                f,
+                "Character reference refers to non-`Char` code point U+{:04X}",
+                code
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Error of parsing a reference without `nom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReferenceParseError {
+    /// `&` without terminating `;`.
+    Unterminated,
+    /// Reference is syntactically invalid.
+    Invalid,
+    /// Character reference refers to a non-`Char` code point.
+    InvalidChar(CharRefError),
+}
+
+/// Radix of a character reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CharRefRadix {
+    /// Decimal (`&#N;`).
+    Decimal,
+    /// Hexadecimal (`&#xH;`).
+    Hexadecimal,
+}
+
+impl CharRefRadix {
+    /// Returns the radix as a number.
+    pub fn as_u32(self) -> u32 {
+        match self {
+            CharRefRadix::Decimal => 10,
+            CharRefRadix::Hexadecimal => 16,
+        }
+    }
+}
+
+/// Character reference (`&#N;` or `&#xH;`).
+///
+/// The referenced character is always an XML `Char`.
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CharRef>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CharRef {
+    /// Referenced character.
+    value: char,
+    /// Radix.
+    radix: CharRefRadix,
+}
+
+impl CharRef {
+    /// Creates a new `CharRef`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::syntax::{CharRef, CharRefError, CharRefRadix};
+    /// # fn run() -> Result<(), CharRefError> {
+    /// let r = CharRef::new('<', CharRefRadix::Hexadecimal)?;
+    /// assert_eq!(r.to_string(), "&#x3C;");
+    ///
+    /// assert!(CharRef::new('\u{0}', CharRefRadix::Decimal).is_err());
+    /// # Ok(())
+    /// # }
+    /// # run().expect("Should never fail");
+    /// ```
+    pub fn new(value: char, radix: CharRefRadix) -> Result<Self, CharRefError> {
+        if !is_xml_char(value) {
+            return Err(CharRefError::InvalidChar(value as u32));
+        }
+        Ok(Self { value, radix })
+    }
+
+    /// Creates a new `CharRef` from the given code point.
+    pub fn from_u32(code: u32, radix: CharRefRadix) -> Result<Self, CharRefError> {
+        let value = ::std::char::from_u32(code).ok_or(CharRefError::InvalidChar(code))?;
+        Self::new(value, radix)
+    }
+
+    /// Returns the referenced character.
+    pub fn value(&self) -> char {
+        self.value
+    }
+
+    /// Returns the radix.
+    pub fn radix(&self) -> CharRefRadix {
+        self.radix
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CharRef {
+    /// Creates a new `CharRef` from the digits between `&#` or `&#x` and `;`.
+    pub(crate) fn from_digits(
+        digits: &str,
+        radix: CharRefRadix,
+    ) -> Result<Self, ReferenceParseError> {
+        let valid = !digits.is_empty()
+            && digits.chars().all(|c| match radix {
+                CharRefRadix::Decimal => c.is_ascii_digit(),
+                CharRefRadix::Hexadecimal => c.is_ascii_hexdigit(),
+            });
+        if !valid {
+            return Err(ReferenceParseError::Invalid);
+        }
+        // Too large code points are reported as `u32::MAX`.
+        let code = u32::from_str_radix(digits, radix.as_u32())
+            .map_err(|_| ReferenceParseError::InvalidChar(CharRefError::InvalidChar(u32::MAX)))?;
+        Self::from_u32(code, radix).map_err(ReferenceParseError::InvalidChar)
+    }
+}
+
+impl fmt::Display for CharRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.radix {
+            CharRefRadix::Decimal => write!(f, "&#{};", self.value as u32),
+            CharRefRadix::Hexadecimal => write!(f, "&#x{:X};", self.value as u32),
+        }
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl CharRef {
+    named!(
+        pub nom_parse<CompleteStr<'_>, Self>,
+        map_opt!(
+            alt!(
+                delimited!(tag!("&#x"), take_while!(is_name_char), char!(';')) => {
+                    |s| (s, CharRefRadix::Hexadecimal)
+                } |
+                delimited!(tag!("&#"), take_while!(is_name_char), char!(';')) => {
+                    |s| (s, CharRefRadix::Decimal)
+                }
+            ),
+            |(digits, radix): (CompleteStr, CharRefRadix)| Self::from_digits(*digits, radix).ok()
+        )
+    );
+}
+
+/// Entity reference (`&Name;`).
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-EntityRef>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntityRef<'a> {
+    /// Name of the referenced entity.
+    name: &'a NameStr,
+}
+
+impl<'a> EntityRef<'a> {
+    /// Creates a new `EntityRef`.
+    pub fn new(name: &'a NameStr) -> Self {
+        Self { name }
+    }
+
+    /// Returns the name of the referenced entity.
+    pub fn name(&self) -> &'a NameStr {
+        self.name
+    }
+}
+
+impl<'a> fmt::Display for EntityRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "&{};", self.name)
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl<'a> EntityRef<'a> {
+    named!(
+        pub nom_parse<CompleteStr<'a>, Self>,
+        map!(
+            delimited!(char!('&'), NameStr::nom_parse, char!(';')),
+            Self::new
+        )
+    );
+}
+
+/// Parameter-entity reference (`%Name;`).
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-PEReference>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PeReference<'a> {
+    /// Name of the referenced parameter entity.
+    name: &'a NameStr,
+}
+
+impl<'a> PeReference<'a> {
+    /// Creates a new `PeReference`.
+    pub fn new(name: &'a NameStr) -> Self {
+        Self { name }
+    }
+
+    /// Returns the name of the referenced parameter entity.
+    pub fn name(&self) -> &'a NameStr {
+        self.name
+    }
+}
+
+impl<'a> fmt::Display for PeReference<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "%{};", self.name)
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl<'a> PeReference<'a> {
+    named!(
+        pub nom_parse<CompleteStr<'a>, Self>,
+        map!(
+            delimited!(char!('%'), NameStr::nom_parse, char!(';')),
+            Self::new
+        )
+    );
+}
+
+/// Reference (entity reference or character reference).
+///
+/// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-Reference>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Reference<'a> {
+    /// Entity reference.
+    Entity(EntityRef<'a>),
+    /// Character reference.
+    Char(CharRef),
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Reference<'a> {
+    /// Parses the reference at the beginning of the given string.
+    ///
+    /// The string should start with `&`.
+    /// Returns the reference and its length in bytes.
+    ///
+    /// Only the `Name` characters (after `#` or `#x` for character references)
+    /// are scanned for the terminating `;`, so that the search does not run
+    /// over the rest of the input.
+    pub(crate) fn parse_prefix(s: &'a str) -> Result<(Self, usize), ReferenceParseError> {
+        debug_assert!(s.starts_with('&'));
+        let body_start = if s.starts_with("&#x") {
+            3
+        } else if s.starts_with("&#") {
+            2
+        } else {
+            1
+        };
+        let end = s[body_start..]
+            .char_indices()
+            .find(|&(_, c)| !is_name_char(c))
+            .map(|(len, c)| (body_start + len, c));
+        let end = match end {
+            Some((end, ';')) => end,
+            _ => return Err(ReferenceParseError::Unterminated),
+        };
+        let body = &s[1..end];
+        let reference = if let Some(digits) = body.strip_prefix("#x") {
+            Reference::Char(CharRef::from_digits(digits, CharRefRadix::Hexadecimal)?)
+        } else if let Some(digits) = body.strip_prefix('#') {
+            Reference::Char(CharRef::from_digits(digits, CharRefRadix::Decimal)?)
+        } else {
+            let name = NameStr::new(body).map_err(|_| ReferenceParseError::Invalid)?;
+            Reference::Entity(EntityRef::new(name))
+        };
+        Ok((reference, end + 1))
+    }
+}
+
+impl<'a> fmt::Display for Reference<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reference::Entity(r) => r.fmt(f),
+            Reference::Char(r) => r.fmt(f),
+        }
+    }
+}
+
+impl<'a> From<EntityRef<'a>> for Reference<'a> {
+    fn from(r: EntityRef<'a>) -> Self {
+        Reference::Entity(r)
+    }
+}
+
+impl<'a> From<CharRef> for Reference<'a> {
+    fn from(r: CharRef) -> Self {
+        Reference::Char(r)
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[allow(missing_docs)]
+impl<'a> Reference<'a> {
+    named!(
+        pub nom_parse<CompleteStr<'a>, Self>,
+        alt!(
+            map!(CharRef::nom_parse, Reference::Char) |
+            map!(EntityRef::nom_parse, Reference::Entity)
+        )
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_prefix() {
+        let name = NameStr::new("amp").expect("Should never fail");
+        assert_eq!(
+            Reference::parse_prefix("&amp;rest"),
+            Ok((Reference::Entity(EntityRef::new(name)), 5))
+        );
+        let r = CharRef::new('<', CharRefRadix::Hexadecimal).expect("Should never fail");
+        assert_eq!(
+            Reference::parse_prefix("&#x3c;;"),
+            Ok((Reference::Char(r), 6))
+        );
+        assert_eq!(
+            Reference::parse_prefix("&amp &lt;"),
+            Err(ReferenceParseError::Unterminated)
+        );
+        assert_eq!(
+            Reference::parse_prefix("&#;"),
+            Err(ReferenceParseError::Invalid)
+        );
+        assert_eq!(
+            Reference::parse_prefix("&#x3g;"),
+            Err(ReferenceParseError::Invalid)
+        );
+        assert_eq!(
+            Reference::parse_prefix("&#0;"),
+            Err(ReferenceParseError::InvalidChar(CharRefError::InvalidChar(
+                0
+            )))
+        );
+        assert_eq!(
+            Reference::parse_prefix("&#99999999999;"),
+            Err(ReferenceParseError::InvalidChar(CharRefError::InvalidChar(
+                u32::MAX
+            )))
+        );
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[cfg(test)]
+mod nom_tests {
+    use super::*;
+
+    #[test]
+    fn parse_char_ref() {
+        let r = CharRef::new('<', CharRefRadix::Decimal).expect("Should never fail");
+        assert_eq!(CharRef::nom_parse("&#60;x".into()), Ok(("x".into(), r)));
+
+        let r = CharRef::new('\u{1F600}', CharRefRadix::Hexadecimal).expect("Should never fail");
+        assert_eq!(CharRef::nom_parse("&#x1f600;".into()), Ok(("".into(), r)));
+
+        assert!(CharRef::nom_parse("&#0;".into()).is_err());
+        assert!(CharRef::nom_parse("&#xD800;".into()).is_err());
+        assert!(CharRef::nom_parse("&#x110000;".into()).is_err());
+        assert!(CharRef::nom_parse("&#99999999999;".into()).is_err());
+        assert!(CharRef::nom_parse("&#x;".into()).is_err());
+    }
+
+    #[test]
+    fn parse_entity_refs() {
+        let name = NameStr::new("amp").expect("Should never fail");
+        assert_eq!(
+            EntityRef::nom_parse("&amp;rest".into()),
+            Ok(("rest".into(), EntityRef::new(name)))
+        );
+        assert_eq!(
+            PeReference::nom_parse("%amp;".into()),
+            Ok(("".into(), PeReference::new(name)))
+        );
+        assert!(EntityRef::nom_parse("&amp".into()).is_err());
+        assert!(EntityRef::nom_parse("&1amp;".into()).is_err());
+
+        assert_eq!(
+            Reference::nom_parse("&amp;".into()),
+            Ok(("".into(), Reference::Entity(EntityRef::new(name))))
+        );
+        let r = CharRef::new('A', CharRefRadix::Decimal).expect("Should never fail");
+        assert_eq!(
+            Reference::nom_parse("&#65;".into()),
+            Ok(("".into(), Reference::Char(r)))
+        );
+    }
+}
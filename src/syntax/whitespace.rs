@@ -0,0 +1,44 @@
+//! Whitespace and `Eq` parsers.
+
+use nom::types::CompleteStr;
+
+use strings::is_whitespace_char;
+
+named!(
+    /// Parses whitespaces (`S`).
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-S>.
+    ,
+    pub whitespace<CompleteStr<'_>, CompleteStr<'_>>,
+    take_while1!(is_whitespace_char)
+);
+
+named!(
+    /// Parses `Eq` (`S? '=' S?`).
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-Eq>.
+    ,
+    pub eq<CompleteStr<'_>, ()>,
+    do_parse!(opt!(whitespace) >> char!('=') >> opt!(whitespace) >> ())
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_whitespace() {
+        assert_eq!(
+            whitespace(" \t\r\nfoo".into()),
+            Ok(("foo".into(), " \t\r\n".into()))
+        );
+        assert!(whitespace("foo".into()).is_err());
+    }
+
+    #[test]
+    fn parse_eq() {
+        assert_eq!(eq("=foo".into()), Ok(("foo".into(), ())));
+        assert_eq!(eq(" \n= 'foo'".into()), Ok(("'foo'".into(), ())));
+        assert!(eq(" foo".into()).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Cursor over the source text.
+
+use nom::types::CompleteStr;
+use nom::{Context, Err, IResult};
+
+use position::Span;
+use syntax::whitespace;
+use tokenizer::{TokenizeError, TokenizeErrorKind};
+
+/// Cursor over the source text.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Cursor<'a> {
+    /// Whole source text.
+    input: &'a str,
+    /// Current byte position.
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a new `Cursor`.
+    pub(crate) fn new(input: &'a str, pos: usize) -> Self {
+        Self { input, pos }
+    }
+
+    /// Returns the current byte position.
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the rest of the input.
+    pub(crate) fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Returns whether the cursor reached the end of the input.
+    pub(crate) fn is_eof(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    /// Returns the span from the given position to the current position.
+    pub(crate) fn span_from(&self, start: usize) -> Span {
+        Span::new(start, self.pos)
+    }
+
+    /// Creates an error at the current position.
+    pub(crate) fn error(&self, kind: TokenizeErrorKind) -> TokenizeError {
+        TokenizeError::new(kind, self.pos)
+    }
+
+    /// Creates an error at the current position, reporting EOF if the input is consumed.
+    pub(crate) fn expected(&self, expected: &'static str) -> TokenizeError {
+        if self.is_eof() {
+            self.error(TokenizeErrorKind::UnexpectedEof)
+        } else {
+            self.error(TokenizeErrorKind::Expected(expected))
+        }
+    }
+
+    /// Runs the given parser and advances the cursor.
+    pub(crate) fn parse<O, F>(
+        &mut self,
+        parser: F,
+        expected: &'static str,
+    ) -> Result<O, TokenizeError>
+    where
+        F: FnOnce(CompleteStr<'a>) -> IResult<CompleteStr<'a>, O>,
+    {
+        match parser(CompleteStr(self.rest())) {
+            Ok((rest, output)) => {
+                self.pos = self.input.len() - rest.len();
+                Ok(output)
+            },
+            Err(Err::Error(Context::Code(rest, _))) | Err(Err::Failure(Context::Code(rest, _))) => {
+                let pos = self.input.len() - rest.len();
+                Err(Cursor::new(self.input, pos).expected(expected))
+            },
+            Err(Err::Incomplete(_)) => Err(self.error(TokenizeErrorKind::UnexpectedEof)),
+        }
+    }
+
+    /// Consumes the given string if the rest of the input starts with it.
+    pub(crate) fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the given string, or returns an error.
+    pub(crate) fn expect(&mut self, s: &'static str) -> Result<(), TokenizeError> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            Err(self.expected(s))
+        }
+    }
+
+    /// Consumes whitespaces and returns whether any whitespace is consumed.
+    pub(crate) fn skip_whitespace(&mut self) -> bool {
+        self.parse(whitespace, "whitespace").is_ok()
+    }
+
+    /// Consumes text until the given terminator and the terminator itself.
+    ///
+    /// Returns the text without the terminator.
+    pub(crate) fn take_until(&mut self, terminator: &str) -> Result<&'a str, TokenizeError> {
+        let rest = self.rest();
+        match rest.find(terminator) {
+            Some(len) => {
+                self.pos += len + terminator.len();
+                Ok(&rest[..len])
+            },
+            None => Err(TokenizeError::new(
+                TokenizeErrorKind::UnexpectedEof,
+                self.input.len(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Tokenizer error.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+
+use strings::Limit;
+
+/// Kind of a tokenizer error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TokenizeErrorKind {
+    /// Input is not valid UTF-8.
+    InvalidUtf8,
+    /// Unexpected end of input.
+    UnexpectedEof,
+    /// Unexpected input; the expected construct is described.
+    Expected(&'static str),
+    /// XML declaration not at the beginning of the document.
+    MisplacedXmlDecl,
+    /// Processing instruction target is `xml` in any case.
+    ReservedPiTarget,
+    /// `--` in a comment.
+    DoubleHyphenInComment,
+    /// A name exceeds the validation limit.
+    LimitExceeded(Limit),
+}
+
+impl fmt::Display for TokenizeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenizeErrorKind::InvalidUtf8 => f.write_str("Invalid UTF-8 sequence"),
+            TokenizeErrorKind::UnexpectedEof => f.write_str("Unexpected end of input"),
+            TokenizeErrorKind::Expected(expected) => write!(f, "Expected {}", expected),
+            TokenizeErrorKind::MisplacedXmlDecl => {
+                f.write_str("XML declaration is allowed only at the beginning of the document")
+            },
+            TokenizeErrorKind::ReservedPiTarget => {
+                f.write_str("Processing instruction target should not be `xml`")
+            },
+            TokenizeErrorKind::DoubleHyphenInComment => {
+                f.write_str("Comment should not contain `--`")
+            },
+            TokenizeErrorKind::LimitExceeded(limit) => write!(f, "Limit exceeded: {}", limit),
+        }
+    }
+}
+
+/// Tokenizer error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TokenizeError {
+    /// Error kind.
+    kind: TokenizeErrorKind,
+    /// Byte position of the error.
+    position: usize,
+}
+
+impl TokenizeError {
+    /// Creates a new `TokenizeError`.
+    pub(crate) fn new(kind: TokenizeErrorKind, position: usize) -> Self {
+        Self { kind, position }
+    }
+
+    /// Returns the error kind.
+    pub fn kind(&self) -> TokenizeErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte position of the error.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for TokenizeError {}
+
+impl fmt::Display for TokenizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte position {}", self.kind, self.position)
+    }
+}
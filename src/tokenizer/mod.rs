@@ -0,0 +1,8 @@
+//! Pull tokenizer.
+
+pub use self::error::{TokenizeError, TokenizeErrorKind};
+pub use self::pull::Tokenizer;
+
+mod cursor;
+mod error;
+mod pull;
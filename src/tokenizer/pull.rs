@@ -0,0 +1,727 @@
+//! Pull tokenizer.
+
+use std::str;
+
+use event::{Event, ExternalId};
+use strings::ValidationLimits;
+use strings::{
+    is_name_char, is_whitespace_char, EncNameStr, NameError, NameStr, PiTargetStr, QnameStr,
+};
+use syntax::{eq, QuotedLiteral, Standalone, VersionNum, XmlDecl};
+use tokenizer::cursor::Cursor;
+use tokenizer::{TokenizeError, TokenizeErrorKind};
+
+/// Tokenizer state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Outside of tags.
+    Content,
+    /// Inside of a start tag or an empty element tag.
+    StartTag,
+    /// Finished (successfully or with an error).
+    Finished,
+}
+
+/// Pull tokenizer.
+///
+/// This is an iterator of [`Event`]s.
+/// Once an error is returned, the iterator returns `None`.
+///
+/// The tokenizer checks the lexical syntax of each construct, but does not
+/// check well-formedness constraints spanning multiple events (such as tag
+/// matching).
+///
+/// Names are checked against [`ValidationLimits`] before they are validated.
+/// [`Tokenizer::new`] uses the default limits, which are suitable for
+/// untrusted documents.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::event::Event;
+/// # use xmlop_datatypes::tokenizer::{Tokenizer, TokenizeError};
+/// # fn run() -> Result<(), TokenizeError> {
+/// let mut names = Vec::new();
+/// for event in Tokenizer::new("<root><x:child attr='value'/></root>") {
+///     if let Event::StartTagOpen { name, .. } = event? {
+///         names.push(name.as_str());
+///     }
+/// }
+/// assert_eq!(names, ["root", "x:child"]);
+/// # Ok(())
+/// # }
+/// # run().expect("Should never fail");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    /// Source text.
+    input: &'a str,
+    /// Current byte position.
+    pos: usize,
+    /// Byte position of the document start (after the BOM if exists).
+    doc_start: usize,
+    /// State.
+    state: State,
+    /// Limits of names.
+    limits: ValidationLimits,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Creates a new `Tokenizer` from the given string, with the default
+    /// limits.
+    ///
+    /// A leading byte order mark is skipped.
+    pub fn new(input: &'a str) -> Self {
+        Self::with_limits(input, ValidationLimits::default())
+    }
+
+    /// Creates a new `Tokenizer` from the given string, with the given limits.
+    ///
+    /// A leading byte order mark is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::strings::{Limit, ValidationLimits};
+    /// # use xmlop_datatypes::tokenizer::{Tokenizer, TokenizeErrorKind};
+    /// let limits = ValidationLimits {
+    ///     max_name_bytes: 8,
+    ///     ..ValidationLimits::default()
+    /// };
+    /// let err = Tokenizer::with_limits("<too-long-name/>", limits)
+    ///     .find_map(Result::err)
+    ///     .expect("Should fail");
+    /// assert_eq!(err.kind(), TokenizeErrorKind::LimitExceeded(Limit::NameBytes(8)));
+    /// assert_eq!(err.position(), 1);
+    /// ```
+    pub fn with_limits(input: &'a str, limits: ValidationLimits) -> Self {
+        let doc_start = if input.starts_with('\u{FEFF}') {
+            '\u{FEFF}'.len_utf8()
+        } else {
+            0
+        };
+        Self {
+            input,
+            pos: doc_start,
+            doc_start,
+            state: State::Content,
+            limits,
+        }
+    }
+
+    /// Creates a new `Tokenizer` from the given UTF-8 bytes, with the default
+    /// limits.
+    ///
+    /// A leading byte order mark is skipped.
+    pub fn from_bytes(input: &'a [u8]) -> Result<Self, TokenizeError> {
+        str::from_utf8(input)
+            .map(Self::new)
+            .map_err(|e| TokenizeError::new(TokenizeErrorKind::InvalidUtf8, e.valid_up_to()))
+    }
+
+    /// Returns the current byte position.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the limits.
+    pub fn limits(&self) -> &ValidationLimits {
+        &self.limits
+    }
+
+    /// Reads the next event.
+    fn next_event(&mut self) -> Result<Option<Event<'a>>, TokenizeError> {
+        let mut cursor = Cursor::new(self.input, self.pos);
+        let event = match self.state {
+            State::Finished => return Ok(None),
+            State::StartTag => self.read_in_start_tag(&mut cursor)?,
+            State::Content if cursor.is_eof() => return Ok(None),
+            State::Content => self.read_in_content(&mut cursor)?,
+        };
+        self.pos = cursor.pos();
+        Ok(Some(event))
+    }
+
+    /// Reads an event inside of a start tag.
+    fn read_in_start_tag(&mut self, cursor: &mut Cursor<'a>) -> Result<Event<'a>, TokenizeError> {
+        let has_whitespace = cursor.skip_whitespace();
+        let start = cursor.pos();
+        if cursor.eat("/>") {
+            self.state = State::Content;
+            return Ok(Event::StartTagClose {
+                empty: true,
+                span: cursor.span_from(start),
+            });
+        }
+        if cursor.eat(">") {
+            self.state = State::Content;
+            return Ok(Event::StartTagClose {
+                empty: false,
+                span: cursor.span_from(start),
+            });
+        }
+        if !has_whitespace {
+            return Err(cursor.expected("whitespace, `>` or `/>`"));
+        }
+        let name = read_qname(cursor, &self.limits, "attribute name")?;
+        cursor.parse(eq, "`=`")?;
+        let value = cursor.parse(QuotedLiteral::nom_parse, "quoted attribute value")?;
+        Ok(Event::Attribute {
+            name,
+            value,
+            span: cursor.span_from(start),
+        })
+    }
+
+    /// Reads an event outside of tags.
+    fn read_in_content(&mut self, cursor: &mut Cursor<'a>) -> Result<Event<'a>, TokenizeError> {
+        let rest = cursor.rest();
+        if !rest.starts_with('<') {
+            let len = rest.find('<').unwrap_or(rest.len());
+            let start = cursor.pos();
+            cursor.eat(&rest[..len]);
+            return Ok(Event::Text {
+                text: &rest[..len],
+                span: cursor.span_from(start),
+            });
+        }
+        if is_xml_decl_start(rest) {
+            if cursor.pos() != self.doc_start {
+                return Err(cursor.error(TokenizeErrorKind::MisplacedXmlDecl));
+            }
+            return read_xml_decl(cursor);
+        }
+        if rest.starts_with("<?") {
+            return read_pi(cursor, &self.limits);
+        }
+        if rest.starts_with("<!--") {
+            return read_comment(cursor);
+        }
+        if rest.starts_with("<![CDATA[") {
+            return read_cdata(cursor);
+        }
+        if rest.starts_with("<!DOCTYPE") {
+            return read_doctype(cursor, &self.limits);
+        }
+        if rest.starts_with("</") {
+            return read_end_tag(cursor, &self.limits);
+        }
+        let start = cursor.pos();
+        cursor.expect("<")?;
+        let name = read_qname(cursor, &self.limits, "element name")?;
+        self.state = State::StartTag;
+        Ok(Event::StartTagOpen {
+            name,
+            span: cursor.span_from(start),
+        })
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Event<'a>, TokenizeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_event() {
+            Ok(Some(event)) => Some(Ok(event)),
+            Ok(None) => {
+                self.state = State::Finished;
+                None
+            },
+            Err(e) => {
+                self.state = State::Finished;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+/// Returns whether the given string starts with an XML declaration.
+fn is_xml_decl_start(s: &str) -> bool {
+    s.starts_with("<?xml")
+        && s[5..]
+            .chars()
+            .next()
+            .is_some_and(|c| is_whitespace_char(c) || c == '?')
+}
+
+/// Converts the limit error of a name at the given position.
+fn limit_error(e: NameError, pos: usize) -> TokenizeError {
+    match e {
+        NameError::LimitExceeded(limit) => {
+            TokenizeError::new(TokenizeErrorKind::LimitExceeded(limit), pos)
+        },
+        e => unreachable!("Should never fail: not a limit error: {}", e),
+    }
+}
+
+/// Reads a name.
+///
+/// The limits are checked before the characters are validated, and the
+/// scan stops just after the byte limit, so overlong names are not scanned.
+fn read_name<'a>(
+    cursor: &mut Cursor<'a>,
+    limits: &ValidationLimits,
+    expected: &'static str,
+) -> Result<&'a NameStr, TokenizeError> {
+    let start = cursor.pos();
+    let rest = cursor.rest();
+    let len = rest
+        .char_indices()
+        .find(|&(pos, c)| pos > limits.max_name_bytes || !is_name_char(c))
+        .map_or(rest.len(), |(pos, _)| pos);
+    let name = &rest[..len];
+    limits.check_name(name).map_err(|e| limit_error(e, start))?;
+    let name = NameStr::new(name).map_err(|_| cursor.expected(expected))?;
+    cursor.eat(name.as_str());
+    Ok(name)
+}
+
+/// Reads a name and validates it as `QName`.
+///
+/// The whole `Name` is consumed before the validation, so that a name such as
+/// `a:b:c` is rejected instead of being read as `a:b` followed by garbage.
+fn read_qname<'a>(
+    cursor: &mut Cursor<'a>,
+    limits: &ValidationLimits,
+    expected: &'static str,
+) -> Result<&'a QnameStr, TokenizeError> {
+    let start = cursor.pos();
+    let name = read_name(cursor, limits, expected)?.as_str();
+    limits
+        .check_qname(name)
+        .map_err(|e| limit_error(e, start))?;
+    QnameStr::new(name).map_err(|e| {
+        let pos = match e {
+            NameError::InvalidNameChar(pos, _) => start + pos,
+            _ => start,
+        };
+        TokenizeError::new(TokenizeErrorKind::Expected(expected), pos)
+    })
+}
+
+/// Reads a pseudo-attribute of the XML declaration, and converts the value.
+///
+/// If `convert` returns `None`, an error expecting `expected` is returned at
+/// the position of the value.
+fn read_decl_attr<'a, T, F>(
+    cursor: &mut Cursor<'a>,
+    name: &'static str,
+    expected: &'static str,
+    convert: F,
+) -> Result<Option<T>, TokenizeError>
+where
+    F: FnOnce(&'a str) -> Option<T>,
+{
+    let mut lookahead = *cursor;
+    if !lookahead.skip_whitespace() || !lookahead.eat(name) {
+        return Ok(None);
+    }
+    *cursor = lookahead;
+    cursor.parse(eq, "`=`")?;
+    // Skip the quotation mark.
+    let value_pos = cursor.pos() + 1;
+    let value = cursor.parse(QuotedLiteral::nom_parse, "quoted value")?;
+    match convert(value.value()) {
+        Some(v) => Ok(Some(v)),
+        None => Err(TokenizeError::new(
+            TokenizeErrorKind::Expected(expected),
+            value_pos,
+        )),
+    }
+}
+
+/// Reads an XML declaration.
+///
+/// Any `1.x` version is accepted, as XML 1.0 (fifth edition) processors do.
+fn read_xml_decl<'a>(cursor: &mut Cursor<'a>) -> Result<Event<'a>, TokenizeError> {
+    let start = cursor.pos();
+    cursor.expect("<?xml")?;
+    let version = read_decl_attr(cursor, "version", "version number", |s| {
+        VersionNum::parse_lenient(s).ok()
+    })?;
+    let version = match version {
+        Some(version) => version,
+        None => return Err(cursor.expected("version information")),
+    };
+    let encoding = read_decl_attr(cursor, "encoding", "encoding name", |s| {
+        EncNameStr::new(s).ok()
+    })?;
+    let standalone = read_decl_attr(cursor, "standalone", "`yes` or `no`", |s| {
+        s.parse::<Standalone>().ok()
+    })?;
+    cursor.skip_whitespace();
+    cursor.expect("?>")?;
+    Ok(Event::XmlDecl {
+        decl: XmlDecl {
+            version,
+            encoding,
+            standalone,
+        },
+        span: cursor.span_from(start),
+    })
+}
+
+/// Reads a processing instruction.
+fn read_pi<'a>(
+    cursor: &mut Cursor<'a>,
+    limits: &ValidationLimits,
+) -> Result<Event<'a>, TokenizeError> {
+    let start = cursor.pos();
+    cursor.expect("<?")?;
+    let target_pos = cursor.pos();
+    let target = read_name(cursor, limits, "processing instruction target")?;
+    let target = PiTargetStr::new(target.as_str())
+        .map_err(|_| TokenizeError::new(TokenizeErrorKind::ReservedPiTarget, target_pos))?;
+    let data = if cursor.eat("?>") {
+        None
+    } else if cursor.skip_whitespace() {
+        Some(cursor.take_until("?>")?)
+    } else {
+        return Err(cursor.expected("whitespace or `?>`"));
+    };
+    Ok(Event::ProcessingInstruction {
+        target,
+        data,
+        span: cursor.span_from(start),
+    })
+}
+
+/// Reads a comment.
+fn read_comment<'a>(cursor: &mut Cursor<'a>) -> Result<Event<'a>, TokenizeError> {
+    let start = cursor.pos();
+    cursor.expect("<!--")?;
+    let rest = cursor.rest();
+    match rest.find("--") {
+        Some(len) if rest[len..].starts_with("-->") => {
+            cursor.eat(&rest[..(len + 3)]);
+            Ok(Event::Comment {
+                text: &rest[..len],
+                span: cursor.span_from(start),
+            })
+        },
+        Some(len) => Err(TokenizeError::new(
+            TokenizeErrorKind::DoubleHyphenInComment,
+            cursor.pos() + len,
+        )),
+        None => Err(TokenizeError::new(
+            TokenizeErrorKind::UnexpectedEof,
+            cursor.pos() + rest.len(),
+        )),
+    }
+}
+
+/// Reads a CDATA section.
+fn read_cdata<'a>(cursor: &mut Cursor<'a>) -> Result<Event<'a>, TokenizeError> {
+    let start = cursor.pos();
+    cursor.expect("<![CDATA[")?;
+    let text = cursor.take_until("]]>")?;
+    Ok(Event::Cdata {
+        text,
+        span: cursor.span_from(start),
+    })
+}
+
+/// Reads an end tag.
+fn read_end_tag<'a>(
+    cursor: &mut Cursor<'a>,
+    limits: &ValidationLimits,
+) -> Result<Event<'a>, TokenizeError> {
+    let start = cursor.pos();
+    cursor.expect("</")?;
+    let name = read_qname(cursor, limits, "element name")?;
+    cursor.skip_whitespace();
+    cursor.expect(">")?;
+    Ok(Event::EndTag {
+        name,
+        span: cursor.span_from(start),
+    })
+}
+
+/// Reads a document type declaration.
+fn read_doctype<'a>(
+    cursor: &mut Cursor<'a>,
+    limits: &ValidationLimits,
+) -> Result<Event<'a>, TokenizeError> {
+    let start = cursor.pos();
+    cursor.expect("<!DOCTYPE")?;
+    if !cursor.skip_whitespace() {
+        return Err(cursor.expected("whitespace"));
+    }
+    let name = read_name(cursor, limits, "root element name")?;
+    let has_whitespace = cursor.skip_whitespace();
+    let external_id = if has_whitespace && cursor.eat("SYSTEM") {
+        let system = read_literal_after_whitespace(cursor, "system literal")?;
+        Some(ExternalId::System { system })
+    } else if has_whitespace && cursor.eat("PUBLIC") {
+        let public = read_literal_after_whitespace(cursor, "public ID literal")?;
+        let system = read_literal_after_whitespace(cursor, "system literal")?;
+        Some(ExternalId::Public { public, system })
+    } else {
+        None
+    };
+    cursor.skip_whitespace();
+    let internal_subset = if cursor.eat("[") {
+        let rest = cursor.rest();
+        let len = find_internal_subset_end(rest).ok_or_else(|| {
+            TokenizeError::new(TokenizeErrorKind::UnexpectedEof, cursor.pos() + rest.len())
+        })?;
+        cursor.eat(&rest[..len]);
+        cursor.expect("]")?;
+        cursor.skip_whitespace();
+        Some(&rest[..len])
+    } else {
+        None
+    };
+    cursor.expect(">")?;
+    Ok(Event::Doctype {
+        name,
+        external_id,
+        internal_subset,
+        span: cursor.span_from(start),
+    })
+}
+
+/// Reads whitespaces and a quoted literal.
+fn read_literal_after_whitespace<'a>(
+    cursor: &mut Cursor<'a>,
+    expected: &'static str,
+) -> Result<QuotedLiteral<'a>, TokenizeError> {
+    if !cursor.skip_whitespace() {
+        return Err(cursor.expected("whitespace"));
+    }
+    cursor.parse(QuotedLiteral::nom_parse, expected)
+}
+
+/// Returns the byte length of the internal subset, i.e. the position of the closing `]`.
+///
+/// Quoted literals, comments and processing instructions are skipped.
+fn find_internal_subset_end(s: &str) -> Option<usize> {
+    let mut pos = 0;
+    while pos < s.len() {
+        let rest = &s[pos..];
+        let skip = if rest.starts_with("<!--") {
+            rest.find("-->")? + 3
+        } else if rest.starts_with("<?") {
+            rest.find("?>")? + 2
+        } else if rest.starts_with('"') || rest.starts_with('\'') {
+            let quote = &rest[..1];
+            rest[1..].find(quote)? + 2
+        } else if rest.starts_with(']') {
+            return Some(pos);
+        } else {
+            rest.chars().next().map_or(1, char::len_utf8)
+        };
+        pos += skip;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use position::Span;
+    use strings::Limit;
+    use syntax::Quote;
+
+    use super::*;
+
+    fn tokenize(s: &str) -> Result<Vec<Event<'_>>, TokenizeError> {
+        Tokenizer::new(s).collect()
+    }
+
+    fn qname(s: &str) -> &QnameStr {
+        QnameStr::new(s).expect("Should never fail")
+    }
+
+    #[test]
+    fn tokenize_elements() {
+        let events = tokenize("<a x:y = 'v' z=\"w\"><b/>text</a >").expect("Should never fail");
+        assert_eq!(
+            events,
+            vec![
+                Event::StartTagOpen {
+                    name: qname("a"),
+                    span: Span::new(0, 2),
+                },
+                Event::Attribute {
+                    name: qname("x:y"),
+                    value: QuotedLiteral::new(Quote::Single, "v").expect("Should never fail"),
+                    span: Span::new(3, 12),
+                },
+                Event::Attribute {
+                    name: qname("z"),
+                    value: QuotedLiteral::new(Quote::Double, "w").expect("Should never fail"),
+                    span: Span::new(13, 18),
+                },
+                Event::StartTagClose {
+                    empty: false,
+                    span: Span::new(18, 19),
+                },
+                Event::StartTagOpen {
+                    name: qname("b"),
+                    span: Span::new(19, 21),
+                },
+                Event::StartTagClose {
+                    empty: true,
+                    span: Span::new(21, 23),
+                },
+                Event::Text {
+                    text: "text",
+                    span: Span::new(23, 27),
+                },
+                Event::EndTag {
+                    name: qname("a"),
+                    span: Span::new(27, 32),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_prolog() {
+        let source = "\u{FEFF}<?xml version='1.0' encoding=\"UTF-8\" standalone='yes' ?>\
+                      <!DOCTYPE root SYSTEM 'root.dtd' [<!ENTITY e ']>'>]>\
+                      <?pi data?><!-- comment --><root><![CDATA[<&>]]></root>";
+        let events = tokenize(source).expect("Should never fail");
+        match events[0] {
+            Event::XmlDecl { decl, span } => {
+                assert_eq!(decl.version, VersionNum::V1_0);
+                assert_eq!(decl.encoding.map(EncNameStr::as_str), Some("UTF-8"));
+                assert_eq!(decl.standalone, Some(Standalone::Yes));
+                assert_eq!(span.start(), 3);
+            },
+            ref ev => panic!("Unexpected event: {:?}", ev),
+        }
+        match events[1] {
+            Event::Doctype {
+                name,
+                external_id: Some(ExternalId::System { system }),
+                internal_subset,
+                ..
+            } => {
+                assert_eq!(name, "root");
+                assert_eq!(system.value(), "root.dtd");
+                assert_eq!(internal_subset, Some("<!ENTITY e ']>'>"));
+            },
+            ref ev => panic!("Unexpected event: {:?}", ev),
+        }
+        match events[2] {
+            Event::ProcessingInstruction { target, data, .. } => {
+                assert_eq!(target, "pi");
+                assert_eq!(data, Some("data"));
+            },
+            ref ev => panic!("Unexpected event: {:?}", ev),
+        }
+        match events[3] {
+            Event::Comment { text, .. } => assert_eq!(text, " comment "),
+            ref ev => panic!("Unexpected event: {:?}", ev),
+        }
+        match events[6] {
+            Event::Cdata { text, .. } => assert_eq!(text, "<&>"),
+            ref ev => panic!("Unexpected event: {:?}", ev),
+        }
+        assert_eq!(events.len(), 8);
+    }
+
+    #[test]
+    fn tokenize_errors() {
+        let err = tokenize("<a>  <?xml version='1.0'?>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::MisplacedXmlDecl);
+        assert_eq!(err.position(), 5);
+
+        let err = tokenize("<a/><?XML version='1.0'?>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::ReservedPiTarget);
+        assert_eq!(err.position(), 6);
+
+        let err = tokenize("<!-- a -- b -->").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::DoubleHyphenInComment);
+        assert_eq!(err.position(), 7);
+
+        let err = tokenize("<a b='c'd='e'/>").expect_err("Should fail");
+        assert_eq!(
+            err.kind(),
+            TokenizeErrorKind::Expected("whitespace, `>` or `/>`")
+        );
+        assert_eq!(err.position(), 8);
+
+        let err = tokenize("<a b>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("`=`"));
+
+        let err = tokenize("<1a/>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("element name"));
+        assert_eq!(err.position(), 1);
+
+        let err = tokenize("<a").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::UnexpectedEof);
+
+        let err = tokenize("<![CDATA[foo").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::UnexpectedEof);
+
+        let mut tokenizer = Tokenizer::new("<a:b:c/>");
+        let err = tokenizer
+            .next()
+            .expect("Should return an error")
+            .expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("element name"));
+        assert_eq!(err.position(), 4);
+        assert!(tokenizer.next().is_none());
+
+        let err = tokenize("<a b:c:d='e'/>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("attribute name"));
+        assert_eq!(err.position(), 6);
+
+        let err = tokenize("<a></a:>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("element name"));
+        assert_eq!(err.position(), 6);
+    }
+
+    #[test]
+    fn tokenize_xml_decl_errors() {
+        let err = tokenize("<?xml version='abc'?>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("version number"));
+        assert_eq!(err.position(), 15);
+
+        let err = tokenize("<?xml version='1.0' encoding='-utf8'?>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("encoding name"));
+        assert_eq!(err.position(), 30);
+
+        let err = tokenize("<?xml version='1.0' standalone='maybe'?>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::Expected("`yes` or `no`"));
+
+        assert!(tokenize("<?xml version='1.5'?><a/>").is_ok());
+    }
+
+    #[test]
+    fn tokenize_with_limits() {
+        let limits = ValidationLimits {
+            max_name_bytes: 4,
+            max_prefix_bytes: 1,
+            ..ValidationLimits::default()
+        };
+        let tokenize = |s| Tokenizer::with_limits(s, limits).collect::<Result<Vec<_>, _>>();
+        assert!(tokenize("<a:bc d='e'></a:bc>").is_ok());
+        let err = tokenize("<roots/>").expect_err("Should fail");
+        assert_eq!(
+            err.kind(),
+            TokenizeErrorKind::LimitExceeded(Limit::NameBytes(4))
+        );
+        assert_eq!(err.position(), 1);
+        let err = tokenize("<a x:y='1' ab:c='2'/>").expect_err("Should fail");
+        assert_eq!(
+            err.kind(),
+            TokenizeErrorKind::LimitExceeded(Limit::PrefixBytes(1))
+        );
+        assert_eq!(err.position(), 11);
+        let err = tokenize("<?target?><a/>").expect_err("Should fail");
+        assert_eq!(
+            err.kind(),
+            TokenizeErrorKind::LimitExceeded(Limit::NameBytes(4))
+        );
+    }
+
+    #[test]
+    fn tokenize_bytes() {
+        let err = Tokenizer::from_bytes(b"<a>\xFF</a>").expect_err("Should fail");
+        assert_eq!(err.kind(), TokenizeErrorKind::InvalidUtf8);
+        assert_eq!(err.position(), 3);
+    }
+}
@@ -0,0 +1,116 @@
+//! XML version dependent character rules.
+
+use strings::{is_name_char, is_name_start_char, is_restricted_char, is_xml11_char, is_xml_char};
+use strings::{NameError, NameStr};
+use syntax::VersionNum;
+
+/// XML version which determines the character rules.
+///
+/// One setting drives the `Char` set, the restricted characters, the
+/// end-of-line handling and the name validation.
+/// Since XML 1.0 fifth edition, both versions share the name characters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum XmlVersion {
+    /// XML 1.0.
+    #[default]
+    V1_0,
+    /// XML 1.1.
+    V1_1,
+}
+
+impl XmlVersion {
+    /// Checks whether the given character is `Char` of the version.
+    pub fn is_char(self, c: char) -> bool {
+        match self {
+            XmlVersion::V1_0 => is_xml_char(c),
+            XmlVersion::V1_1 => is_xml11_char(c),
+        }
+    }
+
+    /// Checks whether the given character is `RestrictedChar` of the version.
+    ///
+    /// Restricted characters can appear only as character references.
+    /// XML 1.0 has no restricted characters.
+    pub fn is_restricted_char(self, c: char) -> bool {
+        match self {
+            XmlVersion::V1_0 => false,
+            XmlVersion::V1_1 => is_restricted_char(c),
+        }
+    }
+
+    /// Checks whether the given character can appear literally in a document.
+    pub fn is_literal_char(self, c: char) -> bool {
+        self.is_char(c) && !self.is_restricted_char(c)
+    }
+
+    /// Checks whether the given character is name start character.
+    pub fn is_name_start_char(self, c: char) -> bool {
+        is_name_start_char(c)
+    }
+
+    /// Checks whether the given character is name character.
+    pub fn is_name_char(self, c: char) -> bool {
+        is_name_char(c)
+    }
+
+    /// Validates the given string as `Name` of the version.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xmlop_datatypes::version::XmlVersion;
+    /// assert!(XmlVersion::V1_0.validate_name("foo").is_ok());
+    /// assert!(XmlVersion::V1_1.validate_name("1foo").is_err());
+    /// ```
+    pub fn validate_name(self, s: &str) -> Result<&NameStr, NameError> {
+        NameStr::new(s)
+    }
+
+    /// Checks whether the given character is a line ending to be normalized.
+    ///
+    /// See <https://www.w3.org/TR/2006/REC-xml11-20060816/#sec-line-ends>.
+    pub fn is_line_end_char(self, c: char) -> bool {
+        match self {
+            XmlVersion::V1_0 => c == '\r',
+            XmlVersion::V1_1 => matches!(c, '\r' | '\u{85}' | '\u{2028}'),
+        }
+    }
+}
+
+impl From<VersionNum> for XmlVersion {
+    /// Converts the version number.
+    ///
+    /// Unknown `1.x` versions are processed as XML 1.0.
+    fn from(v: VersionNum) -> Self {
+        match v {
+            VersionNum::V1_1 => XmlVersion::V1_1,
+            VersionNum::V1_0 | VersionNum::Other(_) => XmlVersion::V1_0,
+        }
+    }
+}
+
+impl From<XmlVersion> for VersionNum {
+    fn from(v: XmlVersion) -> Self {
+        match v {
+            XmlVersion::V1_0 => VersionNum::V1_0,
+            XmlVersion::V1_1 => VersionNum::V1_1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_chars() {
+        assert!(!XmlVersion::V1_0.is_char('\u{1}'));
+        assert!(XmlVersion::V1_1.is_char('\u{1}'));
+        assert!(!XmlVersion::V1_1.is_literal_char('\u{1}'));
+        assert!(XmlVersion::V1_0.is_literal_char('\u{7F}'));
+        assert!(!XmlVersion::V1_1.is_literal_char('\u{7F}'));
+        assert!(XmlVersion::V1_1.is_literal_char('\u{85}'));
+        assert!(!XmlVersion::V1_0.is_line_end_char('\u{85}'));
+        assert!(XmlVersion::V1_1.is_line_end_char('\u{2028}'));
+    }
+}
@@ -0,0 +1,489 @@
+//! Well-formedness checker.
+
+use std::borrow::ToOwned;
+use std::string::String;
+use std::vec::Vec;
+
+use event::Event;
+use namespace::{NamespaceContext, XMLNS_NAMESPACE, XML_NAMESPACE};
+use strings::{is_whitespace_char, is_xml_char, Qname};
+use wf::WfError;
+
+/// Start tag whose attributes are being read.
+#[derive(Debug, Clone)]
+struct PendingStartTag {
+    /// Element name.
+    name: Qname,
+    /// Attributes.
+    attrs: Vec<(Qname, String)>,
+}
+
+/// Well-formedness checker over an XML event stream.
+///
+/// The checker receives start tags, attributes, start tag ends, end tags and
+/// text in document order, and reports violations of well-formedness and
+/// namespace constraints.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::strings::{NcnameString, Qname};
+/// # use xmlop_datatypes::wf::{WfChecker, WfError};
+/// # fn run() -> Result<(), WfError> {
+/// let name = |s: &str| {
+///     Qname::from_local(NcnameString::new(s.to_owned()).expect("Should never fail"))
+/// };
+/// let mut checker = WfChecker::new();
+/// checker.start_element(name("root"))?;
+/// checker.attribute(name("id"), "1")?;
+/// assert_eq!(
+///     checker.attribute(name("id"), "2"),
+///     Err(WfError::DuplicateAttribute(name("id")))
+/// );
+/// checker.close_start_tag(false)?;
+/// checker.text("hello")?;
+/// assert!(checker.end_element(name("wrong")).is_err());
+/// # Ok(())
+/// # }
+/// # run().expect("Should never fail");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct WfChecker {
+    /// Open elements.
+    open: Vec<Qname>,
+    /// Start tag being read.
+    pending: Option<PendingStartTag>,
+    /// Whether the root element has been started.
+    root_seen: bool,
+    /// In-scope namespaces.
+    namespaces: NamespaceContext,
+}
+
+impl WfChecker {
+    /// Creates a new `WfChecker`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the in-scope namespaces.
+    pub fn namespaces(&self) -> &NamespaceContext {
+        &self.namespaces
+    }
+
+    /// Returns the number of open elements.
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Checks the beginning of a start tag or an empty element tag.
+    pub fn start_element(&mut self, name: Qname) -> Result<(), WfError> {
+        self.ensure_no_pending()?;
+        if self.open.is_empty() && self.root_seen {
+            return Err(WfError::MultipleRootElements(name));
+        }
+        self.pending = Some(PendingStartTag {
+            name,
+            attrs: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Checks an attribute of the current start tag.
+    ///
+    /// `value` is the raw attribute value without quotes.
+    pub fn attribute(&mut self, name: Qname, value: &str) -> Result<(), WfError> {
+        let pending = self.pending.as_mut().ok_or(WfError::UnexpectedAttribute)?;
+        if pending.attrs.iter().any(|(n, _)| *n == name) {
+            return Err(WfError::DuplicateAttribute(name));
+        }
+        check_chars(value)?;
+        if value.contains('<') {
+            return Err(WfError::LtInAttributeValue(name));
+        }
+        pending.attrs.push((name, value.to_owned()));
+        Ok(())
+    }
+
+    /// Checks the end of the current start tag (`>`) or empty element tag (`/>`).
+    ///
+    /// Namespace constraints are checked here, because namespace declarations
+    /// may appear after prefixed attributes.
+    pub fn close_start_tag(&mut self, empty: bool) -> Result<(), WfError> {
+        let PendingStartTag { name, attrs } =
+            self.pending.take().ok_or(WfError::UnexpectedAttribute)?;
+        self.namespaces.push_scope();
+        if let Err(e) = self.check_namespaces(&name, &attrs) {
+            self.namespaces.pop_scope();
+            return Err(e);
+        }
+        self.root_seen = true;
+        if empty {
+            self.namespaces.pop_scope();
+        } else {
+            self.open.push(name);
+        }
+        Ok(())
+    }
+
+    /// Checks an end tag.
+    pub fn end_element(&mut self, name: Qname) -> Result<(), WfError> {
+        self.ensure_no_pending()?;
+        let expected = self
+            .open
+            .pop()
+            .ok_or_else(|| WfError::UnmatchedEndTag(name.clone()))?;
+        self.namespaces.pop_scope();
+        if expected != name {
+            return Err(WfError::ElementTypeMismatch {
+                expected,
+                found: name,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks raw character data.
+    pub fn text(&mut self, text: &str) -> Result<(), WfError> {
+        self.ensure_no_pending()?;
+        check_chars(text)?;
+        if let Some(pos) = text.find("]]>") {
+            return Err(WfError::CdataEndInText(pos));
+        }
+        if self.open.is_empty() && !text.chars().all(is_whitespace_char) {
+            return Err(WfError::TextOutsideRootElement);
+        }
+        Ok(())
+    }
+
+    /// Checks the content of a CDATA section.
+    pub fn cdata(&mut self, text: &str) -> Result<(), WfError> {
+        self.ensure_no_pending()?;
+        check_chars(text)?;
+        if self.open.is_empty() {
+            return Err(WfError::TextOutsideRootElement);
+        }
+        Ok(())
+    }
+
+    /// Checks the given event.
+    ///
+    /// Comments and processing instructions are checked only for their characters.
+    pub fn event(&mut self, event: &Event) -> Result<(), WfError> {
+        match *event {
+            Event::StartTagOpen { name, .. } => self.start_element(name.to_qname()),
+            Event::Attribute { name, value, .. } => self.attribute(name.to_qname(), value.value()),
+            Event::StartTagClose { empty, .. } => self.close_start_tag(empty),
+            Event::EndTag { name, .. } => self.end_element(name.to_qname()),
+            Event::Text { text, .. } => self.text(text),
+            Event::Cdata { text, .. } => self.cdata(text),
+            Event::Comment { text, .. } => {
+                self.ensure_no_pending()?;
+                check_chars(text)
+            },
+            Event::ProcessingInstruction { data, .. } => {
+                self.ensure_no_pending()?;
+                data.map_or(Ok(()), check_chars)
+            },
+            Event::XmlDecl { .. } | Event::Doctype { .. } => self.ensure_no_pending(),
+        }
+    }
+
+    /// Checks that the document is complete.
+    pub fn finish(self) -> Result<(), WfError> {
+        self.ensure_no_pending()?;
+        if let Some(name) = self.open.into_iter().last() {
+            return Err(WfError::UnclosedElement(name));
+        }
+        if !self.root_seen {
+            return Err(WfError::NoRootElement);
+        }
+        Ok(())
+    }
+
+    /// Declares namespaces of the start tag and checks namespace constraints.
+    fn check_namespaces(&mut self, name: &Qname, attrs: &[(Qname, String)]) -> Result<(), WfError> {
+        for (attr, value) in attrs {
+            declare_namespace(&mut self.namespaces, attr, value)?;
+        }
+        if name.prefix().is_some_and(|p| p == "xmlns") {
+            return Err(WfError::ReservedNamespace(name.clone()));
+        }
+        if self.namespaces.expand_element_name(name).is_none() {
+            return Err(undeclared(name));
+        }
+        let mut expanded_attrs = Vec::with_capacity(attrs.len());
+        for (attr, _) in attrs.iter().filter(|(attr, _)| !is_namespace_decl(attr)) {
+            let expanded = self
+                .namespaces
+                .expand_attribute_name(attr)
+                .ok_or_else(|| undeclared(attr))?;
+            if let Some(&(first, _)) = expanded_attrs.iter().find(|&&(_, e)| e == expanded) {
+                return Err(WfError::DuplicateExpandedAttribute(
+                    Qname::clone(first),
+                    attr.clone(),
+                ));
+            }
+            expanded_attrs.push((attr, expanded));
+        }
+        Ok(())
+    }
+
+    /// Returns an error if a start tag is not closed.
+    fn ensure_no_pending(&self) -> Result<(), WfError> {
+        if self.pending.is_some() {
+            return Err(WfError::UnclosedStartTag);
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether the given attribute name is a namespace declaration.
+fn is_namespace_decl(name: &Qname) -> bool {
+    match name.prefix() {
+        Some(prefix) => prefix == "xmlns",
+        None => name.local() == "xmlns",
+    }
+}
+
+/// Creates an undeclared prefix error for the given name.
+fn undeclared(name: &Qname) -> WfError {
+    let prefix = name
+        .prefix()
+        .unwrap_or_else(|| unreachable!("Unprefixed names are always resolvable"));
+    WfError::UndeclaredPrefix(prefix.to_owned())
+}
+
+/// Declares the namespace if the attribute is a namespace declaration.
+fn declare_namespace(
+    namespaces: &mut NamespaceContext,
+    attr: &Qname,
+    value: &str,
+) -> Result<(), WfError> {
+    if !is_namespace_decl(attr) {
+        return Ok(());
+    }
+    let reserved = || WfError::ReservedNamespace(attr.clone());
+    match attr.prefix() {
+        None => {
+            if value == XML_NAMESPACE || value == XMLNS_NAMESPACE {
+                return Err(reserved());
+            }
+            namespaces.declare(None, value.to_owned());
+        },
+        Some(_) => {
+            let prefix = attr.local();
+            if prefix == "xmlns" || value == XMLNS_NAMESPACE {
+                return Err(reserved());
+            }
+            if (prefix == "xml") != (value == XML_NAMESPACE) {
+                return Err(reserved());
+            }
+            if value.is_empty() {
+                return Err(WfError::PrefixUndeclaring(prefix.to_owned()));
+            }
+            namespaces.declare(Some(prefix.to_owned()), value.to_owned());
+        },
+    }
+    Ok(())
+}
+
+/// Checks that the string consists of XML `Char`s.
+fn check_chars(s: &str) -> Result<(), WfError> {
+    match s.char_indices().find(|&(_, c)| !is_xml_char(c)) {
+        Some((pos, c)) => Err(WfError::InvalidChar(pos, c)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strings::NcnameString;
+
+    use super::*;
+
+    fn qname(s: &str) -> Qname {
+        let mut parts = s.splitn(2, ':');
+        let first = NcnameString::new(parts.next().unwrap_or("").to_owned());
+        let first = first.expect("Should never fail");
+        match parts.next() {
+            Some(local) => Qname::from_prefix_and_local(
+                first,
+                NcnameString::new(local.to_owned()).expect("Should never fail"),
+            ),
+            None => Qname::from_local(first),
+        }
+    }
+
+    fn element(
+        checker: &mut WfChecker,
+        name: &str,
+        attrs: &[(&str, &str)],
+        empty: bool,
+    ) -> Result<(), WfError> {
+        checker.start_element(qname(name))?;
+        for &(attr, value) in attrs {
+            checker.attribute(qname(attr), value)?;
+        }
+        checker.close_start_tag(empty)
+    }
+
+    #[test]
+    fn well_formed_document() {
+        let mut checker = WfChecker::new();
+        checker.text("\n").expect("Should never fail");
+        element(
+            &mut checker,
+            "a:root",
+            &[("xmlns:a", "urn:a"), ("xmlns", "urn:default"), ("a:x", "1")],
+            false,
+        )
+        .expect("Should never fail");
+        element(
+            &mut checker,
+            "child",
+            &[("x", "1"), ("xml:lang", "en")],
+            true,
+        )
+        .expect("Should never fail");
+        checker.cdata("<&>").expect("Should never fail");
+        checker
+            .end_element(qname("a:root"))
+            .expect("Should never fail");
+        checker.finish().expect("Should never fail");
+    }
+
+    #[test]
+    fn structure_errors() {
+        let mut checker = WfChecker::new();
+        assert_eq!(checker.text("text"), Err(WfError::TextOutsideRootElement));
+        element(&mut checker, "root", &[], true).expect("Should never fail");
+        assert_eq!(
+            element(&mut checker, "root2", &[], true),
+            Err(WfError::MultipleRootElements(qname("root2")))
+        );
+
+        let mut checker = WfChecker::new();
+        element(&mut checker, "a", &[], false).expect("Should never fail");
+        assert_eq!(
+            checker.end_element(qname("b")),
+            Err(WfError::ElementTypeMismatch {
+                expected: qname("a"),
+                found: qname("b"),
+            })
+        );
+        assert_eq!(
+            checker.end_element(qname("a")),
+            Err(WfError::UnmatchedEndTag(qname("a")))
+        );
+
+        let mut checker = WfChecker::new();
+        element(&mut checker, "a", &[], false).expect("Should never fail");
+        assert_eq!(checker.text("a]]>b"), Err(WfError::CdataEndInText(1)));
+        assert_eq!(
+            checker.text("a\u{1}"),
+            Err(WfError::InvalidChar(1, '\u{1}'))
+        );
+        assert_eq!(checker.finish(), Err(WfError::UnclosedElement(qname("a"))));
+
+        assert_eq!(WfChecker::new().finish(), Err(WfError::NoRootElement));
+    }
+
+    #[test]
+    fn attribute_errors() {
+        let mut checker = WfChecker::new();
+        assert_eq!(
+            element(&mut checker, "a", &[("x", "a<b")], true),
+            Err(WfError::LtInAttributeValue(qname("x")))
+        );
+
+        let mut checker = WfChecker::new();
+        assert_eq!(
+            element(
+                &mut checker,
+                "a",
+                &[
+                    ("xmlns:p", "urn:x"),
+                    ("xmlns:q", "urn:x"),
+                    ("p:x", ""),
+                    ("q:x", "")
+                ],
+                true
+            ),
+            Err(WfError::DuplicateExpandedAttribute(
+                qname("p:x"),
+                qname("q:x")
+            ))
+        );
+    }
+
+    #[test]
+    fn namespace_errors() {
+        let check =
+            |name: &str, attrs: &[(&str, &str)]| element(&mut WfChecker::new(), name, attrs, true);
+        assert_eq!(
+            check("p:a", &[]),
+            Err(WfError::UndeclaredPrefix(
+                NcnameString::new("p".to_owned()).expect("Should never fail")
+            ))
+        );
+        assert_eq!(
+            check("a", &[("xmlns:p", "")]),
+            Err(WfError::PrefixUndeclaring(
+                NcnameString::new("p".to_owned()).expect("Should never fail")
+            ))
+        );
+        assert_eq!(
+            check("a", &[("xmlns:xml", "urn:x")]),
+            Err(WfError::ReservedNamespace(qname("xmlns:xml")))
+        );
+        assert_eq!(
+            check("a", &[("xmlns:p", XML_NAMESPACE)]),
+            Err(WfError::ReservedNamespace(qname("xmlns:p")))
+        );
+        assert_eq!(
+            check("xmlns:a", &[]),
+            Err(WfError::ReservedNamespace(qname("xmlns:a")))
+        );
+        assert!(check("a", &[("xmlns:xml", XML_NAMESPACE)]).is_ok());
+        assert!(check("a", &[("xmlns", "")]).is_ok());
+    }
+}
+
+#[cfg(feature = "nom-4")]
+#[cfg(test)]
+mod nom_tests {
+    use strings::NcnameStr;
+    use tokenizer::Tokenizer;
+
+    use super::*;
+
+    fn check(s: &str) -> Result<(), WfError> {
+        let mut checker = WfChecker::new();
+        for event in Tokenizer::new(s) {
+            checker.event(&event.expect("Should be tokenized"))?;
+        }
+        checker.finish()
+    }
+
+    #[test]
+    fn check_tokenized_document() {
+        assert!(
+            check("<?xml version='1.0'?><!-- c --><r xmlns:p='urn:p'><p:c p:a='1'/></r>").is_ok()
+        );
+        assert_eq!(
+            check("<r><c></r></c>"),
+            Err(WfError::ElementTypeMismatch {
+                expected: Qname::from_local(
+                    NcnameStr::new("c").expect("Should never fail").to_owned()
+                ),
+                found: Qname::from_local(
+                    NcnameStr::new("r").expect("Should never fail").to_owned()
+                ),
+            })
+        );
+        assert_eq!(
+            check("<r a='1' a='2'/>").map_err(|e| e.to_string()),
+            Err("[WFC: Unique Att Spec] duplicate attribute `a`".to_owned())
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Well-formedness error.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+
+use strings::{NcnameString, Qname};
+
+/// Well-formedness or namespace constraint violation.
+///
+/// Each variant documents the violated constraint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WfError {
+    /// [WFC: Element Type Match] the end tag name differs from the start tag name.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#GIMatch>.
+    ElementTypeMismatch {
+        /// Name of the open element.
+        expected: Qname,
+        /// Name in the end tag.
+        found: Qname,
+    },
+    /// [WFC: Element Type Match] end tag without corresponding start tag.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#GIMatch>.
+    UnmatchedEndTag(Qname),
+    /// [WFC: Unique Att Spec] the same attribute name appears twice in a tag.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#uniqattspec>.
+    DuplicateAttribute(Qname),
+    /// [NSC: Attributes Unique] two attributes have the same expanded name.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#nsc-AttrsUnique>.
+    DuplicateExpandedAttribute(Qname, Qname),
+    /// [NSC: Prefix Declared] the prefix is not declared.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#nsc-NSDeclared>.
+    UndeclaredPrefix(NcnameString),
+    /// [NSC: No Prefix Undeclaring] a prefixed namespace declaration has an empty value.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#nsc-NoPrefixUndecl>.
+    PrefixUndeclaring(NcnameString),
+    /// [NSC: Reserved Prefixes and Namespace Names] `xml` or `xmlns` prefix or
+    /// namespace name is misused.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#xmlReserved>.
+    ReservedNamespace(Qname),
+    /// [WFC: No < in Attribute Values] an attribute value contains `<`.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#CleanAttrVals>.
+    LtInAttributeValue(Qname),
+    /// [2.2 Characters] a character is not an XML `Char`.
+    ///
+    /// The byte position is relative to the text or attribute value.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-Char>.
+    InvalidChar(usize, char),
+    /// [2.4 Character Data] character data contains `]]>`.
+    ///
+    /// The byte position is relative to the text.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-CharData>.
+    CdataEndInText(usize),
+    /// [2.1 Well-Formed XML Documents] more than one root element.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-document>.
+    MultipleRootElements(Qname),
+    /// [2.1 Well-Formed XML Documents] character data outside of the root element.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-document>.
+    TextOutsideRootElement,
+    /// [2.1 Well-Formed XML Documents] no root element.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-document>.
+    NoRootElement,
+    /// [2.1 Well-Formed XML Documents] the document ends with open elements.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-document>.
+    UnclosedElement(Qname),
+    /// Attribute or start tag end without open start tag.
+    UnexpectedAttribute,
+    /// Content or end tag before the start tag is closed.
+    UnclosedStartTag,
+}
+
+#[cfg(feature = "std")]
+impl error::Error for WfError {}
+
+impl fmt::Display for WfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WfError::ElementTypeMismatch { expected, found } => write!(
+                f,
+                "[WFC: Element Type Match] expected end tag `{}`, but found `{}`",
+                expected, found
+            ),
+            WfError::UnmatchedEndTag(name) => write!(
+                f,
+                "[WFC: Element Type Match] end tag `{}` has no corresponding start tag",
+                name
+            ),
+            WfError::DuplicateAttribute(name) => write!(
+                f,
+                "[WFC: Unique Att Spec] duplicate attribute `{}`",
+                name
+            ),
+            WfError::DuplicateExpandedAttribute(first, second) => write!(
+                f,
+                "[NSC: Attributes Unique] attributes `{}` and `{}` have the same expanded name",
+                first, second
+            ),
+            WfError::UndeclaredPrefix(prefix) => write!(
+                f,
+                "[NSC: Prefix Declared] undeclared prefix `{}`",
+                prefix
+            ),
+            WfError::PrefixUndeclaring(prefix) => write!(
+                f,
+                "[NSC: No Prefix Undeclaring] prefix `{}` is bound to empty namespace name",
+                prefix
+            ),
+            WfError::ReservedNamespace(name) => write!(
+                f,
+                "[NSC: Reserved Prefixes and Namespace Names] reserved prefix or namespace is misused by `{}`",
+                name
+            ),
+            WfError::LtInAttributeValue(name) => write!(
+                f,
+                "[WFC: No < in Attribute Values] value of attribute `{}` contains `<`",
+                name
+            ),
+            WfError::InvalidChar(pos, c) => write!(
+                f,
+                "[2.2 Characters] invalid character at byte position {}: {:?}",
+                pos, c
+            ),
+            WfError::CdataEndInText(pos) => write!(
+                f,
+                "[2.4 Character Data] `]]>` in character data at byte position {}",
+                pos
+            ),
+            WfError::MultipleRootElements(name) => write!(
+                f,
+                "[2.1 Well-Formed XML Documents] second root element `{}`",
+                name
+            ),
+            WfError::TextOutsideRootElement => f.write_str(
+                "[2.1 Well-Formed XML Documents] character data outside of the root element",
+            ),
+            WfError::NoRootElement => {
+                f.write_str("[2.1 Well-Formed XML Documents] no root element")
+            },
+            WfError::UnclosedElement(name) => write!(
+                f,
+                "[2.1 Well-Formed XML Documents] element `{}` is not closed",
+                name
+            ),
+            WfError::UnexpectedAttribute => {
+                f.write_str("Attribute or start tag end appeared outside of a start tag")
+            },
+            WfError::UnclosedStartTag => f.write_str("Start tag is not closed"),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Well-formedness checking.
+
+pub use self::checker::WfChecker;
+pub use self::error::WfError;
+
+mod checker;
+mod error;
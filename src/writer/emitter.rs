@@ -0,0 +1,864 @@
+//! Well-formed XML writer.
+
+use std::fmt;
+use std::io;
+
+use escape::{escape_attribute, escape_text};
+use fresh::NameGenerator;
+use namespace::{declaration_name, ExpandedName, NamespaceContext, XMLNS_NAMESPACE, XML_NAMESPACE};
+use strings::{is_whitespace_char, is_xml_char, NcnameStr, NcnameString, PiTargetStr, Qname};
+use syntax::{Quote, XmlDecl};
+use writer::{FmtOutput, IoOutput, Output, WriteError};
+
+/// Position in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocumentState {
+    /// Nothing is written yet.
+    Start,
+    /// In the prolog, before the root element.
+    Prolog,
+    /// Inside of the root element.
+    RootElement,
+    /// After the root element.
+    Epilog,
+}
+
+/// Streaming writer of well-formed XML.
+///
+/// Element and attribute names are validated types, and text-like contents
+/// are escaped or checked, so that the writer never emits malformed markup.
+/// The document structure (a single root element, the XML declaration at the
+/// beginning) and the uniqueness of attributes and namespace declarations in
+/// each tag are also checked.
+///
+/// # Examples
+///
+/// ```rust
+/// # use xmlop_datatypes::strings::{NcnameString, Qname};
+/// # use xmlop_datatypes::writer::Writer;
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let root = Qname::from_local(NcnameString::new("root".to_owned())?);
+/// let attr = Qname::from_local(NcnameString::new("attr".to_owned())?);
+///
+/// let mut writer = Writer::from_fmt(String::new());
+/// writer.start_element(&root)?;
+/// writer.attribute(&attr, "\"quoted\" & <escaped>")?;
+/// writer.text("1 < 2")?;
+/// writer.cdata("]]>")?;
+/// let xml = writer.finish()?.0;
+/// assert_eq!(
+///     xml,
+///     "<root attr=\"&quot;quoted&quot; &amp; &lt;escaped>\">1 &lt; 2\
+///      <![CDATA[]]]]><![CDATA[>]]></root>"
+/// );
+/// # Ok(())
+/// # }
+/// # run().expect("Should never fail");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Writer<O> {
+    /// Output destination.
+    output: O,
+    /// Position in the document.
+    state: DocumentState,
+    /// Open elements.
+    open: Vec<Qname>,
+    /// Whether the last start tag is not closed yet.
+    in_start_tag: bool,
+    /// Attributes written to the current start tag.
+    attributes: Vec<Qname>,
+    /// In-scope namespaces.
+    namespaces: NamespaceContext,
+    /// Whether to declare namespaces automatically.
+    auto_declare_namespaces: bool,
+}
+
+impl<W: io::Write> Writer<IoOutput<W>> {
+    /// Creates a new `Writer` to the given `io::Write`.
+    pub fn from_io(w: W) -> Self {
+        Self::new(IoOutput(w))
+    }
+}
+
+impl<W: fmt::Write> Writer<FmtOutput<W>> {
+    /// Creates a new `Writer` to the given `fmt::Write`.
+    pub fn from_fmt(w: W) -> Self {
+        Self::new(FmtOutput(w))
+    }
+}
+
+impl<O: Output> Writer<O> {
+    /// Creates a new `Writer`.
+    pub fn new(output: O) -> Self {
+        Self {
+            output,
+            state: DocumentState::Start,
+            open: Vec::new(),
+            in_start_tag: false,
+            attributes: Vec::new(),
+            namespaces: NamespaceContext::new(),
+            auto_declare_namespaces: false,
+        }
+    }
+
+    /// Sets whether to declare namespaces automatically.
+    ///
+    /// If enabled, [`start_element_ns`][`Writer::start_element_ns`] and
+    /// [`attribute_ns`][`Writer::attribute_ns`] write namespace declarations
+    /// when the prefix is not bound to the given namespace.
+    /// If disabled (default), they return an error in such case.
+    pub fn set_auto_declare_namespaces(&mut self, enable: bool) {
+        self.auto_declare_namespaces = enable;
+    }
+
+    /// Returns the in-scope namespaces.
+    pub fn namespaces(&self) -> &NamespaceContext {
+        &self.namespaces
+    }
+
+    /// Returns the number of open elements.
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Writes an XML declaration.
+    ///
+    /// This should be the first write to the document.
+    pub fn xml_decl(&mut self, decl: &XmlDecl) -> Result<(), WriteError> {
+        if self.state != DocumentState::Start {
+            return Err(WriteError::MisplacedXmlDecl);
+        }
+        self.state = DocumentState::Prolog;
+        self.output.write_str(&decl.to_string())
+    }
+
+    /// Writes a start tag without namespace checking.
+    pub fn start_element(&mut self, name: &Qname) -> Result<(), WriteError> {
+        if self.state == DocumentState::Epilog {
+            return Err(WriteError::MultipleRootElements(name.clone()));
+        }
+        self.close_start_tag()?;
+        self.state = DocumentState::RootElement;
+        self.output.write_str("<")?;
+        write_qname(&mut self.output, name)?;
+        self.open.push(name.clone());
+        self.namespaces.push_scope();
+        self.in_start_tag = true;
+        self.attributes.clear();
+        Ok(())
+    }
+
+    /// Writes a start tag of the element in the given namespace.
+    ///
+    /// If the prefix of the name is not bound to the namespace, a namespace
+    /// declaration is written or an error is returned, depending on
+    /// [`set_auto_declare_namespaces`][`Writer::set_auto_declare_namespaces`].
+    pub fn start_element_ns(
+        &mut self,
+        name: &Qname,
+        namespace: Option<&str>,
+    ) -> Result<(), WriteError> {
+        let bound = match name.prefix() {
+            Some(prefix) => self.namespaces.resolve_prefix(prefix) == namespace,
+            None => self.namespaces.default_namespace() == namespace,
+        };
+        if !bound {
+            if !self.auto_declare_namespaces || namespace.is_none() && name.prefix().is_some() {
+                return Err(WriteError::UnboundNamespace(name.clone()));
+            }
+            check_declaration(name.prefix(), namespace.unwrap_or(""))?;
+        }
+        self.start_element(name)?;
+        if !bound {
+            self.write_namespace_decl(name.prefix(), namespace.unwrap_or(""))?;
+        }
+        Ok(())
+    }
+
+    /// Writes an attribute without namespace checking.
+    ///
+    /// The value is escaped.
+    /// Namespace declarations (`xmlns` and `xmlns:*`) are written by
+    /// [`namespace_decl`][`Writer::namespace_decl`].
+    pub fn attribute(&mut self, name: &Qname, value: &str) -> Result<(), WriteError> {
+        if !self.in_start_tag {
+            return Err(WriteError::NotInStartTag);
+        }
+        if let Some(prefix) = declared_prefix(name) {
+            return self.namespace_decl(prefix, value);
+        }
+        if self.attributes.contains(name) {
+            return Err(WriteError::DuplicateAttribute(name.clone()));
+        }
+        check_chars(value)?;
+        self.output.write_str(" ")?;
+        write_qname(&mut self.output, name)?;
+        self.output.write_str("=\"")?;
+        self.output
+            .write_str(&escape_attribute(value, Quote::Double)?)?;
+        self.output.write_str("\"")?;
+        self.attributes.push(name.clone());
+        Ok(())
+    }
+
+    /// Writes an attribute in the given namespace.
+    ///
+    /// If the prefix of the name is not bound to the namespace, a namespace
+    /// declaration is written or an error is returned, depending on
+    /// [`set_auto_declare_namespaces`][`Writer::set_auto_declare_namespaces`].
+    ///
+    /// A prefix used by the element name or the attributes already written is
+    /// never rebound, because it would change their namespaces.
+    /// In such case, an in-scope prefix bound to the namespace or a fresh
+    /// prefix is used instead.
+    pub fn attribute_ns(
+        &mut self,
+        name: &Qname,
+        namespace: Option<&str>,
+        value: &str,
+    ) -> Result<(), WriteError> {
+        if !self.in_start_tag {
+            return Err(WriteError::NotInStartTag);
+        }
+        if declared_prefix(name).is_some() {
+            return self.attribute(name, value);
+        }
+        let (prefix, namespace) = match (name.prefix(), namespace) {
+            (None, None) => return self.attribute(name, value),
+            (None, Some(_)) => return Err(WriteError::UnprefixedNamespacedAttribute(name.clone())),
+            (Some(_), None) => return Err(WriteError::UnboundNamespace(name.clone())),
+            (Some(prefix), Some(namespace)) => (prefix, namespace),
+        };
+        if (prefix == "xml") != (namespace == XML_NAMESPACE) || namespace == XMLNS_NAMESPACE {
+            return Err(WriteError::ReservedNamespace(name.clone()));
+        }
+        let expanded = ExpandedName::new(Some(namespace), name.local());
+        if let Some(dup) = self
+            .attributes
+            .iter()
+            .find(|attr| self.namespaces.expand_attribute_name(attr) == Some(expanded))
+        {
+            return Err(WriteError::DuplicateExpandedAttribute(
+                dup.clone(),
+                name.clone(),
+            ));
+        }
+        if self.namespaces.resolve_prefix(prefix) == Some(namespace) {
+            return self.attribute(name, value);
+        }
+        if !self.auto_declare_namespaces {
+            return Err(WriteError::UnboundNamespace(name.clone()));
+        }
+        check_chars(namespace)?;
+        let prefix = if self.is_prefix_free_in_tag(prefix) {
+            self.namespace_decl(Some(prefix), namespace)?;
+            prefix.to_owned()
+        } else if let Some(bound) = self.namespaces.prefix_for(namespace) {
+            bound.to_owned()
+        } else {
+            let fresh = self.fresh_prefix(prefix);
+            self.namespace_decl(Some(&fresh), namespace)?;
+            fresh
+        };
+        let name = Qname::from_prefix_and_local(prefix, name.local().to_owned());
+        self.attribute(&name, value)
+    }
+
+    /// Writes a namespace declaration to the current start tag.
+    ///
+    /// `None` prefix means the default namespace.
+    ///
+    /// A prefix used by the element name or the attributes already written
+    /// (or the default namespace, if the element name is unprefixed) cannot
+    /// be rebound to another namespace, because it would change their
+    /// namespaces.
+    pub fn namespace_decl(
+        &mut self,
+        prefix: Option<&NcnameStr>,
+        namespace: &str,
+    ) -> Result<(), WriteError> {
+        if !self.in_start_tag {
+            return Err(WriteError::NotInStartTag);
+        }
+        check_chars(namespace)?;
+        check_declaration(prefix, namespace)?;
+        if self.namespaces.current_scope().any(|(p, _)| p == prefix) {
+            return Err(WriteError::DuplicateDeclaration(declaration_name(prefix)));
+        }
+        let (used, bound) = match prefix {
+            Some(prefix) => (
+                self.is_prefix_used_in_tag(prefix),
+                self.namespaces.resolve_prefix(prefix),
+            ),
+            None => (
+                self.open.last().is_some_and(|name| name.prefix().is_none()),
+                self.namespaces.default_namespace(),
+            ),
+        };
+        if used && bound.is_some() && bound != Some(namespace) {
+            return Err(WriteError::PrefixInUse(declaration_name(prefix)));
+        }
+        self.write_namespace_decl(prefix, namespace)
+    }
+
+    /// Writes a namespace declaration without checking it.
+    fn write_namespace_decl(
+        &mut self,
+        prefix: Option<&NcnameStr>,
+        namespace: &str,
+    ) -> Result<(), WriteError> {
+        match prefix {
+            Some(prefix) => {
+                self.output.write_str(" xmlns:")?;
+                self.output.write_str(prefix.as_str())?;
+            },
+            None => self.output.write_str(" xmlns")?,
+        }
+        self.output.write_str("=\"")?;
+        self.output
+            .write_str(&escape_attribute(namespace, Quote::Double)?)?;
+        self.output.write_str("\"")?;
+        self.namespaces
+            .declare(prefix.map(ToOwned::to_owned), namespace.to_owned());
+        Ok(())
+    }
+
+    /// Writes an end tag for the innermost open element.
+    ///
+    /// If the element has no content, it is written as an empty element tag.
+    pub fn end_element(&mut self) -> Result<(), WriteError> {
+        let name = self.open.pop().ok_or(WriteError::NoOpenElement)?;
+        self.namespaces.pop_scope();
+        if self.open.is_empty() {
+            self.state = DocumentState::Epilog;
+        }
+        if self.in_start_tag {
+            self.in_start_tag = false;
+            return self.output.write_str("/>");
+        }
+        self.output.write_str("</")?;
+        write_qname(&mut self.output, &name)?;
+        self.output.write_str(">")
+    }
+
+    /// Writes escaped character data.
+    ///
+    /// Outside of the root element, only whitespaces are allowed.
+    pub fn text(&mut self, text: &str) -> Result<(), WriteError> {
+        check_chars(text)?;
+        if self.state != DocumentState::RootElement {
+            if !text.chars().all(is_whitespace_char) {
+                return Err(WriteError::TextOutsideRootElement);
+            }
+            self.enter_prolog();
+        }
+        self.close_start_tag()?;
+        self.output.write_str(&escape_text(text)?)
+    }
+
+    /// Writes a CDATA section.
+    ///
+    /// `]]>` in the text is split into multiple CDATA sections.
+    pub fn cdata(&mut self, text: &str) -> Result<(), WriteError> {
+        check_chars(text)?;
+        if self.state != DocumentState::RootElement {
+            return Err(WriteError::TextOutsideRootElement);
+        }
+        self.close_start_tag()?;
+        self.output.write_str("<![CDATA[")?;
+        let mut rest = text;
+        while let Some(pos) = rest.find("]]>") {
+            self.output.write_str(&rest[..(pos + 2)])?;
+            self.output.write_str("]]><![CDATA[")?;
+            rest = &rest[(pos + 2)..];
+        }
+        self.output.write_str(rest)?;
+        self.output.write_str("]]>")
+    }
+
+    /// Writes a comment.
+    pub fn comment(&mut self, text: &str) -> Result<(), WriteError> {
+        check_chars(text)?;
+        if let Some(pos) = text.find("--") {
+            return Err(WriteError::InvalidComment(pos));
+        }
+        if text.ends_with('-') {
+            return Err(WriteError::InvalidComment(text.len() - 1));
+        }
+        self.enter_prolog();
+        self.close_start_tag()?;
+        self.output.write_str("<!--")?;
+        self.output.write_str(text)?;
+        self.output.write_str("-->")
+    }
+
+    /// Writes a processing instruction.
+    pub fn processing_instruction(
+        &mut self,
+        target: &PiTargetStr,
+        data: Option<&str>,
+    ) -> Result<(), WriteError> {
+        if let Some(data) = data {
+            check_chars(data)?;
+            if let Some(pos) = data.find("?>") {
+                return Err(WriteError::InvalidPiData(pos));
+            }
+        }
+        self.enter_prolog();
+        self.close_start_tag()?;
+        self.output.write_str("<?")?;
+        self.output.write_str(target.as_str())?;
+        if let Some(data) = data {
+            self.output.write_str(" ")?;
+            self.output.write_str(data)?;
+        }
+        self.output.write_str("?>")
+    }
+
+    /// Closes all open elements and returns the output.
+    ///
+    /// Fails if no root element is written.
+    pub fn finish(mut self) -> Result<O, WriteError> {
+        while !self.open.is_empty() {
+            self.end_element()?;
+        }
+        if self.state != DocumentState::Epilog {
+            return Err(WriteError::NoRootElement);
+        }
+        Ok(self.output)
+    }
+
+    /// Closes the start tag if it is open.
+    fn close_start_tag(&mut self) -> Result<(), WriteError> {
+        if self.in_start_tag {
+            self.in_start_tag = false;
+            self.output.write_str(">")?;
+        }
+        Ok(())
+    }
+
+    /// Marks the beginning of the document as written.
+    fn enter_prolog(&mut self) {
+        if self.state == DocumentState::Start {
+            self.state = DocumentState::Prolog;
+        }
+    }
+
+    /// Returns whether the prefix can be declared on the current start tag,
+    /// i.e. it is not declared there and not used by the element name or the
+    /// attributes.
+    fn is_prefix_free_in_tag(&self, prefix: &NcnameStr) -> bool {
+        !self
+            .namespaces
+            .current_scope()
+            .any(|(p, _)| p == Some(prefix))
+            && !self.is_prefix_used_in_tag(prefix)
+    }
+
+    /// Returns whether the prefix is used by the element name or the
+    /// attributes of the current start tag.
+    fn is_prefix_used_in_tag(&self, prefix: &NcnameStr) -> bool {
+        self.open.last().and_then(Qname::prefix) == Some(prefix)
+            || self
+                .attributes
+                .iter()
+                .any(|attr| attr.prefix() == Some(prefix))
+    }
+
+    /// Returns a prefix which is neither in scope nor used in the current
+    /// start tag.
+    fn fresh_prefix(&self, base: &NcnameStr) -> NcnameString {
+        NameGenerator::with_predicate(|p| {
+            self.namespaces.resolve_prefix(p).is_some() || self.is_prefix_used_in_tag(p)
+        })
+        .generate_prefix(base.as_str())
+    }
+}
+
+/// Writes the given QName.
+fn write_qname<O: Output>(output: &mut O, name: &Qname) -> Result<(), WriteError> {
+    if let Some(prefix) = name.prefix() {
+        output.write_str(prefix.as_str())?;
+        output.write_str(":")?;
+    }
+    output.write_str(name.local().as_str())
+}
+
+/// Returns the declared prefix if the attribute name is a namespace
+/// declaration.
+///
+/// `Some(None)` means the default namespace declaration.
+fn declared_prefix(name: &Qname) -> Option<Option<&NcnameStr>> {
+    match name.prefix() {
+        Some(prefix) if prefix == "xmlns" => Some(Some(name.local())),
+        Some(_) => None,
+        None if name.local() == "xmlns" => Some(None),
+        None => None,
+    }
+}
+
+/// Checks the namespace constraints of a namespace declaration.
+fn check_declaration(prefix: Option<&NcnameStr>, namespace: &str) -> Result<(), WriteError> {
+    match prefix {
+        Some(p) if p == "xml" && namespace == XML_NAMESPACE => Ok(()),
+        Some(p) if p == "xml" || p == "xmlns" => {
+            Err(WriteError::ReservedNamespace(declaration_name(prefix)))
+        },
+        _ if namespace == XML_NAMESPACE || namespace == XMLNS_NAMESPACE => {
+            Err(WriteError::ReservedNamespace(declaration_name(prefix)))
+        },
+        Some(p) if namespace.is_empty() => Err(WriteError::PrefixUndeclaring(p.to_owned())),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the string consists of XML `Char`s.
+fn check_chars(s: &str) -> Result<(), WriteError> {
+    match s.char_indices().find(|&(_, c)| !is_xml_char(c)) {
+        Some((pos, c)) => Err(WriteError::InvalidChar(pos, c)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strings::NcnameString;
+    use syntax::{Standalone, VersionNum};
+
+    use super::*;
+
+    fn ncname(s: &str) -> NcnameString {
+        NcnameString::new(s.to_owned()).expect("Should never fail")
+    }
+
+    const DECL_1_0: XmlDecl<'static> = XmlDecl {
+        version: VersionNum::V1_0,
+        encoding: None,
+        standalone: None,
+    };
+
+    #[test]
+    fn write_document() {
+        let mut writer = Writer::from_fmt(String::new());
+        writer
+            .xml_decl(&XmlDecl {
+                standalone: Some(Standalone::Yes),
+                ..DECL_1_0
+            })
+            .expect("Should never fail");
+        writer
+            .processing_instruction(
+                PiTargetStr::new("xml-stylesheet").expect("Should never fail"),
+                Some("href='a.xsl'"),
+            )
+            .expect("Should never fail");
+        writer
+            .start_element(&Qname::from_local(ncname("root")))
+            .expect("Should never fail");
+        writer.comment(" c ").expect("Should never fail");
+        writer
+            .start_element(&Qname::from_local(ncname("empty")))
+            .expect("Should never fail");
+        writer.end_element().expect("Should never fail");
+        writer.text("a\r\n>").expect("Should never fail");
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" standalone=\"yes\"?><?xml-stylesheet href='a.xsl'?>\
+             <root><!-- c --><empty/>a&#xD;\n&gt;</root>"
+        );
+    }
+
+    #[test]
+    fn write_to_io() {
+        let mut writer = Writer::from_io(Vec::new());
+        writer
+            .start_element(&Qname::from_local(ncname("r\u{E9}sum\u{E9}")))
+            .expect("Should never fail");
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(xml, "<r\u{E9}sum\u{E9}/>".as_bytes());
+    }
+
+    #[test]
+    fn refuse_malformed() {
+        let mut writer = Writer::from_fmt(String::new());
+        let root = Qname::from_local(ncname("root"));
+        assert!(matches!(
+            writer.attribute(&root, "v"),
+            Err(WriteError::NotInStartTag)
+        ));
+        writer.start_element(&root).expect("Should never fail");
+        assert!(matches!(
+            writer.attribute(&root, "\u{1}"),
+            Err(WriteError::InvalidChar(0, '\u{1}'))
+        ));
+        assert!(matches!(
+            writer.comment("a--b"),
+            Err(WriteError::InvalidComment(1))
+        ));
+        assert!(matches!(
+            writer.comment("a-"),
+            Err(WriteError::InvalidComment(1))
+        ));
+        assert!(matches!(
+            writer.processing_instruction(
+                PiTargetStr::new("pi").expect("Should never fail"),
+                Some("a?>")
+            ),
+            Err(WriteError::InvalidPiData(1))
+        ));
+        writer.end_element().expect("Should never fail");
+        assert!(matches!(
+            writer.end_element(),
+            Err(WriteError::NoOpenElement)
+        ));
+    }
+
+    #[test]
+    fn declare_namespaces() {
+        let mut writer = Writer::from_fmt(String::new());
+        let root = Qname::from_prefix_and_local(ncname("a"), ncname("root"));
+        let child = Qname::from_local(ncname("child"));
+        let attr = Qname::from_prefix_and_local(ncname("b"), ncname("attr"));
+        assert!(matches!(
+            writer.start_element_ns(&root, Some("urn:a")),
+            Err(WriteError::UnboundNamespace(_))
+        ));
+
+        writer.set_auto_declare_namespaces(true);
+        writer
+            .start_element_ns(&root, Some("urn:a"))
+            .expect("Should never fail");
+        writer
+            .start_element_ns(&child, Some("urn:default"))
+            .expect("Should never fail");
+        writer
+            .attribute_ns(&attr, Some("urn:b"), "v")
+            .expect("Should never fail");
+        writer.end_element().expect("Should never fail");
+        writer
+            .start_element_ns(&root, Some("urn:a"))
+            .expect("Should never fail");
+        writer
+            .start_element_ns(&child, None)
+            .expect("Should never fail");
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(
+            xml,
+            "<a:root xmlns:a=\"urn:a\"><child xmlns=\"urn:default\" xmlns:b=\"urn:b\" b:attr=\"v\"/>\
+             <a:root><child/></a:root></a:root>"
+        );
+    }
+
+    #[test]
+    fn keep_prefixes_in_tag() {
+        let mut writer = Writer::from_fmt(String::new());
+        writer.set_auto_declare_namespaces(true);
+        let a = Qname::from_prefix_and_local(ncname("p"), ncname("a"));
+        let b = Qname::from_prefix_and_local(ncname("p"), ncname("b"));
+        let x = Qname::from_prefix_and_local(ncname("p"), ncname("x"));
+        let y = Qname::from_prefix_and_local(ncname("q"), ncname("y"));
+        let z = Qname::from_prefix_and_local(ncname("q"), ncname("z"));
+        writer
+            .start_element_ns(&a, Some("urn:1"))
+            .expect("Should never fail");
+        writer
+            .start_element_ns(&b, Some("urn:1"))
+            .expect("Should never fail");
+        writer
+            .attribute_ns(&x, Some("urn:2"), "1")
+            .expect("Should never fail");
+        writer
+            .attribute_ns(&y, Some("urn:1"), "2")
+            .expect("Should never fail");
+        writer
+            .attribute_ns(&z, Some("urn:3"), "3")
+            .expect("Should never fail");
+        assert_eq!(
+            writer.namespaces().resolve_prefix(&ncname("p")),
+            Some("urn:1")
+        );
+        assert!(matches!(
+            writer.attribute_ns(&x, Some("urn:2"), "4"),
+            Err(WriteError::DuplicateExpandedAttribute(..))
+        ));
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(
+            xml,
+            "<p:a xmlns:p=\"urn:1\"><p:b xmlns:p1=\"urn:2\" p1:x=\"1\" xmlns:q=\"urn:1\" q:y=\"2\" \
+             xmlns:q1=\"urn:3\" q1:z=\"3\"/></p:a>"
+        );
+
+        // Fresh prefixes never start with `xml`.
+        let mut writer = Writer::from_fmt(String::new());
+        writer.set_auto_declare_namespaces(true);
+        let a = Qname::from_prefix_and_local(ncname("xmlp"), ncname("a"));
+        let x = Qname::from_prefix_and_local(ncname("xmlp"), ncname("x"));
+        writer
+            .start_element_ns(&a, Some("urn:1"))
+            .expect("Should never fail");
+        writer
+            .attribute_ns(&x, Some("urn:2"), "1")
+            .expect("Should never fail");
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(
+            xml,
+            "<xmlp:a xmlns:xmlp=\"urn:1\" xmlns:_xmlp=\"urn:2\" _xmlp:x=\"1\"/>"
+        );
+    }
+
+    #[test]
+    fn refuse_malformed_document() {
+        let root = Qname::from_local(ncname("root"));
+        let mut writer = Writer::from_fmt(String::new());
+        writer.text("\n").expect("Should never fail");
+        assert!(matches!(
+            writer.xml_decl(&DECL_1_0),
+            Err(WriteError::MisplacedXmlDecl)
+        ));
+        assert!(matches!(
+            writer.text("a"),
+            Err(WriteError::TextOutsideRootElement)
+        ));
+        writer.start_element(&root).expect("Should never fail");
+        writer.end_element().expect("Should never fail");
+        assert!(matches!(
+            writer.cdata("a"),
+            Err(WriteError::TextOutsideRootElement)
+        ));
+        assert!(matches!(
+            writer.start_element(&root),
+            Err(WriteError::MultipleRootElements(_))
+        ));
+        writer.comment(" epilog ").expect("Should never fail");
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(xml, "\n<root/><!-- epilog -->");
+
+        let mut writer = Writer::from_fmt(String::new());
+        writer.xml_decl(&DECL_1_0).expect("Should never fail");
+        assert!(matches!(
+            writer.xml_decl(&DECL_1_0),
+            Err(WriteError::MisplacedXmlDecl)
+        ));
+        assert!(matches!(writer.finish(), Err(WriteError::NoRootElement)));
+    }
+
+    #[test]
+    fn refuse_duplicates_in_tag() {
+        let mut writer = Writer::from_fmt(String::new());
+        let attr = Qname::from_local(ncname("attr"));
+        let xmlns_p = Qname::from_prefix_and_local(ncname("xmlns"), ncname("p"));
+        writer
+            .start_element(&Qname::from_local(ncname("root")))
+            .expect("Should never fail");
+        writer.attribute(&attr, "1").expect("Should never fail");
+        assert!(matches!(
+            writer.attribute(&attr, "2"),
+            Err(WriteError::DuplicateAttribute(_))
+        ));
+        writer
+            .attribute(&xmlns_p, "urn:p")
+            .expect("Should never fail");
+        assert_eq!(
+            writer.namespaces().resolve_prefix(&ncname("p")),
+            Some("urn:p")
+        );
+        assert!(matches!(
+            writer.namespace_decl(Some(&ncname("p")), "urn:p"),
+            Err(WriteError::DuplicateDeclaration(_))
+        ));
+        assert!(matches!(
+            writer.namespace_decl(Some(&ncname("q")), ""),
+            Err(WriteError::PrefixUndeclaring(_))
+        ));
+        assert!(matches!(
+            writer.namespace_decl(Some(&ncname("xml")), "urn:q"),
+            Err(WriteError::ReservedNamespace(_))
+        ));
+        assert!(matches!(
+            writer.namespace_decl(Some(&ncname("xmlns")), XMLNS_NAMESPACE),
+            Err(WriteError::ReservedNamespace(_))
+        ));
+        assert!(matches!(
+            writer.namespace_decl(None, XML_NAMESPACE),
+            Err(WriteError::ReservedNamespace(_))
+        ));
+        writer
+            .namespace_decl(Some(&ncname("xml")), XML_NAMESPACE)
+            .expect("Should never fail");
+        writer.namespace_decl(None, "").expect("Should never fail");
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(
+            xml,
+            "<root attr=\"1\" xmlns:p=\"urn:p\" xmlns:xml=\"http://www.w3.org/XML/1998/namespace\" \
+             xmlns=\"\"/>"
+        );
+    }
+
+    #[test]
+    fn refuse_rebinding_prefix_in_tag() {
+        let mut writer = Writer::from_fmt(String::new());
+        writer.set_auto_declare_namespaces(true);
+        let p = ncname("p");
+        let q = ncname("q");
+        let xmlns_p = Qname::from_prefix_and_local(ncname("xmlns"), ncname("p"));
+        let xmlns = Qname::from_local(ncname("xmlns"));
+        writer
+            .start_element_ns(
+                &Qname::from_prefix_and_local(p.clone(), ncname("a")),
+                Some("urn:1"),
+            )
+            .expect("Should never fail");
+        writer
+            .namespace_decl(Some(&q), "urn:q")
+            .expect("Should never fail");
+        writer
+            .start_element_ns(
+                &Qname::from_prefix_and_local(p.clone(), ncname("b")),
+                Some("urn:1"),
+            )
+            .expect("Should never fail");
+        assert!(matches!(
+            writer.namespace_decl(Some(&p), "urn:2"),
+            Err(WriteError::PrefixInUse(_))
+        ));
+        assert!(matches!(
+            writer.attribute(&xmlns_p, "urn:2"),
+            Err(WriteError::PrefixInUse(_))
+        ));
+        writer
+            .attribute(&Qname::from_prefix_and_local(q.clone(), ncname("x")), "1")
+            .expect("Should never fail");
+        assert!(matches!(
+            writer.namespace_decl(Some(&q), "urn:2"),
+            Err(WriteError::PrefixInUse(_))
+        ));
+        writer
+            .namespace_decl(None, "urn:default")
+            .expect("Should never fail");
+        writer
+            .start_element(&Qname::from_local(ncname("c")))
+            .expect("Should never fail");
+        assert!(matches!(
+            writer.namespace_decl(None, "urn:2"),
+            Err(WriteError::PrefixInUse(_))
+        ));
+        assert!(matches!(
+            writer.attribute(&xmlns, ""),
+            Err(WriteError::PrefixInUse(_))
+        ));
+        writer
+            .start_element(&Qname::from_prefix_and_local(ncname("s"), ncname("d")))
+            .expect("Should never fail");
+        writer
+            .namespace_decl(Some(&ncname("s")), "urn:s")
+            .expect("Should never fail");
+        writer.namespace_decl(None, "").expect("Should never fail");
+        let xml = writer.finish().expect("Should never fail").0;
+        assert_eq!(
+            xml,
+            "<p:a xmlns:p=\"urn:1\" xmlns:q=\"urn:q\"><p:b q:x=\"1\" xmlns=\"urn:default\">\
+             <c><s:d xmlns:s=\"urn:s\" xmlns=\"\"/></c></p:b></p:a>"
+        );
+    }
+}
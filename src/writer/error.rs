@@ -0,0 +1,177 @@
+//! Writer error.
+
+#[cfg(feature = "std")]
+use std::error;
+use std::fmt;
+use std::io;
+
+use escape::EscapeError;
+use strings::{NcnameString, Qname};
+
+/// Writer error.
+#[derive(Debug)]
+pub enum WriteError {
+    /// I/O error from the underlying `io::Write`.
+    Io(io::Error),
+    /// Error from the underlying `fmt::Write`.
+    Fmt(fmt::Error),
+    /// A character is not an XML `Char`.
+    ///
+    /// The byte position is relative to the written string.
+    InvalidChar(usize, char),
+    /// Comment contains `--` or ends with `-`.
+    ///
+    /// The byte position is relative to the comment text.
+    InvalidComment(usize),
+    /// Processing instruction data contains `?>`.
+    ///
+    /// The byte position is relative to the data.
+    InvalidPiData(usize),
+    /// Attribute or namespace declaration is written outside of a start tag.
+    NotInStartTag,
+    /// No open element to close.
+    NoOpenElement,
+    /// The element or attribute prefix is not bound to the expected namespace,
+    /// and automatic namespace declaration is disabled.
+    UnboundNamespace(Qname),
+    /// An unprefixed attribute cannot be in a namespace.
+    UnprefixedNamespacedAttribute(Qname),
+    /// XML declaration is written after other contents.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-prolog>.
+    MisplacedXmlDecl,
+    /// Another root element is written after the root element.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-document>.
+    MultipleRootElements(Qname),
+    /// Character data (other than whitespaces) or a CDATA section is written
+    /// outside of the root element.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-document>.
+    TextOutsideRootElement,
+    /// The document is finished without a root element.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#NT-document>.
+    NoRootElement,
+    /// The same attribute name is written twice in a tag.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#uniqattspec>.
+    DuplicateAttribute(Qname),
+    /// Two attributes in a tag have the same expanded name.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#nsc-AttrsUnique>.
+    DuplicateExpandedAttribute(Qname, Qname),
+    /// The same prefix (or the default namespace) is declared twice in a tag.
+    ///
+    /// See <https://www.w3.org/TR/2008/REC-xml-20081126/#uniqattspec>.
+    DuplicateDeclaration(Qname),
+    /// The declaration rebinds a prefix (or the default namespace) used by
+    /// the element name or the attributes already written in the tag.
+    PrefixInUse(Qname),
+    /// `xml` or `xmlns` prefix or namespace name is misused by the attribute
+    /// or the declaration (as `xmlns:prefix` or `xmlns`).
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#xmlReserved>.
+    ReservedNamespace(Qname),
+    /// A prefixed namespace declaration has an empty value.
+    ///
+    /// See <https://www.w3.org/TR/REC-xml-names/#nsc-NoPrefixUndecl>.
+    PrefixUndeclaring(NcnameString),
+}
+
+#[cfg(feature = "std")]
+impl error::Error for WriteError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            WriteError::Io(e) => Some(e),
+            WriteError::Fmt(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WriteError::Io(e) => write!(f, "I/O error: {}", e),
+            WriteError::Fmt(e) => write!(f, "Formatter error: {}", e),
+            WriteError::InvalidChar(pos, c) => {
+                write!(f, "Invalid character at byte position {}: {:?}", pos, c)
+            },
+            WriteError::InvalidComment(pos) => write!(
+                f,
+                "Comment should not contain `--` or end with `-` (byte position {})",
+                pos
+            ),
+            WriteError::InvalidPiData(pos) => write!(
+                f,
+                "Processing instruction data should not contain `?>` (byte position {})",
+                pos
+            ),
+            WriteError::NotInStartTag => {
+                f.write_str("Attributes should be written just after the start tag")
+            },
+            WriteError::NoOpenElement => f.write_str("No open element to close"),
+            WriteError::UnboundNamespace(name) => write!(
+                f,
+                "Prefix of `{}` is not bound to the expected namespace",
+                name
+            ),
+            WriteError::UnprefixedNamespacedAttribute(name) => write!(
+                f,
+                "Unprefixed attribute `{}` cannot be in a namespace",
+                name
+            ),
+            WriteError::MisplacedXmlDecl => {
+                f.write_str("XML declaration should be at the beginning of the document")
+            },
+            WriteError::MultipleRootElements(name) => {
+                write!(f, "Element `{}` is written after the root element", name)
+            },
+            WriteError::TextOutsideRootElement => {
+                f.write_str("Character data should be inside of the root element")
+            },
+            WriteError::NoRootElement => f.write_str("Document has no root element"),
+            WriteError::DuplicateAttribute(name) => write!(f, "Duplicate attribute `{}`", name),
+            WriteError::DuplicateExpandedAttribute(first, second) => write!(
+                f,
+                "Attributes `{}` and `{}` have the same expanded name",
+                first, second
+            ),
+            WriteError::DuplicateDeclaration(name) => {
+                write!(f, "Duplicate namespace declaration `{}`", name)
+            },
+            WriteError::PrefixInUse(name) => write!(
+                f,
+                "Namespace declaration `{}` rebinds a prefix used in the tag",
+                name
+            ),
+            WriteError::ReservedNamespace(name) => {
+                write!(f, "Reserved prefix or namespace is misused by `{}`", name)
+            },
+            WriteError::PrefixUndeclaring(prefix) => write!(
+                f,
+                "Prefix `{}` should not be bound to empty namespace name",
+                prefix
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for WriteError {
+    fn from(e: io::Error) -> Self {
+        WriteError::Io(e)
+    }
+}
+
+impl From<EscapeError> for WriteError {
+    fn from(e: EscapeError) -> Self {
+        WriteError::InvalidChar(e.position(), e.invalid_char())
+    }
+}
+
+impl From<fmt::Error> for WriteError {
+    fn from(e: fmt::Error) -> Self {
+        WriteError::Fmt(e)
+    }
+}
@@ -0,0 +1,9 @@
+//! Well-formed XML writer.
+
+pub use self::emitter::Writer;
+pub use self::error::WriteError;
+pub use self::output::{FmtOutput, IoOutput, Output};
+
+mod emitter;
+mod error;
+mod output;
@@ -0,0 +1,32 @@
+//! Output destinations.
+
+use std::fmt;
+use std::io;
+
+use writer::WriteError;
+
+/// Output destination of a writer.
+pub trait Output {
+    /// Writes the given string.
+    fn write_str(&mut self, s: &str) -> Result<(), WriteError>;
+}
+
+/// Output to [`io::Write`], encoded in UTF-8.
+#[derive(Debug, Clone)]
+pub struct IoOutput<W>(pub W);
+
+impl<W: io::Write> Output for IoOutput<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), WriteError> {
+        self.0.write_all(s.as_bytes()).map_err(Into::into)
+    }
+}
+
+/// Output to [`fmt::Write`].
+#[derive(Debug, Clone)]
+pub struct FmtOutput<W>(pub W);
+
+impl<W: fmt::Write> Output for FmtOutput<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), WriteError> {
+        self.0.write_str(s).map_err(Into::into)
+    }
+}